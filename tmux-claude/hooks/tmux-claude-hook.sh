@@ -2,7 +2,7 @@
 # tmux-claude-hook.sh - Hook script for forwarding Claude events to tmux-claude daemon
 #
 # Usage: tmux-claude-hook.sh <event-type>
-# Events: Stop, PreToolUse, PostToolUse, UserPromptSubmit, Notification
+# Events: Stop, PreToolUse, PostToolUse, PermissionRequest, UserPromptSubmit, Notification
 #
 # This script reads JSON from stdin (Claude hook data) and forwards it to the daemon.
 
@@ -34,8 +34,8 @@ case "$EVENT_TYPE" in
             '{"Stop": {"session_id": $session_id, "cwd": $cwd}}')
         ;;
     PreToolUse)
-        TOOL_NAME=$(echo "$HOOK_DATA" | jq -r '.tool.name // .toolName // "unknown"' 2>/dev/null || echo "unknown")
-        TOOL_INPUT=$(echo "$HOOK_DATA" | jq -c '.tool.input // .toolInput // null' 2>/dev/null || echo "null")
+        TOOL_NAME=$(echo "$HOOK_DATA" | jq -r '.tool_name // .tool.name // .toolName // "unknown"' 2>/dev/null || echo "unknown")
+        TOOL_INPUT=$(echo "$HOOK_DATA" | jq -c '.tool_input // .tool.input // .toolInput // null' 2>/dev/null || echo "null")
         EVENT_JSON=$(jq -n \
             --arg session_id "$SESSION_ID" \
             --arg cwd "$CWD" \
@@ -43,8 +43,19 @@ case "$EVENT_TYPE" in
             --argjson tool_input "$TOOL_INPUT" \
             '{"PreToolUse": {"session_id": $session_id, "cwd": $cwd, "tool_name": $tool_name, "tool_input": $tool_input}}')
         ;;
+    PermissionRequest)
+        # Full tool input (Edit/Write contents included) so the TUI can preview edits
+        TOOL_NAME=$(echo "$HOOK_DATA" | jq -r '.tool_name // .tool.name // .toolName // "unknown"' 2>/dev/null || echo "unknown")
+        TOOL_INPUT=$(echo "$HOOK_DATA" | jq -c '.tool_input // .tool.input // .toolInput // null' 2>/dev/null || echo "null")
+        EVENT_JSON=$(jq -n \
+            --arg session_id "$SESSION_ID" \
+            --arg cwd "$CWD" \
+            --arg tool_name "$TOOL_NAME" \
+            --argjson tool_input "$TOOL_INPUT" \
+            '{"PermissionRequest": {"session_id": $session_id, "cwd": $cwd, "tool_name": $tool_name, "tool_input": $tool_input}}')
+        ;;
     PostToolUse)
-        TOOL_NAME=$(echo "$HOOK_DATA" | jq -r '.tool.name // .toolName // "unknown"' 2>/dev/null || echo "unknown")
+        TOOL_NAME=$(echo "$HOOK_DATA" | jq -r '.tool_name // .tool.name // .toolName // "unknown"' 2>/dev/null || echo "unknown")
         EVENT_JSON=$(jq -n \
             --arg session_id "$SESSION_ID" \
             --arg cwd "$CWD" \
@@ -71,6 +82,19 @@ case "$EVENT_TYPE" in
         ;;
 esac
 
+# Attach the transcript path, so the daemon doesn't have to derive it from the cwd
+TRANSCRIPT_PATH=$(echo "$HOOK_DATA" | jq -r '.transcript_path // empty' 2>/dev/null || true)
+if [[ -n "$TRANSCRIPT_PATH" ]]; then
+    EVENT_JSON=$(echo "$EVENT_JSON" | jq -c --arg path "$TRANSCRIPT_PATH" 'map_values(. + {"transcript_path": $path})')
+fi
+
+# Attach the tmux pane this hook ran in, so the daemon can address the right server
+if [[ -n "$TMUX" && -n "$TMUX_PANE" ]]; then
+    TMUX_JSON=$(jq -n --arg socket "${TMUX%%,*}" --arg pane_id "$TMUX_PANE" \
+        '{"socket": $socket, "pane_id": $pane_id}')
+    EVENT_JSON=$(echo "$EVENT_JSON" | jq -c --argjson tmux "$TMUX_JSON" 'map_values(. + {"tmux": $tmux})')
+fi
+
 # Wrap in DaemonCommand
 COMMAND_JSON=$(jq -n --argjson event "$EVENT_JSON" '{"HookEvent": $event}')
 
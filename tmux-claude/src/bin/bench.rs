@@ -5,15 +5,22 @@
 //! Measures the main components of each refresh cycle:
 //! - sysinfo: System process information gathering (CPU/RAM)
 //! - tmux: Session/window/pane discovery via tmux commands
-//! - jsonl: Reading Claude status from jsonl files (replaces capture-pane)
+//! - jsonl: Reading what was appended to Claude transcripts (`JsonlTailer`, as the
+//!   daemon does; the first cycle reads every transcript in full)
 //!
 //! Mock mode (--mock) provides reproducible benchmarks without depending on
 //! current tmux state. Use --sessions to control the number of mock sessions.
 
+#[allow(dead_code)]
+#[path = "../common/jsonl_tail.rs"]
+mod jsonl_tail;
+
 use clap::Parser;
+use jsonl_tail::JsonlTailer;
+use std::collections::HashMap;
 use std::fs;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::time::Instant;
 use sysinfo::System;
@@ -51,12 +58,19 @@ fn main() {
     }
 
     let mut all_metrics: Vec<Metrics> = Vec::with_capacity(args.iterations);
+    let mock_root = std::env::temp_dir().join(format!("tmux-claude-bench-{}", std::process::id()));
+    let mut tailer = if args.mock {
+        create_mock_transcripts(&mock_root, args.sessions);
+        JsonlTailer::new(mock_root.clone(), HashMap::new(), true)
+    } else {
+        JsonlTailer::new(claude_projects_dir(), HashMap::new(), true)
+    };
 
     for i in 1..=args.iterations {
         let metrics = if args.mock {
-            run_mock_refresh_cycle(args.sessions)
+            run_mock_refresh_cycle(args.sessions, &mock_root, &mut tailer)
         } else {
-            run_refresh_cycle()
+            run_refresh_cycle(&mut tailer)
         };
 
         // Print progress every 10 iterations
@@ -69,6 +83,9 @@ fn main() {
 
         all_metrics.push(metrics);
     }
+    if args.mock {
+        let _ = fs::remove_dir_all(&mock_root);
+    }
 
     // Calculate statistics
     let stats = Statistics::from_metrics(&all_metrics);
@@ -176,37 +193,25 @@ struct Metrics {
     pane_count: usize,
 }
 
-/// Convert cwd to Claude projects path
-fn cwd_to_claude_projects_path(cwd: &str) -> PathBuf {
+/// Directory holding Claude's transcripts (`~/.claude/projects`)
+fn claude_projects_dir() -> PathBuf {
     let home = dirs::home_dir().unwrap_or_default();
-    let encoded = cwd.replace('/', "-");
-    home.join(".claude").join("projects").join(encoded)
+    home.join(".claude").join("projects")
 }
 
-/// Find the most recently modified jsonl file
-fn find_latest_jsonl(projects_path: &PathBuf) -> Option<PathBuf> {
-    let entries = fs::read_dir(projects_path).ok()?;
-    entries
-        .filter_map(|e| e.ok())
-        .filter(|e| {
-            e.path()
-                .extension()
-                .map(|ext| ext == "jsonl")
-                .unwrap_or(false)
-        })
-        .max_by_key(|e| e.metadata().and_then(|m| m.modified()).ok())
-        .map(|e| e.path())
+/// Transcript of mock session `i` under `root`
+fn mock_transcript_path(root: &Path, i: usize) -> PathBuf {
+    root.join("-mock-project")
+        .join(format!("session-{}.jsonl", i))
 }
 
-/// Read the last N lines of a file
-fn read_last_lines(path: &PathBuf, n: usize) -> Vec<String> {
-    let file = match fs::File::open(path) {
-        Ok(f) => f,
-        Err(_) => return Vec::new(),
-    };
-    let reader = BufReader::new(file);
-    let lines: Vec<String> = reader.lines().filter_map(|l| l.ok()).collect();
-    lines.into_iter().rev().take(n).collect()
+/// One mock transcript per session, with a conversation already in it
+fn create_mock_transcripts(root: &Path, session_count: usize) {
+    fs::create_dir_all(root.join("-mock-project")).expect("cannot create mock transcripts");
+    for i in 0..session_count {
+        let content = mock_jsonl_lines().join("\n") + "\n";
+        fs::write(mock_transcript_path(root, i), content).expect("cannot write mock transcript");
+    }
 }
 
 /// Mock jsonl content representing typical Claude session data
@@ -221,7 +226,18 @@ fn mock_jsonl_lines() -> Vec<String> {
 }
 
 /// Run a mock refresh cycle for reproducible benchmarking
-fn run_mock_refresh_cycle(session_count: usize) -> Metrics {
+fn run_mock_refresh_cycle(session_count: usize, root: &Path, tailer: &mut JsonlTailer) -> Metrics {
+    // Every session writes a turn between two refreshes (not timed; file
+    // notifications arrive while sysinfo runs)
+    for i in 0..session_count {
+        if let Ok(mut file) = fs::OpenOptions::new()
+            .append(true)
+            .open(mock_transcript_path(root, i))
+        {
+            let _ = writeln!(file, "{}", mock_jsonl_lines().join("\n"));
+        }
+    }
+
     let total_start = Instant::now();
 
     // 1. sysinfo - still do real system info gathering
@@ -242,15 +258,9 @@ fn run_mock_refresh_cycle(session_count: usize) -> Metrics {
     }
     let tmux_ms = tmux_start.elapsed().as_secs_f64() * 1000.0;
 
-    // 3. jsonl - measure actual parsing with mock content
+    // 3. jsonl - read the appended entries
     let jsonl_start = Instant::now();
-    let mock_lines = mock_jsonl_lines();
-    for _ in 0..pane_count {
-        // Simulate the parsing that happens in the real code
-        for line in &mock_lines {
-            let _parsed: Result<serde_json::Value, _> = serde_json::from_str(line);
-        }
-    }
+    let _round = tailer.read_changes(usize::MAX);
     let jsonl_ms = jsonl_start.elapsed().as_secs_f64() * 1000.0;
 
     let total_ms = total_start.elapsed().as_secs_f64() * 1000.0;
@@ -266,7 +276,7 @@ fn run_mock_refresh_cycle(session_count: usize) -> Metrics {
     }
 }
 
-fn run_refresh_cycle() -> Metrics {
+fn run_refresh_cycle(tailer: &mut JsonlTailer) -> Metrics {
     let total_start = Instant::now();
 
     // 1. sysinfo - gather system process information
@@ -287,7 +297,6 @@ fn run_refresh_cycle() -> Metrics {
 
     let mut window_count = 0;
     let mut pane_count = 0;
-    let mut pane_cwds: Vec<String> = Vec::new();
 
     for session in sessions_str.lines() {
         let windows_output = Command::new("tmux")
@@ -309,25 +318,16 @@ fn run_refresh_cycle() -> Metrics {
                 .output()
                 .expect("tmux list-panes failed");
 
-            for line in String::from_utf8_lossy(&panes_output.stdout).lines() {
-                pane_count += 1;
-                if let Some(cwd) = line.split('\t').nth(1) {
-                    pane_cwds.push(cwd.to_string());
-                }
-            }
+            pane_count += String::from_utf8_lossy(&panes_output.stdout)
+                .lines()
+                .count();
         }
     }
     let tmux_ms = tmux_start.elapsed().as_secs_f64() * 1000.0;
 
-    // 3. jsonl reading - read status from Claude project files
+    // 3. jsonl reading - what Claude appended to its transcripts since the last cycle
     let jsonl_start = Instant::now();
-    for cwd in &pane_cwds {
-        let projects_path = cwd_to_claude_projects_path(cwd);
-        if let Some(jsonl_path) = find_latest_jsonl(&projects_path) {
-            let _lines = read_last_lines(&jsonl_path, 10);
-            // In real code, we'd parse these lines
-        }
-    }
+    let _round = tailer.read_changes(usize::MAX);
     let jsonl_ms = jsonl_start.elapsed().as_secs_f64() * 1000.0;
 
     let total_ms = total_start.elapsed().as_secs_f64() * 1000.0;
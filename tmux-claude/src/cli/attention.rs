@@ -0,0 +1,167 @@
+//! `jump-attention` and `cycle-next/prev --attention`: triage sessions waiting on the user.
+
+use crate::common::tmux::{focus_pane, get_current_pane_id, TmuxServer};
+use crate::ipc::messages::SessionState;
+use crate::tui::client::DaemonClient;
+use anyhow::{bail, Result};
+use chrono::DateTime;
+
+/// Local sessions that need attention and can be focused, oldest request first
+pub fn attention_queue(sessions: &[SessionState]) -> Vec<&SessionState> {
+    let mut queue: Vec<&SessionState> = sessions
+        .iter()
+        .filter(|s| {
+            s.needs_attention
+                && s.host.is_none()
+                && (!s.tmux_pane_id.is_empty() || !s.tmux_session.is_empty())
+        })
+        .collect();
+    // Compare parsed times, not strings (offsets and precision vary); sessions without
+    // a usable timestamp (state from older daemons) go last
+    queue.sort_by_key(|s| {
+        let since = s
+            .attention_since
+            .as_deref()
+            .or(s.last_activity.as_deref())
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok());
+        (since.is_none(), since, s.session_id.clone())
+    });
+    queue
+}
+
+/// Next (or previous) session after the one in `current_pane`. When the current pane
+/// isn't in the queue, forward starts at the oldest request and backward at the newest.
+pub fn next_target<'a>(
+    queue: &[&'a SessionState],
+    current_pane: Option<&str>,
+    forward: bool,
+) -> Option<&'a SessionState> {
+    if queue.is_empty() {
+        return None;
+    }
+    let current = current_pane.and_then(|pane| queue.iter().position(|s| s.tmux_pane_id == pane));
+    let idx = match (current, forward) {
+        (Some(idx), true) => (idx + 1) % queue.len(),
+        (Some(idx), false) => (idx + queue.len() - 1) % queue.len(),
+        (None, true) => 0,
+        (None, false) => queue.len() - 1,
+    };
+    Some(queue[idx])
+}
+
+/// All sessions the daemon knows about
+fn load_sessions() -> Result<Vec<SessionState>> {
+    let mut client = DaemonClient::new();
+    if !client.connect() {
+        bail!("Daemon is not running");
+    }
+    let Some(sessions) = client.get_state() else {
+        bail!("Failed to get state from daemon");
+    };
+    Ok(sessions)
+}
+
+/// Focus a session's Claude pane, or tell the user nothing needs attention
+fn focus_target(target: Option<&SessionState>) -> Result<()> {
+    let Some(target) = target else {
+        // Usually run from a key binding, so tell the user in tmux
+        let _ = TmuxServer::Current
+            .command()
+            .args(["display-message", "No sessions need attention"])
+            .output();
+        return Ok(());
+    };
+
+    if !focus_pane(&target.tmux_server, &target.tmux_target()) {
+        bail!(
+            "Failed to switch to '{}' on tmux server '{}'",
+            target.tmux_session,
+            target.tmux_server.label()
+        );
+    }
+    Ok(())
+}
+
+/// Focus the Claude pane of the next session needing attention
+pub fn run_attention_cycle(forward: bool) -> Result<()> {
+    let sessions = load_sessions()?;
+    let queue = attention_queue(&sessions);
+    let current_pane = get_current_pane_id();
+    focus_target(next_target(&queue, current_pane.as_deref(), forward))
+}
+
+/// Focus the Claude pane of the oldest session needing attention
+pub fn run_jump_attention() -> Result<()> {
+    let sessions = load_sessions()?;
+    focus_target(attention_queue(&sessions).first().copied())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(id: &str, pane: &str, since: Option<&str>) -> SessionState {
+        let mut session = SessionState::new(
+            id.to_string(),
+            "work".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            "/tmp".to_string(),
+        );
+        session.tmux_pane_id = pane.to_string();
+        session.needs_attention = true;
+        session.attention_since = since.map(str::to_string);
+        session
+    }
+
+    #[test]
+    fn test_queue_is_oldest_first_and_skips_remote() {
+        let mut remote = session("remote", "%9", Some("2026-01-01T00:00:00Z"));
+        remote.host = Some("devbox".to_string());
+        let mut idle = session("idle", "%8", Some("2026-01-01T00:00:00Z"));
+        idle.needs_attention = false;
+        let sessions = vec![
+            session("new", "%1", Some("2026-01-01T10:00:00Z")),
+            session("untimed", "%2", None),
+            session("old", "%3", Some("2026-01-01T09:00:00Z")),
+            remote,
+            idle,
+        ];
+        let ids: Vec<&str> = attention_queue(&sessions)
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["old", "new", "untimed"]);
+    }
+
+    #[test]
+    fn test_queue_compares_times_not_strings() {
+        let sessions = vec![
+            // 08:30 UTC, but sorts after "2026-01-01T09:00:00Z" as a string
+            session("offset", "%1", Some("2026-01-01T10:30:00+02:00")),
+            session("utc", "%2", Some("2026-01-01T09:00:00Z")),
+            session("garbage", "%3", Some("yesterday")),
+        ];
+        let ids: Vec<&str> = attention_queue(&sessions)
+            .iter()
+            .map(|s| s.session_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["offset", "utc", "garbage"]);
+    }
+
+    #[test]
+    fn test_next_target_wraps_around_current_pane() {
+        let sessions = vec![
+            session("a", "%1", Some("2026-01-01T09:00:00Z")),
+            session("b", "%2", Some("2026-01-01T10:00:00Z")),
+        ];
+        let queue = attention_queue(&sessions);
+        let id = |s: Option<&SessionState>| s.map(|s| s.session_id.clone());
+        assert_eq!(id(next_target(&queue, None, true)), Some("a".into()));
+        assert_eq!(id(next_target(&queue, None, false)), Some("b".into()));
+        assert_eq!(id(next_target(&queue, Some("%1"), true)), Some("b".into()));
+        assert_eq!(id(next_target(&queue, Some("%2"), true)), Some("a".into()));
+        assert_eq!(id(next_target(&queue, Some("%1"), false)), Some("b".into()));
+        assert!(next_target(&[], Some("%1"), true).is_none());
+    }
+}
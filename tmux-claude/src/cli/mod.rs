@@ -0,0 +1,8 @@
+//! Non-interactive subcommands for scripts, keybindings and editor plugins.
+
+pub mod attention;
+pub mod new;
+pub mod sessions;
+pub mod stats;
+pub mod status_line;
+pub mod wait;
@@ -0,0 +1,27 @@
+//! `new`: start Claude in a fresh tmux session for a directory or sesh project.
+
+use crate::common::launch::{launch_session, LaunchRequest};
+use crate::tui::client::DaemonClient;
+use anyhow::Result;
+
+/// Start the session and register it with the daemon (if one is running)
+pub fn run_new(request: LaunchRequest) -> Result<()> {
+    let launched = launch_session(&request)?;
+
+    let mut client = DaemonClient::new();
+    let registered =
+        client.connect() && client.register_session(&launched, request.prompt.is_some());
+    if !registered {
+        eprintln!("Daemon is not running; the session shows up once its first hook fires");
+    }
+
+    println!(
+        "Started Claude in tmux session '{}' ({})",
+        launched.session,
+        launched.cwd.display()
+    );
+    if let Some(worktree) = launched.worktree {
+        println!("Worktree on branch '{}'", worktree.branch);
+    }
+    Ok(())
+}
@@ -0,0 +1,293 @@
+//! `list`, `approve`, `deny`, `focus` and `send`: the TUI's session actions as subcommands.
+
+use crate::common::persistence::load_skipped_sessions;
+use crate::common::tmux::{discover_tmux_servers, get_tmux_sessions};
+use crate::common::types::{format_memory, ClaudeStatus, SessionInfo};
+use crate::tui::app::{
+    approve_session, assign_permission_keys, collect_sessions, deny_session, find_session_by_name,
+    focus_session, send_to_session, sort_sessions,
+};
+use crate::tui::client::DaemonClient;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+
+/// One row of `list --json`
+#[derive(Debug, Serialize)]
+pub struct SessionSummary {
+    /// tmux session name ("host:name" for remote sessions)
+    pub name: String,
+    /// Remote host, if the session comes from another daemon
+    pub host: Option<String>,
+    /// tmux server label
+    pub server: String,
+    /// Daemon session id, when the daemon knows the session
+    pub session_id: Option<String>,
+    pub cwd: Option<String>,
+    /// Claude status ("waiting for input", "needs permission", ...); None = no Claude
+    pub status: Option<String>,
+    /// Command or file awaiting approval
+    pub detail: Option<String>,
+    pub needs_attention: bool,
+    /// Letter the TUI shows for approving this session
+    pub permission_key: Option<char>,
+    pub cpu_percent: f32,
+    pub memory_kb: u64,
+    /// Last Claude activity (RFC 3339)
+    pub last_activity: Option<String>,
+}
+
+impl From<&SessionInfo> for SessionSummary {
+    fn from(info: &SessionInfo) -> Self {
+        let detail = match info.claude_status {
+            Some(ClaudeStatus::NeedsPermission(ref command, _)) => Some(command.clone()),
+            Some(ClaudeStatus::EditApproval(ref filename)) => Some(filename.clone()),
+            _ => None,
+        };
+        Self {
+            name: info.name.clone(),
+            host: info.host.clone(),
+            server: info.server.label(),
+            session_id: info.daemon_session_id.clone(),
+            cwd: info.cwd.clone(),
+            status: info.claude_status.as_ref().map(|s| s.to_string()),
+            detail,
+            needs_attention: needs_attention(info),
+            permission_key: info.permission_key,
+            cpu_percent: info.total_cpu,
+            memory_kb: info.total_mem_kb,
+            last_activity: info.last_activity.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+/// Whether Claude in this session is blocked on the user
+fn needs_attention(info: &SessionInfo) -> bool {
+    info.claude_status
+        .as_ref()
+        .map(|s| s.needs_attention())
+        .unwrap_or(false)
+}
+
+/// Whether the session shows a permission or edit prompt
+fn has_prompt(info: &SessionInfo) -> bool {
+    matches!(
+        info.claude_status,
+        Some(ClaudeStatus::NeedsPermission(_, _)) | Some(ClaudeStatus::EditApproval(_))
+    )
+}
+
+/// The session list the TUI shows, plus the daemon connection (for remote sessions)
+struct Sessions {
+    infos: Vec<SessionInfo>,
+    daemon: Option<DaemonClient>,
+}
+
+impl Sessions {
+    /// Load the merged tmux + daemon session view without the TUI: one daemon
+    /// snapshot and a process scan (sampled twice when CPU usage is shown)
+    fn load(filter: Option<String>, sample_cpu: bool) -> Self {
+        let mut client = DaemonClient::new();
+        let mut daemon = client.connect().then_some(client);
+        let snapshot = daemon
+            .as_mut()
+            .and_then(|client| client.get_snapshot())
+            .unwrap_or_default();
+        let tmux_sessions = snapshot
+            .tmux_sessions
+            .unwrap_or_else(|| get_tmux_sessions(&discover_tmux_servers()));
+
+        let mut sys = System::new();
+        let refresh = |sys: &mut System| {
+            sys.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::everything(),
+            );
+        };
+        refresh(&mut sys);
+        if sample_cpu {
+            std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+            refresh(&mut sys);
+        }
+
+        let mut infos = collect_sessions(
+            &sys,
+            tmux_sessions,
+            snapshot.sessions,
+            snapshot.alerts,
+            &snapshot.git,
+            &filter,
+            false,
+        );
+        sort_sessions(&mut infos, &load_skipped_sessions());
+        assign_permission_keys(&mut infos, &mut HashMap::new(), &mut HashSet::new());
+        Self { infos, daemon }
+    }
+
+    /// Resolve a session argument, or fail with a readable error
+    fn get(&self, session: &str) -> Result<&SessionInfo> {
+        match find_session_by_name(&self.infos, session) {
+            Some(idx) => Ok(&self.infos[idx]),
+            None => bail!("No session named '{}'", session),
+        }
+    }
+}
+
+/// Print sessions as a table or JSON
+pub fn run_list(filter: Option<String>, json: bool, attention: bool) -> Result<()> {
+    let loaded = Sessions::load(filter, true);
+    let sessions: Vec<&SessionInfo> = loaded
+        .infos
+        .iter()
+        .filter(|s| !attention || needs_attention(s))
+        .collect();
+
+    if json {
+        let summaries: Vec<SessionSummary> =
+            sessions.iter().map(|s| SessionSummary::from(*s)).collect();
+        println!("{}", serde_json::to_string_pretty(&summaries)?);
+        return Ok(());
+    }
+
+    for info in sessions {
+        let key = info
+            .permission_key
+            .map(|k| format!("[{}]", k))
+            .unwrap_or_default();
+        let status = match info.claude_status {
+            Some(ClaudeStatus::NeedsPermission(ref command, _)) => {
+                format!("needs permission: {}", command)
+            }
+            Some(ref status) => status.to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<3} {:<24} {:>6.1}% {:>7}  {}",
+            key,
+            info.name,
+            info.total_cpu,
+            format_memory(info.total_mem_kb),
+            status
+        );
+    }
+    Ok(())
+}
+
+/// Approve a session's pending permission or edit prompt
+pub fn run_approve(filter: Option<String>, session: &str, always: bool) -> Result<()> {
+    let mut sessions = Sessions::load(filter, false);
+    let info = sessions.get(session)?.clone();
+    if !has_prompt(&info) {
+        bail!("'{}' has no pending permission prompt", session);
+    }
+    if !approve_session(&info, always, sessions.daemon.as_mut()) {
+        bail!("Approval for '{}' failed", info.name);
+    }
+    Ok(())
+}
+
+/// Deny a session's pending permission or edit prompt
+pub fn run_deny(filter: Option<String>, session: &str) -> Result<()> {
+    let mut sessions = Sessions::load(filter, false);
+    let info = sessions.get(session)?.clone();
+    if !has_prompt(&info) {
+        bail!("'{}' has no pending permission prompt", session);
+    }
+    if !deny_session(&info, sessions.daemon.as_mut()) {
+        bail!("Deny for '{}' failed", info.name);
+    }
+    Ok(())
+}
+
+/// Switch the tmux client to a session
+pub fn run_focus(filter: Option<String>, session: &str) -> Result<()> {
+    focus_session(Sessions::load(filter, false).get(session)?)
+}
+
+/// Type a message into a session's Claude pane and submit it
+pub fn run_send(filter: Option<String>, session: &str, message: &str) -> Result<()> {
+    send_to_session(Sessions::load(filter, false).get(session)?, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::tmux::TmuxServer;
+
+    #[test]
+    fn test_summary_of_permission_prompt() {
+        let info = SessionInfo {
+            name: "api".to_string(),
+            claude_status: Some(ClaudeStatus::NeedsPermission(
+                "cargo test".to_string(),
+                None,
+            )),
+            permission_key: Some('y'),
+            daemon_session_id: Some("abc".to_string()),
+            ..Default::default()
+        };
+        let summary = SessionSummary::from(&info);
+        assert_eq!(summary.name, "api");
+        assert_eq!(summary.host, None);
+        assert_eq!(summary.server, "current");
+        assert_eq!(summary.session_id.as_deref(), Some("abc"));
+        assert_eq!(summary.detail.as_deref(), Some("cargo test"));
+        assert!(summary.needs_attention);
+        assert_eq!(summary.permission_key, Some('y'));
+        assert!(has_prompt(&info));
+    }
+
+    #[test]
+    fn test_summary_of_edit_approval_and_idle_sessions() {
+        let edit = SessionInfo {
+            name: "web".to_string(),
+            claude_status: Some(ClaudeStatus::EditApproval("main.rs".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            SessionSummary::from(&edit).detail.as_deref(),
+            Some("main.rs")
+        );
+
+        let waiting = SessionInfo {
+            name: "web".to_string(),
+            claude_status: Some(ClaudeStatus::Waiting),
+            ..Default::default()
+        };
+        let summary = SessionSummary::from(&waiting);
+        assert_eq!(summary.detail, None);
+        assert!(!summary.needs_attention);
+        assert!(!has_prompt(&waiting));
+
+        let shell = SessionInfo {
+            name: "shell".to_string(),
+            ..Default::default()
+        };
+        let summary = SessionSummary::from(&shell);
+        assert_eq!(summary.status, None);
+        assert!(!summary.needs_attention);
+    }
+
+    #[test]
+    fn test_summary_of_remote_and_secondary_server_sessions() {
+        let remote = SessionInfo {
+            name: "devbox:api".to_string(),
+            host: Some("devbox".to_string()),
+            daemon_session_id: Some("r1".to_string()),
+            ..Default::default()
+        };
+        let summary = SessionSummary::from(&remote);
+        assert_eq!(summary.name, "devbox:api");
+        assert_eq!(summary.host.as_deref(), Some("devbox"));
+        assert_eq!(summary.session_id.as_deref(), Some("r1"));
+
+        let secondary = SessionInfo {
+            name: "api".to_string(),
+            server: TmuxServer::Named("work".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(SessionSummary::from(&secondary).server, "work");
+    }
+}
@@ -0,0 +1,258 @@
+//! `stats`: token usage and cost from all Claude transcripts, grouped by project,
+//! session, model or day.
+
+use crate::cli::wait::parse_duration;
+use crate::common::config::load_config;
+use crate::common::jsonl::claude_projects_dir;
+use crate::common::jsonl_tail::JsonlTailer;
+use crate::common::transcript_index::TranscriptIndex;
+use crate::common::usage::{format_tokens, price_for, ModelPrice, Usage};
+use anyhow::Result;
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+
+/// What `stats` groups usage by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum StatsGroup {
+    /// Project working directory
+    Project,
+    /// Claude session id
+    Session,
+    /// Model id
+    Model,
+    /// UTC day
+    Day,
+}
+
+/// One row of `stats`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatsRow {
+    pub key: String,
+    /// Number of sessions contributing to the row
+    pub sessions: usize,
+    pub tokens: Usage,
+    pub cost_usd: f64,
+}
+
+/// Parse `--since`: a date ("2026-03-01") or an age ("7d", "2w", "12h", "30m")
+pub fn parse_since(s: &str) -> Result<NaiveDate, String> {
+    let s = s.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+    let days = |number: &str, per_unit: i64| {
+        number
+            .parse::<i64>()
+            .map(|n| n * per_unit)
+            .map_err(|_| format!("invalid duration '{}'", s))
+    };
+    let age = if let Some(number) = s.strip_suffix('d') {
+        ChronoDuration::days(days(number, 1)?)
+    } else if let Some(number) = s.strip_suffix('w') {
+        ChronoDuration::days(days(number, 7)?)
+    } else {
+        parse_duration(s)
+            .ok()
+            .and_then(|age| ChronoDuration::from_std(age).ok())
+            .ok_or_else(|| format!("invalid --since '{}' (use a date or e.g. 7d, 2w, 12h)", s))?
+    };
+    Ok((Utc::now() - age).date_naive())
+}
+
+/// Usage per group on days from `since` (inclusive), most expensive first
+/// (chronological for `StatsGroup::Day`)
+pub fn aggregate(
+    index: &TranscriptIndex,
+    by: StatsGroup,
+    since: Option<NaiveDate>,
+    prices: &HashMap<String, ModelPrice>,
+) -> Vec<StatsRow> {
+    let since = since.map(|date| date.format("%Y-%m-%d").to_string());
+    let mut rows: HashMap<String, (HashSet<&str>, StatsRow)> = HashMap::new();
+    for (path, file) in &index.files {
+        let session_id = path
+            .rsplit('/')
+            .next()
+            .and_then(|name| name.strip_suffix(".jsonl"))
+            .unwrap_or(path);
+        for (day, models) in &file.usage.days {
+            if since.as_ref().is_some_and(|since| day < since) {
+                continue;
+            }
+            for (model, usage) in models {
+                let key = match by {
+                    StatsGroup::Project => file.project.clone(),
+                    StatsGroup::Session => session_id.to_string(),
+                    StatsGroup::Model => model.clone(),
+                    StatsGroup::Day => day.clone(),
+                };
+                let (sessions, row) = rows.entry(key.clone()).or_insert_with(|| {
+                    let row = StatsRow {
+                        key,
+                        sessions: 0,
+                        tokens: Usage::default(),
+                        cost_usd: 0.0,
+                    };
+                    (HashSet::new(), row)
+                });
+                sessions.insert(session_id);
+                row.tokens.add(usage);
+                row.cost_usd += price_for(model, prices).map_or(0.0, |p| p.cost(usage));
+            }
+        }
+    }
+
+    let mut rows: Vec<StatsRow> = rows
+        .into_values()
+        .map(|(sessions, row)| StatsRow {
+            sessions: sessions.len(),
+            ..row
+        })
+        .collect();
+    if by == StatsGroup::Day {
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+    } else {
+        rows.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd).then(a.key.cmp(&b.key)));
+    }
+    rows
+}
+
+/// Print usage grouped by `by` as a table (or JSON)
+pub fn run_stats(by: StatsGroup, since: Option<NaiveDate>, json: bool) -> Result<()> {
+    // Bring the daemon's persisted index up to date without writing it back
+    let mut index = TranscriptIndex::load();
+    let mut tailer = JsonlTailer::new(claude_projects_dir(), index.cursors(), false);
+    index.apply_round(tailer.read_changes(usize::MAX));
+
+    let config = load_config();
+    let rows = aggregate(&index, by, since, &config.usage.prices);
+    if json {
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
+    if rows.is_empty() {
+        println!("No usage recorded");
+        return Ok(());
+    }
+
+    let header = match by {
+        StatsGroup::Project => "PROJECT",
+        StatsGroup::Session => "SESSION",
+        StatsGroup::Model => "MODEL",
+        StatsGroup::Day => "DAY",
+    };
+    let width = rows
+        .iter()
+        .map(|r| r.key.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let print_row = |key: &str, sessions: String, tokens: &Usage, cost: f64| {
+        println!(
+            "{:<width$}  {:>8}  {:>8}  {:>8}  {:>10}",
+            key,
+            sessions,
+            format_tokens(tokens.total()),
+            format_tokens(tokens.output),
+            format!("${:.2}", cost),
+            width = width
+        );
+    };
+    println!(
+        "{:<width$}  {:>8}  {:>8}  {:>8}  {:>10}",
+        header,
+        "SESSIONS",
+        "TOKENS",
+        "OUTPUT",
+        "COST",
+        width = width
+    );
+    let mut total = Usage::default();
+    let mut total_cost = 0.0;
+    for row in &rows {
+        print_row(
+            &row.key,
+            row.sessions.to_string(),
+            &row.tokens,
+            row.cost_usd,
+        );
+        total.add(&row.tokens);
+        total_cost += row.cost_usd;
+    }
+    print_row("TOTAL", String::new(), &total, total_cost);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::transcript_index::IndexedFile;
+    use crate::common::usage::UsageRecord;
+
+    #[test]
+    fn test_aggregate_by_project_and_day() {
+        let file = |project: &str, records: &[(&str, &str, u64)]| {
+            let mut file = IndexedFile {
+                project: project.to_string(),
+                ..Default::default()
+            };
+            for (i, (day, model, output)) in records.iter().enumerate() {
+                file.usage.record(UsageRecord {
+                    message_id: format!("msg_{}", i),
+                    model: model.to_string(),
+                    day: day.to_string(),
+                    usage: Usage {
+                        output: *output,
+                        ..Default::default()
+                    },
+                    sidechain: false,
+                });
+            }
+            file
+        };
+        let mut index = TranscriptIndex::default();
+        index.files.insert(
+            "/p/-api/a.jsonl".to_string(),
+            file("/api", &[("2026-03-01", "claude-sonnet-4-5", 1_000_000)]),
+        );
+        index.files.insert(
+            "/p/-api/b.jsonl".to_string(),
+            file(
+                "/api",
+                &[
+                    ("2026-03-02", "claude-sonnet-4-5", 1_000_000),
+                    ("2026-03-03", "claude-haiku-4-5", 1_000_000),
+                ],
+            ),
+        );
+        index.files.insert(
+            "/p/-web/c.jsonl".to_string(),
+            file("/web", &[("2026-03-03", "claude-opus-4-5", 1_000_000)]),
+        );
+        let prices = HashMap::new();
+
+        let rows = aggregate(&index, StatsGroup::Project, None, &prices);
+        assert_eq!(rows[0].key, "/api");
+        assert_eq!((rows[0].sessions, rows[0].tokens.output), (2, 3_000_000));
+        assert!((rows[0].cost_usd - 35.0).abs() < 1e-9);
+        assert_eq!(rows[1].key, "/web");
+
+        let since = NaiveDate::from_ymd_opt(2026, 3, 2);
+        let rows = aggregate(&index, StatsGroup::Day, since, &prices);
+        let days: Vec<(&str, usize)> = rows.iter().map(|r| (r.key.as_str(), r.sessions)).collect();
+        assert_eq!(days, vec![("2026-03-02", 1), ("2026-03-03", 2)]);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let today = Utc::now().date_naive();
+        assert_eq!(parse_since("7d"), Ok(today - ChronoDuration::days(7)));
+        assert_eq!(parse_since("2w"), Ok(today - ChronoDuration::days(14)));
+        assert_eq!(
+            parse_since("2026-03-01"),
+            NaiveDate::parse_from_str("2026-03-01", "%Y-%m-%d").map_err(|e| e.to_string())
+        );
+        assert!(parse_since("soon").is_err());
+    }
+}
@@ -0,0 +1,32 @@
+//! `status-line`: session summary for tmux status bars, read from the daemon's snapshot file.
+
+use crate::common::status_line::{
+    render_session, render_summary, session_status, StatusCounts, StatusEntry,
+    DEFAULT_SESSION_FORMAT, DEFAULT_SUMMARY_FORMAT,
+};
+use crate::ipc::messages::get_status_snapshot_path;
+use anyhow::Result;
+use std::fs;
+
+/// Print the summary (or one tmux session's status). Prints nothing if the daemon isn't running.
+pub fn run_status_line(format: Option<String>, session: Option<String>, color: bool) -> Result<()> {
+    let Ok(content) = fs::read_to_string(get_status_snapshot_path()) else {
+        return Ok(());
+    };
+    let entries: Vec<StatusEntry> = serde_json::from_str(&content).unwrap_or_default();
+
+    let line = match session {
+        Some(name) => render_session(
+            format.as_deref().unwrap_or(DEFAULT_SESSION_FORMAT),
+            session_status(&entries, &name),
+            color,
+        ),
+        None => render_summary(
+            format.as_deref().unwrap_or(DEFAULT_SUMMARY_FORMAT),
+            &StatusCounts::from_entries(&entries),
+            color,
+        ),
+    };
+    println!("{}", line);
+    Ok(())
+}
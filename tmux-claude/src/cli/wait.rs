@@ -0,0 +1,385 @@
+//! `wait`: block until a session reaches a status, for scripts and local CI flows.
+
+use crate::common::types::TmuxSession;
+use crate::daemon::remote::send_command;
+use crate::ipc::messages::{
+    get_socket_path, DaemonCommand, DaemonResponse, SessionState, SessionStatus,
+};
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::time::Instant;
+
+/// How often the full state is re-read (catches panes closing, which sends no update)
+const RESYNC_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Exit code on timeout (same as coreutils `timeout`)
+pub const TIMEOUT_EXIT_CODE: i32 = 124;
+
+/// Condition `wait` blocks on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WaitCondition {
+    /// Claude finishes a turn (a session already waiting has to work and stop again)
+    Waiting,
+    /// Claude is blocked on the user (permission, plan, question)
+    Attention,
+    /// The session, live when the wait began, no longer is
+    Gone,
+}
+
+/// Parse a duration like "90", "30s", "10m" or "1h"
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (number, unit) = match s.find(|c: char| !c.is_ascii_digit()) {
+        Some(idx) => s.split_at(idx),
+        None => (s, "s"),
+    };
+    let value: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", s))?;
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        _ => return Err(format!("invalid duration unit '{}' (use s, m or h)", unit)),
+    };
+    let secs = value
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("duration '{}' is too long", s))?;
+    Ok(Duration::from_secs(secs))
+}
+
+/// Whether a daemon session is the one `query` names (session id or tmux session name)
+fn matches_query(session: &SessionState, query: &str) -> bool {
+    session.host.is_none() && (session.session_id == query || session.tmux_session == query)
+}
+
+/// What a wait has observed since it began
+#[derive(Debug, Default)]
+pub struct WaitProgress {
+    /// Sessions already waiting at the start; they count once seen working again
+    stale_waiting: HashSet<String>,
+    /// A matching live session has been seen
+    seen: bool,
+}
+
+impl WaitProgress {
+    /// Progress for a wait starting on this state
+    pub fn start(
+        sessions: &[SessionState],
+        tmux_sessions: Option<&[TmuxSession]>,
+        query: &str,
+    ) -> Self {
+        let mut progress = WaitProgress::default();
+        for session in live_matches(sessions, tmux_sessions, query) {
+            progress.seen = true;
+            if session.status == SessionStatus::Waiting {
+                progress.stale_waiting.insert(session.session_id.clone());
+            }
+        }
+        progress
+    }
+}
+
+/// Live sessions matching `query`
+fn live_matches<'a>(
+    sessions: &'a [SessionState],
+    tmux_sessions: Option<&'a [TmuxSession]>,
+    query: &'a str,
+) -> impl Iterator<Item = &'a SessionState> {
+    sessions
+        .iter()
+        .filter(move |s| matches_query(s, query) && s.pane_alive(tmux_sessions))
+}
+
+/// Whether `condition` holds for the live sessions matching `query`, given
+/// what the wait has seen so far (updated here)
+pub fn condition_met(
+    condition: WaitCondition,
+    sessions: &[SessionState],
+    tmux_sessions: Option<&[TmuxSession]>,
+    query: &str,
+    progress: &mut WaitProgress,
+) -> bool {
+    let mut any = false;
+    let mut met = false;
+    for session in live_matches(sessions, tmux_sessions, query) {
+        any = true;
+        let waiting = session.status == SessionStatus::Waiting;
+        if !waiting {
+            progress.stale_waiting.remove(&session.session_id);
+        }
+        met |= match condition {
+            WaitCondition::Waiting => {
+                waiting && !progress.stale_waiting.contains(&session.session_id)
+            }
+            WaitCondition::Attention => session.needs_attention,
+            WaitCondition::Gone => false,
+        };
+    }
+    progress.seen |= any;
+    match condition {
+        WaitCondition::Gone => progress.seen && !any,
+        _ => met,
+    }
+}
+
+/// Sleep until the deadline, or forever without one
+async fn deadline_reached(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Fetch sessions and tmux topology from the daemon
+async fn fetch_state(socket: &Path) -> Result<(Vec<SessionState>, Option<Vec<TmuxSession>>)> {
+    match send_command(socket, &DaemonCommand::GetState).await? {
+        DaemonResponse::State {
+            sessions,
+            tmux_sessions,
+            ..
+        } => Ok((sessions, tmux_sessions)),
+        other => bail!("Unexpected response from daemon: {:?}", other),
+    }
+}
+
+/// Block until `condition` holds for `query`. Returns false on timeout;
+/// fails for `Gone` when nothing matches at the start.
+pub async fn wait_until(
+    socket: &Path,
+    query: &str,
+    condition: WaitCondition,
+    timeout: Option<Duration>,
+) -> Result<bool> {
+    // A deadline past what Instant can hold is as good as none
+    let deadline = timeout.and_then(|t| Instant::now().checked_add(t));
+
+    // Subscribe before reading the state so no update in between is missed
+    let stream = UnixStream::connect(socket)
+        .await
+        .context("Daemon is not running")?;
+    let (reader, mut writer) = stream.into_split();
+    let json = serde_json::to_string(&DaemonCommand::Subscribe)?;
+    writer.write_all(json.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+    let mut lines = BufReader::new(reader).lines();
+
+    let (mut sessions, mut tmux_sessions) = fetch_state(socket).await?;
+    let mut progress = WaitProgress::start(&sessions, tmux_sessions.as_deref(), query);
+    if condition == WaitCondition::Gone && !progress.seen {
+        bail!("No live session matches '{}'", query);
+    }
+    let mut resync = tokio::time::interval_at(Instant::now() + RESYNC_INTERVAL, RESYNC_INTERVAL);
+
+    while !condition_met(
+        condition,
+        &sessions,
+        tmux_sessions.as_deref(),
+        query,
+        &mut progress,
+    ) {
+        tokio::select! {
+            line = lines.next_line() => {
+                let Some(line) = line? else {
+                    bail!("Daemon closed the connection");
+                };
+                if let Ok(DaemonResponse::StateUpdate { session }) = serde_json::from_str(&line) {
+                    match sessions.iter_mut().find(|s| s.session_id == session.session_id) {
+                        Some(existing) => *existing = session,
+                        None => sessions.push(session),
+                    }
+                }
+            }
+            _ = resync.tick() => {
+                (sessions, tmux_sessions) = fetch_state(socket).await?;
+            }
+            _ = deadline_reached(deadline) => return Ok(false),
+        }
+    }
+    Ok(true)
+}
+
+/// Run `wait` against the local daemon; exits with 124 on timeout
+pub fn run_wait(session: &str, until: WaitCondition, timeout: Option<Duration>) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    let met = runtime.block_on(wait_until(&get_socket_path(), session, until, timeout))?;
+    if !met {
+        eprintln!("Timed out waiting for '{}'", session);
+        std::process::exit(TIMEOUT_EXIT_CODE);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::server::DaemonServer;
+    use crate::ipc::messages::HookEvent;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("10m"), Ok(Duration::from_secs(600)));
+        assert_eq!(parse_duration("1h"), Ok(Duration::from_secs(3600)));
+        assert!(parse_duration("10d").is_err());
+        assert!(parse_duration("m").is_err());
+        // Overflowing values are rejected instead of panicking
+        assert!(parse_duration(&format!("{}h", u64::MAX / 60)).is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+        assert_eq!(
+            parse_duration(&u64::MAX.to_string()),
+            Ok(Duration::from_secs(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_condition_matches_by_id_or_tmux_name() {
+        let mut session = SessionState::new(
+            "abc".to_string(),
+            "api".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            "/work/api".to_string(),
+        );
+        session.status = SessionStatus::Waiting;
+        let mut sessions = vec![session];
+        let met = |condition, sessions: &[SessionState], query, progress: &mut WaitProgress| {
+            condition_met(condition, sessions, None, query, progress)
+        };
+        let mut fresh = WaitProgress::default();
+        assert!(met(WaitCondition::Waiting, &sessions, "abc", &mut fresh));
+        assert!(met(WaitCondition::Waiting, &sessions, "api", &mut fresh));
+        assert!(!met(WaitCondition::Attention, &sessions, "api", &mut fresh));
+        assert!(!met(WaitCondition::Gone, &sessions, "api", &mut fresh));
+
+        // Already waiting when the wait began: only a new turn counts
+        let mut progress = WaitProgress::start(&sessions, None, "api");
+        assert!(!met(
+            WaitCondition::Waiting,
+            &sessions,
+            "api",
+            &mut progress
+        ));
+        sessions[0].status = SessionStatus::Working;
+        assert!(!met(
+            WaitCondition::Waiting,
+            &sessions,
+            "api",
+            &mut progress
+        ));
+        sessions[0].status = SessionStatus::Waiting;
+        assert!(met(WaitCondition::Waiting, &sessions, "api", &mut progress));
+
+        // Gone only once a session that was seen disappears
+        let mut never_seen = WaitProgress::start(&sessions, None, "web");
+        assert!(!met(WaitCondition::Gone, &sessions, "web", &mut never_seen));
+        let mut progress = WaitProgress::start(&sessions, None, "api");
+        assert!(met(WaitCondition::Gone, &[], "api", &mut progress));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_wait_returns_on_subscribed_update_and_times_out() {
+        let dir = std::env::temp_dir().join(format!("tmux-claude-wait-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("daemon.sock");
+        let server = DaemonServer::with_socket_path(socket.clone()).with_remotes(Vec::new());
+        tokio::spawn(async move { server.run().await });
+        let deadline = std::time::Instant::now() + Duration::from_secs(5);
+        while send_command(&socket, &DaemonCommand::Ping).await.is_err() {
+            assert!(std::time::Instant::now() < deadline, "daemon never came up");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        // Nothing matches, so there is nothing to wait to be gone
+        assert!(wait_until(&socket, "w1", WaitCondition::Gone, None)
+            .await
+            .is_err());
+
+        // Nothing needs attention, so this times out
+        let met = wait_until(
+            &socket,
+            "w1",
+            WaitCondition::Attention,
+            Some(Duration::from_millis(300)),
+        )
+        .await
+        .unwrap();
+        assert!(!met);
+
+        // A Stop event arriving while waiting satisfies --until waiting
+        let waiter = tokio::spawn({
+            let socket = socket.clone();
+            async move {
+                wait_until(
+                    &socket,
+                    "w1",
+                    WaitCondition::Waiting,
+                    Some(Duration::from_secs(5)),
+                )
+                .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let event = HookEvent::Stop {
+            session_id: "w1".to_string(),
+            cwd: "/work/w1".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        send_command(&socket, &DaemonCommand::HookEvent(event))
+            .await
+            .unwrap();
+        assert!(waiter.await.unwrap().unwrap());
+
+        // Already waiting: times out until Claude works and stops again
+        let met = wait_until(
+            &socket,
+            "w1",
+            WaitCondition::Waiting,
+            Some(Duration::from_millis(300)),
+        )
+        .await
+        .unwrap();
+        assert!(!met);
+        let waiter = tokio::spawn({
+            let socket = socket.clone();
+            async move {
+                wait_until(
+                    &socket,
+                    "w1",
+                    WaitCondition::Waiting,
+                    Some(Duration::from_secs(5)),
+                )
+                .await
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let prompt = HookEvent::UserPromptSubmit {
+            session_id: "w1".to_string(),
+            cwd: "/work/w1".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        send_command(&socket, &DaemonCommand::HookEvent(prompt))
+            .await
+            .unwrap();
+        let stop = HookEvent::Stop {
+            session_id: "w1".to_string(),
+            cwd: "/work/w1".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        send_command(&socket, &DaemonCommand::HookEvent(stop))
+            .await
+            .unwrap();
+        assert!(waiter.await.unwrap().unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
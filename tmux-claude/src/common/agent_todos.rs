@@ -0,0 +1,77 @@
+//! Claude's own task list, from its `TodoWrite` tool calls.
+
+use serde::{Deserialize, Serialize};
+
+/// Status of one task in Claude's list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TodoStatus {
+    InProgress,
+    Completed,
+    /// Also any status this version doesn't know
+    #[serde(other)]
+    Pending,
+}
+
+/// One task of Claude's list
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentTodo {
+    pub content: String,
+    pub status: TodoStatus,
+    /// Present-tense form shown while the task is in progress ("Running tests")
+    #[serde(default, alias = "activeForm")]
+    pub active_form: Option<String>,
+}
+
+impl AgentTodo {
+    /// Task list of a `TodoWrite` tool input (None if it has none)
+    pub fn list_from_input(input: &serde_json::Value) -> Option<Vec<AgentTodo>> {
+        serde_json::from_value(input.get("todos")?.clone()).ok()
+    }
+
+    /// Text to show: the active form while in progress, else the task itself
+    pub fn label(&self) -> &str {
+        match (self.status, self.active_form.as_deref()) {
+            (TodoStatus::InProgress, Some(active)) if !active.is_empty() => active,
+            _ => &self.content,
+        }
+    }
+}
+
+/// Completed and total task counts
+pub fn todo_progress(todos: &[AgentTodo]) -> (usize, usize) {
+    let done = todos
+        .iter()
+        .filter(|t| t.status == TodoStatus::Completed)
+        .count();
+    (done, todos.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_todo_write_input() {
+        let input = serde_json::json!({"todos": [
+            {"content": "Add parser", "status": "completed", "activeForm": "Adding parser"},
+            {"content": "Run tests", "status": "in_progress", "activeForm": "Running tests"},
+            {"content": "Update docs", "status": "pending", "priority": "low", "id": "3"},
+            {"content": "Ship", "status": "blocked"},
+        ]});
+        let todos = AgentTodo::list_from_input(&input).unwrap();
+        assert_eq!(todo_progress(&todos), (1, 4));
+        assert_eq!(todos[1].label(), "Running tests");
+        assert_eq!(todos[0].label(), "Add parser");
+        assert_eq!(todos[3].status, TodoStatus::Pending);
+        assert_eq!(AgentTodo::list_from_input(&serde_json::json!({})), None);
+
+        // Stored in daemon state under our own field names
+        let stored = serde_json::to_string(&todos[1]).unwrap();
+        assert!(stored.contains(r#""active_form":"Running tests""#));
+        assert_eq!(
+            serde_json::from_str::<AgentTodo>(&stored).unwrap(),
+            todos[1]
+        );
+    }
+}
@@ -0,0 +1,270 @@
+//! Classify API errors, rate limits and usage-limit messages from Claude
+//! transcripts and `Notification` hooks.
+
+use crate::ipc::messages::SessionStatus;
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone, Utc};
+
+/// Why Claude stopped making progress
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiProblem {
+    /// Rate or usage limit; `resets_at` when the message names a reset time
+    RateLimited { resets_at: Option<DateTime<Utc>> },
+    /// Overloaded, server error or timeout
+    ApiError { message: String },
+}
+
+impl ApiProblem {
+    /// Daemon session status for this problem
+    pub fn into_status(self) -> SessionStatus {
+        match self {
+            ApiProblem::RateLimited { resets_at } => SessionStatus::RateLimited {
+                resets_at: resets_at.map(|t| t.to_rfc3339()),
+            },
+            ApiProblem::ApiError { message } => SessionStatus::ApiError { message },
+        }
+    }
+}
+
+/// Classify a message (`status` is the transcript's `apiErrorStatus`, if any)
+pub fn classify_message(
+    text: &str,
+    status: Option<u64>,
+    now: DateTime<Local>,
+) -> Option<ApiProblem> {
+    let lower = text.to_lowercase();
+    if status == Some(429)
+        || lower.contains("usage limit reached")
+        || lower.contains("5-hour limit reached")
+        || lower.contains("rate limit")
+        || lower.contains("rate_limit")
+    {
+        return Some(ApiProblem::RateLimited {
+            resets_at: parse_reset_time(text, now),
+        });
+    }
+    let server_error = status.is_some_and(|s| (500..600).contains(&s));
+    if !(server_error || lower.starts_with("api error") || lower.contains("overloaded")) {
+        return None;
+    }
+    let message = if status == Some(529) || lower.contains("overloaded") {
+        "overloaded".to_string()
+    } else {
+        let first_line = text.lines().next().unwrap_or_default();
+        let detail = first_line
+            .strip_prefix("API Error")
+            .map(|rest| rest.trim_start_matches(':').trim())
+            .unwrap_or(first_line);
+        detail.chars().take(80).collect()
+    };
+    Some(ApiProblem::ApiError { message })
+}
+
+/// Classify a transcript entry flagged `isApiErrorMessage`
+pub fn classify_entry(entry: &serde_json::Value, now: DateTime<Local>) -> Option<ApiProblem> {
+    if entry.get("isApiErrorMessage").and_then(|v| v.as_bool()) != Some(true) {
+        return None;
+    }
+    // Reset times like "3pm" are relative to when the error was written
+    let now = entry
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Local))
+        .unwrap_or(now);
+    let status = entry.get("apiErrorStatus").and_then(|s| s.as_u64());
+    let text = match entry.pointer("/message/content") {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    };
+    classify_message(&text, status, now).or_else(|| {
+        Some(ApiProblem::ApiError {
+            message: "API error".to_string(),
+        })
+    })
+}
+
+/// The problem a transcript ends with: the last assistant entry decides
+/// (None once a normal reply follows the error)
+pub fn trailing_problem(
+    entries: &[serde_json::Value],
+    now: DateTime<Local>,
+) -> Option<Option<ApiProblem>> {
+    entries
+        .iter()
+        .rev()
+        .find(|e| e.get("type").and_then(|t| t.as_str()) == Some("assistant"))
+        .map(|e| classify_entry(e, now))
+}
+
+/// Reset time named in a limit message: a unix timestamp after '|'
+/// ("usage limit reached|1767225600") or a local time after "reset"
+/// ("resets 3pm", "reset at 3:30pm (Europe/Paris)", "resets at 15:00").
+/// Time zone annotations are ignored; the time is taken as local.
+pub fn parse_reset_time(text: &str, now: DateTime<Local>) -> Option<DateTime<Utc>> {
+    if let Some((_, epoch)) = text.rsplit_once('|') {
+        if let Ok(secs) = epoch.trim().parse::<i64>() {
+            return DateTime::from_timestamp(secs, 0);
+        }
+    }
+
+    let lower = text.to_lowercase();
+    let rest = &lower[lower.find("reset")? + "reset".len()..];
+    let rest = rest.strip_prefix('s').unwrap_or(rest).trim_start();
+    let rest = rest.strip_prefix("at ").unwrap_or(rest).trim_start();
+
+    let digits = |s: &str| s.chars().take_while(|c| c.is_ascii_digit()).count();
+    let hour_len = digits(rest);
+    let mut hour: u32 = rest[..hour_len].parse().ok()?;
+    let mut rest = &rest[hour_len..];
+    let mut minute = 0;
+    let mut exact = false;
+    if let Some(after) = rest.strip_prefix(':') {
+        let minute_len = digits(after);
+        minute = after[..minute_len].parse().ok()?;
+        rest = &after[minute_len..];
+        exact = true;
+    }
+    let rest = rest.trim_start();
+    if rest.starts_with("pm") && hour < 12 {
+        hour += 12;
+    } else if rest.starts_with("am") && hour == 12 {
+        hour = 0;
+    } else if !rest.starts_with("am") && !rest.starts_with("pm") && !exact {
+        // A bare number ("resets in 5 hours") isn't a time of day
+        return None;
+    }
+
+    let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+    let today = now.date_naive().and_time(time);
+    let reset = Local.from_local_datetime(&today).earliest()?;
+    let reset = if reset <= now {
+        Local
+            .from_local_datetime(&(today + Duration::days(1)))
+            .earliest()?
+    } else {
+        reset
+    };
+    Some(reset.with_timezone(&Utc))
+}
+
+/// Time left until `resets_at`, e.g. "1h 05m", "4m", "now"
+pub fn format_countdown(resets_at: &DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let secs = resets_at.signed_duration_since(now).num_seconds();
+    if secs <= 0 {
+        "now".to_string()
+    } else if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m", (secs + 59) / 60)
+    } else {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn local(hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 3, 10, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_reset_time() {
+        let now = local(13, 0);
+        let at = |h, m| Some(local(h, m).with_timezone(&Utc));
+        assert_eq!(
+            parse_reset_time("5-hour limit reached ∙ resets 3pm", now),
+            at(15, 0)
+        );
+        assert_eq!(
+            parse_reset_time("Your limit will reset at 3:30pm (Europe/Paris).", now),
+            at(15, 30)
+        );
+        assert_eq!(parse_reset_time("resets at 14:00", now), at(14, 0));
+        // Already past today: tomorrow
+        let tomorrow = Local.with_ymd_and_hms(2026, 3, 11, 9, 0, 0).unwrap();
+        assert_eq!(
+            parse_reset_time("limit resets 9am", now),
+            Some(tomorrow.with_timezone(&Utc))
+        );
+        assert_eq!(
+            parse_reset_time("Claude AI usage limit reached|1767225600", now),
+            DateTime::from_timestamp(1_767_225_600, 0)
+        );
+        assert_eq!(parse_reset_time("limit resets in 5 hours", now), None);
+        assert_eq!(parse_reset_time("usage limit reached", now), None);
+    }
+
+    #[test]
+    fn test_classify_messages_and_entries() {
+        let now = local(13, 0);
+        assert_eq!(
+            classify_message(
+                "Claude usage limit reached. Your limit will reset at 3pm",
+                None,
+                now
+            ),
+            Some(ApiProblem::RateLimited {
+                resets_at: Some(local(15, 0).with_timezone(&Utc))
+            })
+        );
+        assert_eq!(
+            classify_message(
+                r#"API Error: 529 {"type":"error","error":{"type":"overloaded_error"}}"#,
+                None,
+                now
+            ),
+            Some(ApiProblem::ApiError {
+                message: "overloaded".to_string()
+            })
+        );
+        assert_eq!(
+            classify_message("API Error (Request timed out.)", None, now),
+            Some(ApiProblem::ApiError {
+                message: "(Request timed out.)".to_string()
+            })
+        );
+        assert_eq!(
+            classify_message("Claude needs your permission to use Bash", None, now),
+            None
+        );
+        assert_eq!(classify_message("Context limit reached", None, now), None);
+        assert!(matches!(
+            classify_message("5-hour limit reached ∙ resets 3pm", None, now),
+            Some(ApiProblem::RateLimited { .. })
+        ));
+
+        let entries: Vec<serde_json::Value> = [
+            r#"{"type":"user","message":{"content":"hi"}}"#,
+            r#"{"type":"assistant","isApiErrorMessage":true,"apiErrorStatus":429,"message":{"content":[{"type":"text","text":"API Error: 429 rate_limit_error"}]}}"#,
+        ]
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+        assert_eq!(
+            trailing_problem(&entries, now),
+            Some(Some(ApiProblem::RateLimited { resets_at: None }))
+        );
+        let reply = serde_json::json!({"type": "assistant", "message": {"content": "Done"}});
+        let recovered = [entries[1].clone(), reply];
+        assert_eq!(trailing_problem(&recovered, now), Some(None));
+        assert_eq!(trailing_problem(&entries[..1], now), None);
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        let now = Utc::now();
+        let later = |secs| now + Duration::seconds(secs);
+        assert_eq!(format_countdown(&later(-5), now), "now");
+        assert_eq!(format_countdown(&later(90), now), "2m");
+        assert_eq!(format_countdown(&later(3900), now), "1h 05m");
+    }
+}
@@ -0,0 +1,290 @@
+//! User configuration loaded from ~/.config/tmux-claude/config.json.
+
+use crate::common::risk::RiskLevel;
+use crate::common::usage::ModelPrice;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// Top-level configuration. Every section is optional and falls back to defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Resource alert rules evaluated by the daemon
+    pub alerts: AlertConfig,
+    /// Remote daemons whose sessions are aggregated into the local daemon
+    pub remotes: Vec<RemoteConfig>,
+    /// Directory for per-session git worktrees (default: next to the repository)
+    pub worktree_root: Option<PathBuf>,
+    /// Extra risk rules for permission requests
+    pub risk: RiskConfig,
+    /// Model prices and spending budgets
+    pub usage: UsageConfig,
+}
+
+impl Config {
+    /// Worktree root with a leading `~/` expanded
+    pub fn worktree_root(&self) -> Option<PathBuf> {
+        self.worktree_root.as_deref().map(expand_home)
+    }
+}
+
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// A remote daemon reachable through a local Unix socket (typically forwarded
+/// with `ssh -N -L /tmp/devbox.sock:/home/me/.cache/tmux-claude/daemon.sock devbox`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RemoteConfig {
+    /// Host tag shown next to the remote's sessions
+    pub name: String,
+    /// Local path of the forwarded socket (a leading `~/` is expanded)
+    pub socket: PathBuf,
+}
+
+impl RemoteConfig {
+    /// Socket path with a leading `~/` expanded to the home directory
+    pub fn socket_path(&self) -> PathBuf {
+        expand_home(&self.socket)
+    }
+}
+
+/// Default alert: one process using most of a core (for `cpu_sustained_secs`) ...
+const PROCESS_CPU_ALERT: f32 = 90.0;
+/// ... or holding more than ~1.5G resident
+const PROCESS_RSS_ALERT_KB: u64 = 1536000;
+
+/// Resource alert configuration: a global rule plus per-session overrides
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertConfig {
+    pub enabled: bool,
+    /// Rule applied to every tmux session
+    pub global: AlertRule,
+    /// Per-session overrides (tmux session name -> rule); set fields replace the global
+    /// ones, and a 0 threshold turns that check off
+    pub sessions: HashMap<String, AlertRule>,
+    /// Mark a working Claude session stalled after this long without hook events or
+    /// transcript writes (None = never)
+    pub stall_after_secs: Option<u64>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            global: AlertRule {
+                cpu_percent: Some(PROCESS_CPU_ALERT),
+                cpu_sustained_secs: Some(120),
+                rss_kb: Some(PROCESS_RSS_ALERT_KB),
+                max_processes: None,
+            },
+            sessions: HashMap::new(),
+            stall_after_secs: Some(600),
+        }
+    }
+}
+
+impl AlertConfig {
+    /// Get the effective rule for a session (global rule with session overrides applied)
+    pub fn rule_for(&self, session_name: &str) -> AlertRule {
+        let over = self.sessions.get(session_name).cloned().unwrap_or_default();
+        self.global.merged(&over)
+    }
+}
+
+/// Thresholds for a single alert rule. `None` inherits (from the global rule),
+/// 0 disables that check.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertRule {
+    /// A single process using more than this CPU% ...
+    pub cpu_percent: Option<f32>,
+    /// ... for at least this many seconds
+    pub cpu_sustained_secs: Option<u64>,
+    /// A single process with resident memory above this (KB)
+    pub rss_kb: Option<u64>,
+    /// More than this many processes in the session
+    pub max_processes: Option<usize>,
+}
+
+impl AlertRule {
+    /// Apply an override on top of this rule (fields set in `over` win);
+    /// thresholds that end up 0 are turned off
+    pub fn merged(&self, over: &AlertRule) -> AlertRule {
+        AlertRule {
+            cpu_percent: over.cpu_percent.or(self.cpu_percent).filter(|v| *v > 0.0),
+            cpu_sustained_secs: over.cpu_sustained_secs.or(self.cpu_sustained_secs),
+            rss_kb: over.rss_kb.or(self.rss_kb).filter(|v| *v > 0),
+            max_processes: over.max_processes.or(self.max_processes).filter(|v| *v > 0),
+        }
+    }
+}
+
+/// User additions to the built-in risk classifier
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    pub rules: Vec<RiskRule>,
+}
+
+/// Tag commands matching `pattern` (a `*` glob over one pipeline/`&&` segment,
+/// e.g. "kubectl delete *") with `label` at `level`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskRule {
+    pub pattern: String,
+    pub level: RiskLevel,
+    pub label: String,
+}
+
+/// Token prices, per-session budgets, context window warnings and usage-limit handling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageConfig {
+    /// Prices by model id fragment (e.g. "opus-4-5"), overriding the built-in ones
+    pub prices: HashMap<String, ModelPrice>,
+    /// Budget in USD for every Claude session (None = no budget)
+    pub session_budget_usd: Option<f64>,
+    /// Per-session budgets (tmux session name -> USD), overriding the global one
+    pub budgets: HashMap<String, f64>,
+    /// Context windows in tokens by model id fragment (default 200k, or 1M once exceeded)
+    pub context_windows: HashMap<String, u64>,
+    /// Notify when a session's context window is this full (percent; None = never)
+    pub context_warn_percent: Option<f64>,
+    /// Type "continue" into rate-limited sessions once their limit resets
+    pub auto_resume: bool,
+}
+
+impl Default for UsageConfig {
+    fn default() -> Self {
+        Self {
+            prices: HashMap::new(),
+            session_budget_usd: None,
+            budgets: HashMap::new(),
+            context_windows: HashMap::new(),
+            context_warn_percent: Some(85.0),
+            auto_resume: false,
+        }
+    }
+}
+
+impl UsageConfig {
+    /// Effective budget for a tmux session
+    pub fn budget_for(&self, session_name: &str) -> Option<f64> {
+        self.budgets
+            .get(session_name)
+            .copied()
+            .or(self.session_budget_usd)
+    }
+}
+
+/// Get the path to the config file
+pub fn get_config_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".config").join("tmux-claude").join("config.json"))
+}
+
+/// Load configuration from disk, falling back to defaults if missing or invalid
+pub fn load_config() -> Config {
+    get_config_file_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Configuration for the daemon's loops and hook handling: kept in memory and
+/// re-read only when the file's modification time changes
+pub fn cached_config() -> Arc<Config> {
+    static CACHED: Mutex<Option<(Option<SystemTime>, Arc<Config>)>> = Mutex::new(None);
+    let modified = get_config_file_path()
+        .and_then(|path| fs::metadata(path).ok())
+        .and_then(|meta| meta.modified().ok());
+    let mut cached = CACHED.lock().unwrap_or_else(|e| e.into_inner());
+    match cached.as_ref() {
+        Some((at, config)) if *at == modified => config.clone(),
+        _ => {
+            let config = Arc::new(load_config());
+            *cached = Some((modified, config.clone()));
+            config
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_config_uses_defaults() {
+        let config: Config = serde_json::from_str("{}").unwrap();
+        assert!(config.alerts.enabled);
+        assert_eq!(config.alerts.global.cpu_percent, Some(PROCESS_CPU_ALERT));
+    }
+
+    #[test]
+    fn test_session_override_merges_with_global() {
+        let config: Config = serde_json::from_str(
+            r#"{"alerts":{"sessions":{"build":{"cpu_percent":400.0,"max_processes":50}}}}"#,
+        )
+        .unwrap();
+        let rule = config.alerts.rule_for("build");
+        assert_eq!(rule.cpu_percent, Some(400.0));
+        assert_eq!(rule.max_processes, Some(50));
+        assert_eq!(rule.cpu_sustained_secs, Some(120));
+        assert_eq!(config.alerts.rule_for("other"), config.alerts.global);
+    }
+
+    #[test]
+    fn test_remotes_parse_and_expand_home() {
+        let config: Config = serde_json::from_str(
+            r#"{"remotes":[{"name":"devbox","socket":"~/.ssh/devbox.sock"},{"name":"ci","socket":"/tmp/ci.sock"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.remotes.len(), 2);
+        assert_eq!(config.remotes[0].name, "devbox");
+        assert!(!config.remotes[0].socket_path().starts_with("~"));
+        assert!(config.remotes[0]
+            .socket_path()
+            .ends_with(".ssh/devbox.sock"));
+        assert_eq!(
+            config.remotes[1].socket_path(),
+            PathBuf::from("/tmp/ci.sock")
+        );
+    }
+
+    #[test]
+    fn test_risk_rules_parse() {
+        let config: Config = serde_json::from_str(
+            r#"{"risk":{"rules":[{"pattern":"terraform apply*","level":"high","label":"infra"}]}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.risk.rules[0].level, RiskLevel::High);
+        let empty: Config = serde_json::from_str("{}").unwrap();
+        assert!(empty.risk.rules.is_empty());
+    }
+
+    #[test]
+    fn test_usage_prices_and_budgets_parse() {
+        let config: Config = serde_json::from_str(
+            r#"{"usage":{"prices":{"opus":{"input":10.0,"output":40.0}},"session_budget_usd":5.0,"budgets":{"big":20.0}}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.usage.prices["opus"].output, 40.0);
+        assert_eq!(config.usage.prices["opus"].cache_read, None);
+        assert_eq!(config.usage.budget_for("big"), Some(20.0));
+        assert_eq!(config.usage.budget_for("other"), Some(5.0));
+        let empty: Config = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty.usage.budget_for("big"), None);
+        assert_eq!(empty.usage.context_warn_percent, Some(85.0));
+        let off: Config =
+            serde_json::from_str(r#"{"usage":{"context_warn_percent":null}}"#).unwrap();
+        assert_eq!(off.usage.context_warn_percent, None);
+    }
+}
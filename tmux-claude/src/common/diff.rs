@@ -0,0 +1,398 @@
+//! Preview of Write/Edit/MultiEdit tool calls as a unified diff against the file on disk.
+
+use crate::ipc::messages::PendingTool;
+use std::path::{Path, PathBuf};
+
+/// Context lines around each change
+pub const DIFF_CONTEXT: usize = 3;
+
+/// Largest old×new line product diffed exactly (beyond it the change is shown as replace-all)
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Kind of a rendered diff line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    /// Hunk header ("@@ -1,3 +1,4 @@")
+    Header,
+    Context,
+    Added,
+    Removed,
+}
+
+/// One line of a unified diff (text without the +/-/space prefix)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+}
+
+/// File contents before and after a proposed edit
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProposedEdit {
+    /// File the tool writes to (resolved against the session cwd)
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+    /// Why the diff only covers the tool's own strings rather than the whole file
+    pub note: Option<String>,
+}
+
+/// Old/new contents of a file-editing tool call. With `cwd` the file on disk is
+/// read; without it (remote sessions) only the tool input is used.
+pub fn proposed_edit(
+    tool_name: &str,
+    input: &serde_json::Value,
+    cwd: Option<&Path>,
+) -> Option<ProposedEdit> {
+    let file_path = input.get("file_path")?.as_str()?;
+    let path = match cwd {
+        Some(cwd) => cwd.join(file_path),
+        None => PathBuf::from(file_path),
+    };
+    let current = match cwd {
+        Some(_) => std::fs::read_to_string(&path).ok(),
+        None => None,
+    };
+    let str_field = |value: &serde_json::Value, key: &str| {
+        value
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    let edits: Vec<(String, String, bool)> = match tool_name {
+        "Write" => {
+            let new = str_field(input, "content");
+            let note = match (&current, cwd) {
+                (_, None) => Some("remote file, shown as new".to_string()),
+                (None, _) => Some("new file".to_string()),
+                _ => None,
+            };
+            return Some(ProposedEdit {
+                path,
+                old: current.unwrap_or_default(),
+                new,
+                note,
+            });
+        }
+        "Edit" => vec![(
+            str_field(input, "old_string"),
+            str_field(input, "new_string"),
+            input
+                .get("replace_all")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        )],
+        "MultiEdit" => input
+            .get("edits")?
+            .as_array()?
+            .iter()
+            .map(|edit| {
+                (
+                    str_field(edit, "old_string"),
+                    str_field(edit, "new_string"),
+                    edit.get("replace_all")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                )
+            })
+            .collect(),
+        _ => return None,
+    };
+
+    // Apply the edits in order; any miss means the file changed since Claude read it
+    let applied = current.as_ref().and_then(|content| {
+        let mut new = content.clone();
+        for (old_string, new_string, replace_all) in &edits {
+            if old_string.is_empty() || !new.contains(old_string.as_str()) {
+                return None;
+            }
+            new = if *replace_all {
+                new.replace(old_string.as_str(), new_string)
+            } else {
+                new.replacen(old_string.as_str(), new_string, 1)
+            };
+        }
+        Some(new)
+    });
+    match (current, applied) {
+        (Some(old), Some(new)) => Some(ProposedEdit {
+            path,
+            old,
+            new,
+            note: None,
+        }),
+        (current, _) => {
+            let note = if cwd.is_none() {
+                "remote file, showing the edited strings only"
+            } else if current.is_none() {
+                "file not readable, showing the edited strings only"
+            } else {
+                "text not found in file, showing the edited strings only"
+            };
+            let (old, new): (Vec<String>, Vec<String>) =
+                edits.into_iter().map(|(old, new, _)| (old, new)).unzip();
+            Some(ProposedEdit {
+                path,
+                old: old.join("\n"),
+                new: new.join("\n"),
+                note: Some(note.to_string()),
+            })
+        }
+    }
+}
+
+/// Rendered preview of a pending file edit
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditPreview {
+    pub path: PathBuf,
+    pub note: Option<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Diff preview of a pending tool call (None for tools that don't edit files)
+pub fn edit_preview(tool: &PendingTool, cwd: Option<&Path>) -> Option<EditPreview> {
+    let edit = proposed_edit(&tool.tool_name, &tool.tool_input, cwd)?;
+    Some(EditPreview {
+        lines: unified_diff(&edit.old, &edit.new, DIFF_CONTEXT),
+        path: edit.path,
+        note: edit.note,
+    })
+}
+
+/// Line-level edit script between `old` and `new` (common prefix/suffix, LCS in between)
+fn diff_ops<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<(DiffKind, &'a str)> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut ops: Vec<(DiffKind, &str)> = old[..prefix]
+        .iter()
+        .map(|line| (DiffKind::Context, *line))
+        .collect();
+
+    if old_mid.len().saturating_mul(new_mid.len()) > MAX_LCS_CELLS {
+        ops.extend(old_mid.iter().map(|line| (DiffKind::Removed, *line)));
+        ops.extend(new_mid.iter().map(|line| (DiffKind::Added, *line)));
+    } else {
+        // lcs[i][j] = LCS length of old_mid[i..] and new_mid[j..]
+        let (n, m) = (old_mid.len(), new_mid.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if old_mid[i] == new_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && old_mid[i] == new_mid[j] {
+                ops.push((DiffKind::Context, old_mid[i]));
+                i += 1;
+                j += 1;
+            } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+                ops.push((DiffKind::Removed, old_mid[i]));
+                i += 1;
+            } else {
+                ops.push((DiffKind::Added, new_mid[j]));
+                j += 1;
+            }
+        }
+    }
+
+    ops.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|line| (DiffKind::Context, *line)),
+    );
+    ops
+}
+
+/// Unified diff of two texts with `context` lines around each change (empty if equal)
+pub fn unified_diff(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_ops(&old_lines, &new_lines);
+
+    // Line numbers (1-based) of each op in the old and new file
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut old_no, mut new_no) = (1, 1);
+    for (kind, _) in &ops {
+        positions.push((old_no, new_no));
+        match kind {
+            DiffKind::Removed => old_no += 1,
+            DiffKind::Added => new_no += 1,
+            _ => {
+                old_no += 1;
+                new_no += 1;
+            }
+        }
+    }
+
+    let changes: Vec<usize> = ops
+        .iter()
+        .enumerate()
+        .filter(|(_, (kind, _))| *kind != DiffKind::Context)
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut lines = Vec::new();
+    let mut idx = 0;
+    while idx < changes.len() {
+        // Merge changes whose context would overlap into one hunk
+        let start = changes[idx].saturating_sub(context);
+        let mut last = changes[idx];
+        while idx + 1 < changes.len() && changes[idx + 1] <= last + 2 * context + 1 {
+            idx += 1;
+            last = changes[idx];
+        }
+        let end = (last + context + 1).min(ops.len());
+        idx += 1;
+
+        let hunk = &ops[start..end];
+        let old_count = hunk.iter().filter(|(k, _)| *k != DiffKind::Added).count();
+        let new_count = hunk.iter().filter(|(k, _)| *k != DiffKind::Removed).count();
+        let (old_start, new_start) = positions[start];
+        lines.push(DiffLine {
+            kind: DiffKind::Header,
+            text: format!(
+                "@@ -{},{} +{},{} @@",
+                if old_count == 0 {
+                    old_start - 1
+                } else {
+                    old_start
+                },
+                old_count,
+                if new_count == 0 {
+                    new_start - 1
+                } else {
+                    new_start
+                },
+                new_count
+            ),
+        });
+        lines.extend(hunk.iter().map(|(kind, text)| DiffLine {
+            kind: *kind,
+            text: text.to_string(),
+        }));
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(lines: &[DiffLine]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| match line.kind {
+                DiffKind::Header => line.text.clone(),
+                DiffKind::Context => format!(" {}", line.text),
+                DiffKind::Added => format!("+{}", line.text),
+                DiffKind::Removed => format!("-{}", line.text),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_unified_diff_hunks() {
+        let old: String = (1..=20).map(|n| format!("line {}\n", n)).collect();
+        let new = old
+            .replace("line 2\n", "line two\n")
+            .replace("line 15\n", "")
+            .replace("line 20\n", "line 20\nline 21\n");
+        assert_eq!(
+            render(&unified_diff(&old, &new, 3)),
+            vec![
+                "@@ -1,5 +1,5 @@",
+                " line 1",
+                "-line 2",
+                "+line two",
+                " line 3",
+                " line 4",
+                " line 5",
+                "@@ -12,9 +12,9 @@",
+                " line 12",
+                " line 13",
+                " line 14",
+                "-line 15",
+                " line 16",
+                " line 17",
+                " line 18",
+                " line 19",
+                " line 20",
+                "+line 21",
+            ]
+        );
+        assert!(unified_diff(&old, &old, 3).is_empty());
+        assert_eq!(
+            render(&unified_diff("", "a\nb\n", 3)),
+            vec!["@@ -0,0 +1,2 @@", "+a", "+b"]
+        );
+    }
+
+    #[test]
+    fn test_proposed_edit_against_disk() {
+        let dir = std::env::temp_dir().join(format!("tmux-claude-diff-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "fn a() {}\nfn b() {}\nfn a() {}\n").unwrap();
+
+        let edit = serde_json::json!({
+            "file_path": "lib.rs",
+            "old_string": "fn a() {}",
+            "new_string": "fn c() {}",
+        });
+        let proposed = proposed_edit("Edit", &edit, Some(&dir)).unwrap();
+        assert_eq!(proposed.new, "fn c() {}\nfn b() {}\nfn a() {}\n");
+        assert_eq!(proposed.note, None);
+
+        let multi = serde_json::json!({
+            "file_path": dir.join("lib.rs"),
+            "edits": [
+                {"old_string": "fn a() {}", "new_string": "fn c() {}", "replace_all": true},
+                {"old_string": "fn b", "new_string": "fn d"},
+            ],
+        });
+        let proposed = proposed_edit("MultiEdit", &multi, Some(&dir)).unwrap();
+        assert_eq!(proposed.new, "fn c() {}\nfn d() {}\nfn c() {}\n");
+
+        // Stale edits and remote sessions fall back to the tool's own strings
+        let stale = serde_json::json!({
+            "file_path": "lib.rs",
+            "old_string": "fn z() {}",
+            "new_string": "fn y() {}",
+        });
+        let proposed = proposed_edit("Edit", &stale, Some(&dir)).unwrap();
+        assert_eq!(
+            (proposed.old.as_str(), proposed.new.as_str()),
+            ("fn z() {}", "fn y() {}")
+        );
+        assert!(proposed.note.unwrap().starts_with("text not found"));
+        assert!(proposed_edit("Edit", &edit, None).unwrap().note.is_some());
+
+        let write = serde_json::json!({"file_path": "new.rs", "content": "x\n"});
+        let proposed = proposed_edit("Write", &write, Some(&dir)).unwrap();
+        assert_eq!(
+            (proposed.old.as_str(), proposed.note.as_deref()),
+            ("", Some("new file"))
+        );
+        assert_eq!(proposed_edit("Bash", &write, Some(&dir)), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
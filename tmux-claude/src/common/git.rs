@@ -0,0 +1,138 @@
+//! Git helpers: running git and summarizing a working tree's status.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+/// Repository state of a working directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct GitStatus {
+    /// Checked out branch (None when HEAD is detached)
+    pub branch: Option<String>,
+    /// Upstream branch, if one is configured
+    pub upstream: Option<String>,
+    /// Commits ahead of / behind the upstream
+    pub ahead: u32,
+    pub behind: u32,
+    /// Changed, unmerged and untracked paths
+    pub dirty: usize,
+    /// Subject of the HEAD commit (None in a repo without commits)
+    pub last_commit: Option<String>,
+}
+
+impl GitStatus {
+    /// Compact form for the session list, e.g. "main*3↑1↓2"
+    pub fn badge(&self) -> String {
+        let mut badge = self
+            .branch
+            .clone()
+            .unwrap_or_else(|| "detached".to_string());
+        if self.dirty > 0 {
+            badge.push_str(&format!("*{}", self.dirty));
+        }
+        if self.ahead > 0 {
+            badge.push_str(&format!("↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            badge.push_str(&format!("↓{}", self.behind));
+        }
+        badge
+    }
+}
+
+/// Run git in `dir`, failing with its stderr
+pub fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Parse `git status --porcelain=v2 --branch` output (without the commit subject)
+pub fn parse_porcelain_v2(output: &str) -> GitStatus {
+    let mut status = GitStatus::default();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value != "(detached)" => status.branch = Some(value.to_string()),
+                "branch.upstream" => status.upstream = Some(value.to_string()),
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        } else if matches!(line.chars().next(), Some('1' | '2' | 'u' | '?')) {
+            status.dirty += 1;
+        }
+    }
+    status
+}
+
+/// Status of the repository containing `dir` (None if it isn't in one)
+pub fn git_status(dir: &Path) -> Option<GitStatus> {
+    // No optional locks: never contend with the agent's own git commands
+    let output = git(
+        dir,
+        &[
+            "--no-optional-locks",
+            "status",
+            "--porcelain=v2",
+            "--branch",
+        ],
+    )
+    .ok()?;
+    let mut status = parse_porcelain_v2(&output);
+    status.last_commit = git(dir, &["log", "-1", "--format=%s"])
+        .ok()
+        .filter(|s| !s.is_empty());
+    Some(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_porcelain_v2() {
+        let output = "# branch.oid 1234abcd\n\
+                      # branch.head feat/login\n\
+                      # branch.upstream origin/feat/login\n\
+                      # branch.ab +2 -1\n\
+                      1 .M N... 100644 100644 100644 abc abc src/main.rs\n\
+                      2 R. N... 100644 100644 100644 abc abc R100 new.rs\told.rs\n\
+                      u UU N... 100644 100644 100644 100644 a b c conflict.rs\n\
+                      ? notes.txt\n\
+                      ! target/\n";
+        let status = parse_porcelain_v2(output);
+        assert_eq!(status.branch.as_deref(), Some("feat/login"));
+        assert_eq!(status.upstream.as_deref(), Some("origin/feat/login"));
+        assert_eq!((status.ahead, status.behind), (2, 1));
+        assert_eq!(status.dirty, 4);
+        assert_eq!(status.badge(), "feat/login*4↑2↓1");
+    }
+
+    #[test]
+    fn test_detached_clean_badge() {
+        let status = parse_porcelain_v2("# branch.oid 1234abcd\n# branch.head (detached)\n");
+        assert_eq!(status.branch, None);
+        assert_eq!(status.badge(), "detached");
+    }
+}
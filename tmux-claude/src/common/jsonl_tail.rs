@@ -0,0 +1,354 @@
+//! Incremental reading of Claude transcripts.
+//!
+//! Every transcript (one JSONL file per Claude session) has a cursor: its inode
+//! and how many bytes were consumed. A round reads only the complete lines
+//! appended since, starting over when a file was replaced or truncated. File
+//! notifications (inotify, kqueue/FSEvents via `notify`) name the files to look
+//! at; without them, or after a missed event, every file is stat'ed instead.
+
+use anyhow::{Context, Result};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How often every transcript is stat'ed even when notifications work (catches missed events)
+const FULL_SCAN_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Quiet time after a notification before reading (Claude writes a response in bursts)
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Where reading stopped in one transcript
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TailCursor {
+    /// Inode of the file read (0 = unknown)
+    pub inode: u64,
+    /// File size when last read
+    pub size: u64,
+    /// Bytes consumed (always at a line boundary)
+    pub offset: u64,
+}
+
+impl TailCursor {
+    /// Whether a file with this metadata may have lines the cursor hasn't seen
+    fn is_behind(&self, meta: &fs::Metadata) -> bool {
+        (self.inode != 0 && self.inode != meta.ino()) || self.size != meta.len()
+    }
+}
+
+/// Entries appended to one transcript
+#[derive(Debug, Clone)]
+pub struct TailUpdate {
+    pub path: PathBuf,
+    /// Parsed JSONL entries, oldest first (unparseable lines are skipped)
+    pub entries: Vec<serde_json::Value>,
+    /// Read from the start: the file is new, was replaced or was truncated
+    pub reset: bool,
+    pub cursor: TailCursor,
+}
+
+/// What one round of `JsonlTailer::read_changes` found
+#[derive(Debug, Default)]
+pub struct TailRound {
+    pub updates: Vec<TailUpdate>,
+    /// Transcripts that were followed and no longer exist
+    pub removed: Vec<PathBuf>,
+}
+
+impl TailRound {
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Read the complete lines appended to `path` after `cursor`
+pub fn read_appended(path: &Path, cursor: TailCursor) -> Result<TailUpdate> {
+    let mut file =
+        fs::File::open(path).with_context(|| format!("Cannot open {}", path.display()))?;
+    let meta = file.metadata()?;
+    let replaced = cursor.inode != 0 && cursor.inode != meta.ino();
+    let start = if replaced || meta.len() < cursor.offset {
+        0
+    } else {
+        cursor.offset
+    };
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    // A line still being written is picked up next round
+    let complete = bytes.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1);
+
+    let entries = String::from_utf8_lossy(&bytes[..complete])
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    Ok(TailUpdate {
+        path: path.to_path_buf(),
+        entries,
+        reset: start == 0,
+        cursor: TailCursor {
+            inode: meta.ino(),
+            size: start + bytes.len() as u64,
+            offset: start + complete as u64,
+        },
+    })
+}
+
+/// All `*.jsonl` files one level below the projects directory, with their metadata
+fn list_transcripts(root: &Path) -> Vec<(PathBuf, fs::Metadata)> {
+    let Ok(projects) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    projects
+        .filter_map(|e| e.ok())
+        .filter_map(|project| fs::read_dir(project.path()).ok())
+        .flatten()
+        .filter_map(|e| e.ok())
+        .filter(|e| is_transcript(&e.path()))
+        .filter_map(|e| Some((e.path(), e.metadata().ok()?)))
+        .collect()
+}
+
+fn is_transcript(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "jsonl")
+}
+
+/// Follows every transcript under `~/.claude/projects`
+pub struct JsonlTailer {
+    root: PathBuf,
+    cursors: HashMap<PathBuf, TailCursor>,
+    /// Whether to use file notifications (retried on full scans until it works)
+    watch: bool,
+    /// Kept alive for notifications to keep coming (None = full scan every round)
+    watcher: Option<RecommendedWatcher>,
+    events: Option<Receiver<notify::Result<Event>>>,
+    /// Transcripts named by notifications since the last round
+    pending: HashSet<PathBuf>,
+    /// Stat everything next round (first round, or a notification was lost)
+    rescan: bool,
+    last_full_scan: Option<Instant>,
+}
+
+impl JsonlTailer {
+    /// Follow the transcripts under `root`, resuming from `cursors`
+    pub fn new(root: PathBuf, cursors: HashMap<PathBuf, TailCursor>, watch: bool) -> Self {
+        let mut tailer = Self {
+            root,
+            cursors,
+            watch,
+            watcher: None,
+            events: None,
+            pending: HashSet::new(),
+            rescan: true,
+            last_full_scan: None,
+        };
+        tailer.start_watcher();
+        tailer
+    }
+
+    fn start_watcher(&mut self) {
+        if !self.watch || self.watcher.is_some() || !self.root.is_dir() {
+            return;
+        }
+        let (tx, rx) = channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .and_then(|mut watcher| {
+            watcher.watch(&self.root, RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
+        if let Ok(watcher) = watcher {
+            self.watcher = Some(watcher);
+            self.events = Some(rx);
+        }
+    }
+
+    fn handle_event(&mut self, event: notify::Result<Event>) {
+        match event {
+            Ok(event) if event.need_rescan() => self.rescan = true,
+            Ok(event) => {
+                // Only `<project>/<session>.jsonl`, not subagent files further down
+                let root = &self.root;
+                self.pending.extend(event.paths.into_iter().filter(|path| {
+                    is_transcript(path) && path.parent().and_then(Path::parent) == Some(root)
+                }))
+            }
+            Err(_) => self.rescan = true,
+        }
+    }
+
+    fn drain_events(&mut self) {
+        let Some(events) = self.events.take() else {
+            return;
+        };
+        while let Ok(event) = events.try_recv() {
+            self.handle_event(event);
+        }
+        self.events = Some(events);
+    }
+
+    /// Block until a transcript changes or `timeout` passes (just sleep without
+    /// notifications), then let a burst of writes settle
+    pub fn wait(&mut self, timeout: Duration) {
+        let Some(events) = self.events.take() else {
+            std::thread::sleep(timeout);
+            return;
+        };
+        let mut next = events.recv_timeout(timeout);
+        while let Ok(event) = next {
+            self.handle_event(event);
+            next = events.recv_timeout(SETTLE_TIME);
+        }
+        if matches!(next, Err(RecvTimeoutError::Disconnected)) {
+            // Watcher died: fall back to full scans until it can be restarted
+            self.watcher = None;
+            self.rescan = true;
+            return;
+        }
+        self.events = Some(events);
+    }
+
+    /// Read what was appended to changed transcripts, at most `limit` files
+    /// (most recently modified first; the rest are read next round)
+    pub fn read_changes(&mut self, limit: usize) -> TailRound {
+        self.drain_events();
+        let full_scan = self.rescan
+            || self.watcher.is_none()
+            || self
+                .last_full_scan
+                .is_none_or(|at| at.elapsed() >= FULL_SCAN_INTERVAL);
+
+        let mut round = TailRound::default();
+        let mut changed: Vec<(PathBuf, SystemTime)> = Vec::new();
+        if full_scan {
+            self.start_watcher();
+            let files = list_transcripts(&self.root);
+            let existing: HashSet<&PathBuf> = files.iter().map(|(path, _)| path).collect();
+            round.removed = self
+                .cursors
+                .keys()
+                .filter(|path| !existing.contains(path))
+                .cloned()
+                .collect();
+            for (path, meta) in &files {
+                if self.cursors.get(path).is_none_or(|c| c.is_behind(meta)) {
+                    changed.push((
+                        path.clone(),
+                        meta.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    ));
+                }
+            }
+            self.pending.clear();
+            self.rescan = false;
+            self.last_full_scan = Some(Instant::now());
+        } else {
+            for path in std::mem::take(&mut self.pending) {
+                match fs::metadata(&path) {
+                    Ok(meta) if self.cursors.get(&path).is_none_or(|c| c.is_behind(&meta)) => {
+                        changed.push((path, meta.modified().unwrap_or(SystemTime::UNIX_EPOCH)));
+                    }
+                    Ok(_) => {}
+                    Err(_) if self.cursors.contains_key(&path) => round.removed.push(path),
+                    Err(_) => {}
+                }
+            }
+        }
+        for path in &round.removed {
+            self.cursors.remove(path);
+        }
+
+        changed.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+        for (path, _) in changed.drain(limit.min(changed.len())..) {
+            self.pending.insert(path);
+        }
+        for (path, _) in changed {
+            let cursor = self.cursors.get(&path).copied().unwrap_or_default();
+            if let Ok(update) = read_appended(&path, cursor) {
+                self.cursors.insert(path, update.cursor);
+                round.updates.push(update);
+            }
+        }
+        round
+    }
+
+    /// Whether files are left over from a round that hit its limit
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_appended_resumes_and_restarts() {
+        let dir = std::env::temp_dir().join(format!("tmux-claude-tail-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("abc.jsonl");
+        fs::write(&path, "{\"n\":1}\n{\"n\":").unwrap();
+
+        let first = read_appended(&path, TailCursor::default()).unwrap();
+        // The partial second line is left for the next read
+        assert_eq!((first.entries.len(), first.reset), (1, true));
+        assert_eq!(first.cursor.offset, 8);
+
+        fs::write(&path, "{\"n\":1}\n{\"n\":2}\n").unwrap();
+        let second = read_appended(&path, first.cursor).unwrap();
+        assert_eq!((second.entries.len(), second.reset), (1, false));
+        assert_eq!(second.entries[0]["n"], 2);
+
+        // Replaced by a new file (new inode): read from the start
+        let tmp = dir.join("abc.tmp");
+        fs::write(&tmp, "{\"n\":3}\n{\"n\":4}\n{\"n\":5}\n").unwrap();
+        fs::rename(&tmp, &path).unwrap();
+        let third = read_appended(&path, second.cursor).unwrap();
+        assert_eq!((third.entries.len(), third.reset), (3, true));
+
+        // Truncated below the offset: read from the start
+        fs::write(&path, "{\"n\":6}\n").unwrap();
+        let cursor = TailCursor {
+            inode: 0,
+            ..third.cursor
+        };
+        let fourth = read_appended(&path, cursor).unwrap();
+        assert_eq!((fourth.entries.len(), fourth.reset), (1, true));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rounds_read_changed_files_newest_first() {
+        let root = std::env::temp_dir().join(format!("tmux-claude-tailer-{}", std::process::id()));
+        let project = root.join("-work-api");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join("a.jsonl"), "{}\n").unwrap();
+        fs::write(project.join("b.jsonl"), "{}\n{}\n").unwrap();
+
+        let mut tailer = JsonlTailer::new(root.clone(), HashMap::new(), false);
+        let round = tailer.read_changes(1);
+        assert_eq!(round.updates.len(), 1);
+        assert!(tailer.has_pending());
+        let round = tailer.read_changes(10);
+        assert_eq!(round.updates.len(), 1);
+        assert!(tailer.read_changes(10).is_empty());
+
+        // Resumed from saved cursors: only the appended line is read
+        let cursors = tailer.cursors.clone();
+        fs::write(project.join("a.jsonl"), "{}\n{\"n\":2}\n").unwrap();
+        let mut tailer = JsonlTailer::new(root.clone(), cursors, false);
+        let round = tailer.read_changes(10);
+        assert_eq!(round.updates.len(), 1);
+        assert_eq!(round.updates[0].entries, vec![serde_json::json!({"n": 2})]);
+
+        fs::remove_file(project.join("b.jsonl")).unwrap();
+        let round = tailer.read_changes(10);
+        assert_eq!(round.removed, vec![project.join("b.jsonl")]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
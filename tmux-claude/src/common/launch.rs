@@ -0,0 +1,372 @@
+//! Start new Claude sessions in fresh tmux sessions (optionally in a git worktree).
+
+use crate::common::config::{expand_home, load_config};
+use crate::common::persistence::{load_worktrees, save_worktrees};
+use crate::common::tmux::{get_server_sessions, TmuxServer};
+use crate::common::types::TmuxSession;
+use crate::common::worktree::{create_worktree, SessionWorktree};
+use crate::ipc::messages::TmuxLocation;
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What to start and where
+#[derive(Debug, Clone, Default)]
+pub struct LaunchRequest {
+    /// Directory, or the name of a sesh project
+    pub target: String,
+    /// tmux session name (default: directory name, or `<repo>-<branch>` for worktrees)
+    pub name: Option<String>,
+    /// Initial prompt passed to `claude`
+    pub prompt: Option<String>,
+    /// Run in a git worktree for this branch (created under the worktree root if missing)
+    pub worktree: Option<String>,
+}
+
+impl LaunchRequest {
+    /// Parse the TUI's "<dir|project>[@branch] [prompt...]" input
+    pub fn from_input(input: &str) -> Option<Self> {
+        let input = input.trim();
+        let (target, prompt) = match input.split_once(char::is_whitespace) {
+            Some((target, prompt)) => (target, Some(prompt.trim().to_string())),
+            None => (input, None),
+        };
+        let (target, worktree) = match target.split_once('@') {
+            Some((target, branch)) if !branch.is_empty() => (target, Some(branch.to_string())),
+            _ => (target, None),
+        };
+        if target.is_empty() {
+            return None;
+        }
+        Some(Self {
+            target: target.to_string(),
+            prompt: prompt.filter(|p| !p.is_empty()),
+            worktree,
+            ..Default::default()
+        })
+    }
+}
+
+/// A freshly started Claude session
+#[derive(Debug, Clone)]
+pub struct LaunchedSession {
+    /// tmux session name
+    pub session: String,
+    /// Working directory Claude runs in
+    pub cwd: PathBuf,
+    /// Where the Claude pane lives
+    pub location: TmuxLocation,
+    /// Worktree the session runs in, if one was requested
+    pub worktree: Option<SessionWorktree>,
+}
+
+/// Make a name usable as a tmux session name (tmux rejects '.' and ':')
+pub fn sanitize_session_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| match c {
+            '.' | ':' => '_',
+            c if c.is_whitespace() => '-',
+            c => c,
+        })
+        .collect()
+}
+
+/// Quote a string for POSIX shells
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// Command line typed into the new pane
+pub fn claude_command(prompt: Option<&str>) -> String {
+    match prompt.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(prompt) => format!("claude {}", shell_quote(prompt)),
+        None => "claude".to_string(),
+    }
+}
+
+/// Command line that resumes an earlier conversation
+pub fn claude_resume_command(session_id: &str) -> String {
+    format!("claude --resume {}", shell_quote(session_id))
+}
+
+/// The tmux session working in `cwd`: one whose first pane is there, else any with a pane there
+pub fn session_for_cwd(sessions: &[TmuxSession], cwd: &Path) -> Option<String> {
+    let in_cwd = |pane_cwd: &str| Path::new(pane_cwd) == cwd;
+    let first_pane = sessions.iter().find(|s| {
+        s.windows
+            .first()
+            .and_then(|w| w.panes.first())
+            .is_some_and(|p| in_cwd(&p.cwd))
+    });
+    first_pane
+        .or_else(|| {
+            sessions.iter().find(|s| {
+                s.windows
+                    .iter()
+                    .flat_map(|w| &w.panes)
+                    .any(|p| in_cwd(&p.cwd))
+            })
+        })
+        .map(|s| s.name.clone())
+}
+
+/// Find a project's path in `sesh list --config --json` output
+pub fn find_sesh_project_path(json: &str, name: &str) -> Option<PathBuf> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(json).ok()?;
+    entries.iter().find_map(|entry| {
+        let field = |key: &str, alt: &str| {
+            entry
+                .get(key)
+                .or_else(|| entry.get(alt))
+                .and_then(|v| v.as_str())
+        };
+        if field("Name", "name")? != name {
+            return None;
+        }
+        field("Path", "path")
+            .filter(|p| !p.is_empty())
+            .map(|p| expand_home(Path::new(p)))
+    })
+}
+
+/// Resolve a directory or sesh project name to a directory
+fn resolve_target(target: &str) -> Result<PathBuf> {
+    let path = expand_home(Path::new(target));
+    if path.is_dir() {
+        return path
+            .canonicalize()
+            .with_context(|| format!("Cannot resolve '{}'", target));
+    }
+    let sesh_json = Command::new("sesh")
+        .args(["list", "--config", "--json"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string())
+        .unwrap_or_default();
+    match find_sesh_project_path(&sesh_json, target) {
+        Some(path) if path.is_dir() => Ok(path),
+        Some(path) => bail!(
+            "sesh project '{}' points to missing {}",
+            target,
+            path.display()
+        ),
+        None => bail!("'{}' is neither a directory nor a sesh project", target),
+    }
+}
+
+/// Create the tmux session and start Claude in its first pane
+pub fn launch_session(request: &LaunchRequest) -> Result<LaunchedSession> {
+    let mut cwd = resolve_target(&request.target)?;
+    let worktree = match request.worktree.as_deref() {
+        Some(branch) => Some(create_worktree(
+            &cwd,
+            branch,
+            load_config().worktree_root().as_deref(),
+        )?),
+        None => None,
+    };
+    if let Some(ref worktree) = worktree {
+        cwd = worktree.path.clone();
+    }
+
+    let default_name = cwd
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| request.target.clone());
+    let session = sanitize_session_name(request.name.as_deref().unwrap_or(&default_name));
+    if session.is_empty() {
+        bail!("Session name is empty");
+    }
+
+    let server = TmuxServer::Current;
+    let exists = server
+        .command()
+        .args(["has-session", "-t", &format!("={}", session)])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if exists {
+        bail!("tmux session '{}' already exists", session);
+    }
+
+    let location = spawn_pane(&server, &["new-session", "-d", "-s", &session], &cwd)?;
+    type_command(
+        &server,
+        &location.pane_id,
+        &claude_command(request.prompt.as_deref()),
+    );
+
+    // Remember the worktree so park/kill can offer to remove it
+    if let Some(ref worktree) = worktree {
+        let mut worktrees = load_worktrees();
+        worktrees.insert(session.clone(), worktree.clone());
+        save_worktrees(&worktrees);
+    }
+
+    Ok(LaunchedSession {
+        session,
+        cwd,
+        location,
+        worktree,
+    })
+}
+
+/// Resume conversation `session_id` in a new window of the tmux session working in
+/// `cwd`, or in a new session named after the directory if there is none
+pub fn resume_session(cwd: &Path, session_id: &str) -> Result<LaunchedSession> {
+    if !cwd.is_dir() {
+        bail!("{} no longer exists", cwd.display());
+    }
+    let server = TmuxServer::Current;
+    let sessions = get_server_sessions(&server).unwrap_or_default();
+    let (session, location) = match session_for_cwd(&sessions, cwd) {
+        Some(session) => {
+            let target = format!("={}:", session);
+            let location = spawn_pane(&server, &["new-window", "-t", &target], cwd)?;
+            (session, location)
+        }
+        None => {
+            let base = cwd
+                .file_name()
+                .map(|n| sanitize_session_name(&n.to_string_lossy()))
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| "claude".to_string());
+            // The directory's name may be taken by a session working elsewhere
+            let session = (1..)
+                .map(|i| {
+                    if i == 1 {
+                        base.clone()
+                    } else {
+                        format!("{}-{}", base, i)
+                    }
+                })
+                .find(|name| !sessions.iter().any(|s| &s.name == name))
+                .unwrap_or(base);
+            let location = spawn_pane(&server, &["new-session", "-d", "-s", &session], cwd)?;
+            (session, location)
+        }
+    };
+    type_command(
+        &server,
+        &location.pane_id,
+        &claude_resume_command(session_id),
+    );
+    Ok(LaunchedSession {
+        session,
+        cwd: cwd.to_path_buf(),
+        location,
+        worktree: None,
+    })
+}
+
+/// Run a tmux command that creates a pane in `cwd` (`new-session`/`new-window`) and locate it
+fn spawn_pane(server: &TmuxServer, args: &[&str], cwd: &Path) -> Result<TmuxLocation> {
+    let output = server
+        .command()
+        .args(args)
+        .arg("-c")
+        .arg(cwd)
+        .args(["-P", "-F", "#{pane_id}\t#{socket_path}"])
+        .output()
+        .context("Failed to run tmux")?;
+    if !output.status.success() {
+        bail!(
+            "tmux {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let printed = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let Some((pane_id, socket)) = printed.split_once('\t') else {
+        bail!("Unexpected tmux output: {}", printed);
+    };
+    Ok(TmuxLocation {
+        socket: socket.to_string(),
+        pane_id: pane_id.to_string(),
+    })
+}
+
+/// Type a command into a pane's shell, so the pane survives Claude exiting
+fn type_command(server: &TmuxServer, pane_id: &str, command: &str) {
+    let _ = server
+        .command()
+        .args(["send-keys", "-t", pane_id, "-l", command])
+        .output();
+    let _ = server
+        .command()
+        .args(["send-keys", "-t", pane_id, "Enter"])
+        .output();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_session_name() {
+        assert_eq!(sanitize_session_name("my.app:v2"), "my_app_v2");
+        assert_eq!(sanitize_session_name(" two words "), "two-words");
+    }
+
+    #[test]
+    fn test_request_from_input() {
+        let request = LaunchRequest::from_input(" ~/api  fix the\nflaky test ").unwrap();
+        assert_eq!(request.target, "~/api");
+        assert_eq!(request.prompt.as_deref(), Some("fix the\nflaky test"));
+        assert_eq!(LaunchRequest::from_input("web").unwrap().prompt, None);
+        assert!(LaunchRequest::from_input("  ").is_none());
+
+        let request = LaunchRequest::from_input("~/api@feat/login add tests").unwrap();
+        assert_eq!(request.target, "~/api");
+        assert_eq!(request.worktree.as_deref(), Some("feat/login"));
+        assert_eq!(request.prompt.as_deref(), Some("add tests"));
+    }
+
+    #[test]
+    fn test_claude_command_quotes_prompt() {
+        assert_eq!(claude_command(None), "claude");
+        assert_eq!(claude_command(Some("  ")), "claude");
+        assert_eq!(
+            claude_command(Some("fix the user's bug")),
+            "claude 'fix the user'\\''s bug'"
+        );
+    }
+
+    #[test]
+    fn test_session_for_cwd_prefers_first_pane() {
+        let sessions = crate::common::tmux::parse_pane_list(
+            "tools\t0\tsh\t0\t%1\t10\t/home\n\
+             tools\t1\tsh\t0\t%2\t11\t/work/api\n\
+             api\t0\tsh\t0\t%3\t12\t/work/api\n",
+        );
+        assert_eq!(
+            session_for_cwd(&sessions, Path::new("/work/api")).as_deref(),
+            Some("api")
+        );
+        assert_eq!(
+            session_for_cwd(&sessions[..1], Path::new("/home")).as_deref(),
+            Some("tools")
+        );
+        assert_eq!(session_for_cwd(&sessions, Path::new("/work/web")), None);
+        assert_eq!(
+            claude_resume_command("abc-123"),
+            "claude --resume 'abc-123'"
+        );
+    }
+
+    #[test]
+    fn test_find_sesh_project_path() {
+        let json = r#"[
+            {"Src":"config","Name":"api","Path":"/work/api","Score":0},
+            {"Src":"config","Name":"web","Path":"~/web","Score":0}
+        ]"#;
+        assert_eq!(
+            find_sesh_project_path(json, "api"),
+            Some(PathBuf::from("/work/api"))
+        );
+        let web = find_sesh_project_path(json, "web").unwrap();
+        assert!(web.ends_with("web") && !web.starts_with("~"));
+        assert_eq!(find_sesh_project_path(json, "docs"), None);
+        assert_eq!(find_sesh_project_path("not json", "api"), None);
+    }
+}
@@ -1,10 +1,23 @@
 //! Common types and utilities shared between TUI and daemon.
 
-pub mod types;
-pub mod tmux;
+pub mod agent_todos;
+pub mod api_error;
+pub mod chrome;
+pub mod config;
+pub mod debug;
+pub mod diff;
+pub mod git;
 pub mod jsonl;
+pub mod jsonl_tail;
+pub mod launch;
 pub mod persistence;
-pub mod debug;
-pub mod process;
 pub mod ports;
-pub mod chrome;
+pub mod process;
+pub mod risk;
+pub mod status_line;
+pub mod tmux;
+pub mod transcript;
+pub mod transcript_index;
+pub mod types;
+pub mod usage;
+pub mod worktree;
@@ -1,10 +1,13 @@
 //! File persistence for parked sessions, todos, and session restore.
 
+use crate::common::tmux::TmuxServer;
+use crate::common::worktree::SessionWorktree;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{BufRead, BufReader, Write};
 use std::path::PathBuf;
 use std::process::Command;
+use std::time::SystemTime;
 
 /// Escape newlines for single-line file storage
 fn escape_newlines(s: &str) -> String {
@@ -122,6 +125,73 @@ pub fn save_session_todos(todos: &HashMap<String, Vec<String>>) {
     }
 }
 
+/// Get the path to the session worktrees file
+pub fn get_worktrees_file_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|p| p.join("tmux-claude").join("worktrees.txt"))
+}
+
+/// When the worktrees file last changed (None if it doesn't exist)
+pub fn worktrees_modified() -> Option<SystemTime> {
+    let path = get_worktrees_file_path()?;
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Load session worktrees from disk (name -> worktree)
+pub fn load_worktrees() -> HashMap<String, SessionWorktree> {
+    let Some(path) = get_worktrees_file_path() else {
+        return HashMap::new();
+    };
+    let Ok(file) = fs::File::open(&path) else {
+        return HashMap::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter_map(|line| {
+            // Format: "session-name\tbranch\trepo\tworktree-path\tserver"
+            // (no server field = written before multi-server support)
+            let mut fields = line.splitn(5, '\t');
+            let name = fields.next()?;
+            let branch = fields.next()?;
+            let repo = fields.next()?;
+            let path = fields.next()?;
+            let server = fields.next().unwrap_or_default();
+            Some((
+                name.to_string(),
+                SessionWorktree {
+                    repo: PathBuf::from(repo),
+                    branch: branch.to_string(),
+                    path: PathBuf::from(path),
+                    server: TmuxServer::from_spec(server),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Save session worktrees to disk (tab-separated: name\tbranch\trepo\tpath\tserver)
+pub fn save_worktrees(worktrees: &HashMap<String, SessionWorktree>) {
+    let Some(path) = get_worktrees_file_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(mut file) = fs::File::create(&path) {
+        for (name, worktree) in worktrees {
+            let _ = writeln!(
+                file,
+                "{}\t{}\t{}\t{}\t{}",
+                name,
+                worktree.branch,
+                worktree.repo.display(),
+                worktree.path.display(),
+                worktree.server.to_spec()
+            );
+        }
+    }
+}
+
 /// Get the path to the restore file for session persistence across restarts
 pub fn get_restore_file_path() -> Option<PathBuf> {
     dirs::cache_dir().map(|p| p.join("tmux-claude").join("restore.txt"))
@@ -305,9 +375,7 @@ pub fn get_global_mute_path() -> Option<PathBuf> {
 
 /// Check if global mute is enabled (file existence)
 pub fn is_globally_muted() -> bool {
-    get_global_mute_path()
-        .map(|p| p.exists())
-        .unwrap_or(false)
+    get_global_mute_path().map(|p| p.exists()).unwrap_or(false)
 }
 
 /// Set global mute state (creates or removes the flag file)
@@ -1,7 +1,9 @@
 //! Process detection and resource monitoring.
 
-use crate::common::types::ProcessInfo;
-use sysinfo::{Pid, System};
+use crate::common::types::{ProcessInfo, TmuxSession};
+use std::collections::{HashMap, HashSet};
+use std::process::Command;
+use sysinfo::{Pid, Signal, System};
 
 /// Check if a process is Claude Code based on name/command
 pub fn is_claude_process(proc: &ProcessInfo) -> bool {
@@ -52,6 +54,29 @@ pub fn get_all_descendants(sys: &System, parent_pid: u32, descendants: &mut Vec<
     }
 }
 
+/// Whether Claude runs in the pane whose shell is `pane_pid` (or as that process)
+pub fn pane_runs_claude(sys: &System, pane_pid: u32) -> bool {
+    let mut pids = vec![pane_pid];
+    get_all_descendants(sys, pane_pid, &mut pids);
+    pids.iter().any(|&pid| {
+        get_process_info(sys, pid)
+            .map(|info| is_claude_process(&info))
+            .unwrap_or(false)
+    })
+}
+
+/// Get all PIDs in a tmux session (pane shells plus their descendants)
+pub fn get_session_pids(sys: &System, session: &TmuxSession) -> Vec<u32> {
+    let mut pids = Vec::new();
+    for window in &session.windows {
+        for pane in &window.panes {
+            pids.push(pane.pid);
+            get_all_descendants(sys, pane.pid, &mut pids);
+        }
+    }
+    pids
+}
+
 /// Get process info from sysinfo
 pub fn get_process_info(sys: &System, pid: u32) -> Option<ProcessInfo> {
     sys.process(Pid::from_u32(pid)).map(|p| {
@@ -64,6 +89,7 @@ pub fn get_process_info(sys: &System, pid: u32) -> Option<ProcessInfo> {
 
         ProcessInfo {
             pid,
+            parent_pid: p.parent().map(|pp| pp.as_u32()),
             name: p.name().to_string_lossy().to_string(),
             cpu_percent: p.cpu_usage(),
             memory_kb: p.memory() / 1024,
@@ -72,6 +98,109 @@ pub fn get_process_info(sys: &System, pid: u32) -> Option<ProcessInfo> {
     })
 }
 
+/// A row of the flattened process tree shown in the detail view
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessTreeRow {
+    /// Index into the processes slice the tree was built from
+    pub index: usize,
+    /// Nesting depth (0 = root)
+    pub depth: usize,
+    pub has_children: bool,
+}
+
+/// Flatten processes into depth-first tree order using their parent links.
+/// Siblings keep their input order. Processes whose parent is not in the list
+/// become roots, and children of PIDs in `collapsed` are hidden.
+pub fn flatten_process_tree(
+    processes: &[ProcessInfo],
+    collapsed: &HashSet<u32>,
+) -> Vec<ProcessTreeRow> {
+    let pids: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    let mut children: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (i, proc) in processes.iter().enumerate() {
+        match proc.parent_pid {
+            Some(ppid) if ppid != proc.pid && pids.contains(&ppid) => {
+                children.entry(ppid).or_default().push(i)
+            }
+            _ => roots.push(i),
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut visited = HashSet::new();
+    // Reverse so the first sibling is popped first
+    let mut stack: Vec<(usize, usize)> = roots.into_iter().rev().map(|i| (i, 0)).collect();
+    while let Some((i, depth)) = stack.pop() {
+        let pid = processes[i].pid;
+        if !visited.insert(pid) {
+            continue;
+        }
+        let kids = children.get(&pid);
+        rows.push(ProcessTreeRow {
+            index: i,
+            depth,
+            has_children: kids.is_some(),
+        });
+        if let Some(kids) = kids {
+            if !collapsed.contains(&pid) {
+                stack.extend(kids.iter().rev().map(|&k| (k, depth + 1)));
+            }
+        }
+    }
+    rows
+}
+
+/// Keep the processes matching `keep`, plus their ancestors, so kept
+/// processes still hang under their parents in the tree
+pub fn retain_with_ancestors(
+    processes: &mut Vec<ProcessInfo>,
+    keep: impl Fn(&ProcessInfo) -> bool,
+) {
+    let parents: HashMap<u32, Option<u32>> =
+        processes.iter().map(|p| (p.pid, p.parent_pid)).collect();
+    let mut kept = HashSet::new();
+    for proc in processes.iter().filter(|p| keep(p)) {
+        let mut pid = Some(proc.pid);
+        while let Some(current) = pid {
+            if !kept.insert(current) {
+                break;
+            }
+            pid = parents.get(&current).copied().flatten();
+        }
+    }
+    processes.retain(|p| kept.contains(&p.pid));
+}
+
+/// Send a signal to a process. Returns false if the process is gone or the signal failed.
+pub fn send_signal(sys: &System, pid: u32, signal: Signal) -> bool {
+    sys.process(Pid::from_u32(pid))
+        .and_then(|p| p.kill_with(signal))
+        .unwrap_or(false)
+}
+
+/// Short name for a signal, e.g. "SIGTERM"
+pub fn signal_name(signal: Signal) -> &'static str {
+    match signal {
+        Signal::Interrupt => "SIGINT",
+        Signal::Term => "SIGTERM",
+        Signal::Kill => "SIGKILL",
+        _ => "signal",
+    }
+}
+
+/// Niceness applied by the detail view's renice action
+pub const RENICE_NICENESS: i32 = 10;
+
+/// Set a process's niceness via `renice`
+pub fn renice_process(pid: u32, niceness: i32) -> bool {
+    Command::new("renice")
+        .args([&niceness.to_string(), "-p", &pid.to_string()])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -79,6 +208,7 @@ mod tests {
     fn make_proc(name: &str, command: &str) -> ProcessInfo {
         ProcessInfo {
             pid: 1,
+            parent_pid: None,
             name: name.to_string(),
             cpu_percent: 0.0,
             memory_kb: 0,
@@ -111,4 +241,64 @@ mod tests {
         assert!(!is_claude_process(&make_proc("tmux-claude", "")));
         assert!(!is_claude_process(&make_proc("node", "tmux-claude")));
     }
+
+    fn tree_proc(pid: u32, parent_pid: Option<u32>) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            parent_pid,
+            ..make_proc("sh", "")
+        }
+    }
+
+    #[test]
+    fn test_flatten_process_tree_depth_first() {
+        // 1 -> (2 -> 4), 3; sibling order follows input order
+        let procs = vec![
+            tree_proc(2, Some(1)),
+            tree_proc(1, None),
+            tree_proc(4, Some(2)),
+            tree_proc(3, Some(1)),
+        ];
+        let rows = flatten_process_tree(&procs, &HashSet::new());
+        let order: Vec<(u32, usize)> = rows.iter().map(|r| (procs[r.index].pid, r.depth)).collect();
+        assert_eq!(order, vec![(1, 0), (2, 1), (4, 2), (3, 1)]);
+        assert!(rows[0].has_children);
+        assert!(!rows[3].has_children);
+    }
+
+    #[test]
+    fn test_flatten_process_tree_orphans_become_roots() {
+        // Parent 99 was filtered out of the list
+        let procs = vec![tree_proc(5, Some(99)), tree_proc(6, Some(5))];
+        let rows = flatten_process_tree(&procs, &HashSet::new());
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].depth, 0);
+        assert_eq!(rows[1].depth, 1);
+    }
+
+    #[test]
+    fn test_flatten_process_tree_collapsed_hides_children() {
+        let procs = vec![
+            tree_proc(1, None),
+            tree_proc(2, Some(1)),
+            tree_proc(3, Some(2)),
+        ];
+        let collapsed: HashSet<u32> = [1].into_iter().collect();
+        let rows = flatten_process_tree(&procs, &collapsed);
+        assert_eq!(rows.len(), 1);
+        assert!(rows[0].has_children);
+    }
+
+    #[test]
+    fn test_retain_with_ancestors_keeps_parents_of_busy_children() {
+        let mut procs = vec![
+            tree_proc(1, None),
+            tree_proc(2, Some(1)),
+            tree_proc(3, Some(2)),
+            tree_proc(4, Some(1)),
+        ];
+        retain_with_ancestors(&mut procs, |p| p.pid == 3);
+        let pids: Vec<u32> = procs.iter().map(|p| p.pid).collect();
+        assert_eq!(pids, vec![1, 2, 3]);
+    }
 }
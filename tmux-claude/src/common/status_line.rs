@@ -0,0 +1,267 @@
+//! Compact status summary for tmux `status-right` / `window-status-format`.
+//!
+//! The daemon writes a small snapshot file whenever session state changes;
+//! `tmux-claude status-line` only reads that file, so it stays fast enough
+//! for tmux to run every few seconds.
+
+use crate::ipc::messages::SessionStatus;
+use serde::{Deserialize, Serialize};
+
+/// Default format for the summary of all sessions
+pub const DEFAULT_SUMMARY_FORMAT: &str = "#{attention}⚠ #{waiting}⏸ #{working}⚙";
+
+/// Default format for a single tmux session
+pub const DEFAULT_SESSION_FORMAT: &str = "#{icon}";
+
+/// One Claude session as written to the snapshot file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusEntry {
+    /// tmux session the Claude pane lives in (empty if unknown)
+    pub tmux_session: String,
+    /// Remote host, for sessions pulled from another daemon
+    #[serde(default)]
+    pub host: Option<String>,
+    pub status: SessionStatus,
+}
+
+/// Session counts by status
+#[derive(Debug, Default, PartialEq)]
+pub struct StatusCounts {
+    pub permission: usize,
+    pub plan: usize,
+    pub question: usize,
+    pub waiting: usize,
+    pub working: usize,
+    /// Stalled, crashed or failing with API errors
+    pub stuck: usize,
+    /// Waiting for a rate or usage limit to reset
+    pub limited: usize,
+}
+
+impl StatusCounts {
+    /// Count entries by status
+    pub fn from_entries(entries: &[StatusEntry]) -> Self {
+        let mut counts = Self::default();
+        for entry in entries {
+            match entry.status {
+                SessionStatus::NeedsPermission { .. } | SessionStatus::EditApproval { .. } => {
+                    counts.permission += 1
+                }
+                SessionStatus::PlanReview => counts.plan += 1,
+                SessionStatus::QuestionAsked => counts.question += 1,
+                SessionStatus::Waiting => counts.waiting += 1,
+                SessionStatus::Working | SessionStatus::Unknown => counts.working += 1,
+                SessionStatus::Stalled
+                | SessionStatus::Crashed
+                | SessionStatus::ApiError { .. } => counts.stuck += 1,
+                SessionStatus::RateLimited { .. } => counts.limited += 1,
+            }
+        }
+        counts
+    }
+
+    /// Sessions blocked on the user
+    pub fn attention(&self) -> usize {
+        self.permission + self.plan + self.question + self.stuck + self.limited
+    }
+}
+
+/// Urgency order for picking one status out of several panes (higher wins)
+fn urgency(status: &SessionStatus) -> u8 {
+    match status {
+        SessionStatus::Crashed | SessionStatus::Stalled | SessionStatus::ApiError { .. } => 5,
+        SessionStatus::NeedsPermission { .. } | SessionStatus::EditApproval { .. } => 4,
+        SessionStatus::PlanReview
+        | SessionStatus::QuestionAsked
+        | SessionStatus::RateLimited { .. } => 3,
+        SessionStatus::Waiting => 2,
+        SessionStatus::Working => 1,
+        SessionStatus::Unknown => 0,
+    }
+}
+
+/// Most urgent status among the Claude panes of one tmux session
+pub fn session_status<'a>(
+    entries: &'a [StatusEntry],
+    tmux_session: &str,
+) -> Option<&'a SessionStatus> {
+    entries
+        .iter()
+        .filter(|e| e.host.is_none() && e.tmux_session == tmux_session)
+        .map(|e| &e.status)
+        .max_by_key(|s| urgency(s))
+}
+
+/// Short label, icon and tmux color for a status
+fn status_style(status: &SessionStatus) -> (&'static str, &'static str, &'static str) {
+    match status {
+        SessionStatus::NeedsPermission { .. } => ("permission", "⚠", "yellow"),
+        SessionStatus::EditApproval { .. } => ("edit", "⚠", "yellow"),
+        SessionStatus::PlanReview => ("plan", "◆", "magenta"),
+        SessionStatus::QuestionAsked => ("question", "?", "magenta"),
+        SessionStatus::Waiting => ("waiting", "⏸", "cyan"),
+        SessionStatus::Stalled => ("stalled", "⧖", "red"),
+        SessionStatus::Crashed => ("crashed", "✖", "red"),
+        SessionStatus::ApiError { .. } => ("api error", "✖", "red"),
+        SessionStatus::RateLimited { .. } => ("limited", "⏳", "yellow"),
+        SessionStatus::Working | SessionStatus::Unknown => ("working", "⚙", "default"),
+    }
+}
+
+/// Wrap a value in tmux color codes
+fn colored(value: &str, color: &str, color_enabled: bool) -> String {
+    if color_enabled && color != "default" && !value.is_empty() {
+        format!("#[fg={}]{}#[fg=default]", color, value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Replace `#{name}` variables using `lookup`; unknown variables are left as-is
+fn expand(format: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(format.len());
+    let mut rest = format;
+    while let Some(start) = rest.find("#{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match lookup(name) {
+                    Some(value) => out.push_str(&value),
+                    None => out.push_str(&rest[start..start + 3 + end]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Render the all-sessions summary.
+/// Variables: attention, permission, plan, question, stuck, limited, waiting, working, total.
+pub fn render_summary(format: &str, counts: &StatusCounts, color: bool) -> String {
+    expand(format, |name| {
+        let (value, style) = match name {
+            "attention" => (counts.attention(), "yellow"),
+            "permission" => (counts.permission, "yellow"),
+            "plan" => (counts.plan, "magenta"),
+            "question" => (counts.question, "magenta"),
+            "stuck" => (counts.stuck, "red"),
+            "limited" => (counts.limited, "yellow"),
+            "waiting" => (counts.waiting, "cyan"),
+            "working" => (counts.working, "default"),
+            "total" => (
+                counts.attention() + counts.waiting + counts.working,
+                "default",
+            ),
+            _ => return None,
+        };
+        // Zero counts stay uncolored so only actionable numbers stand out
+        Some(colored(&value.to_string(), style, color && value > 0))
+    })
+}
+
+/// Render one tmux session's status (empty variables when no Claude runs there).
+/// Variables: status, icon.
+pub fn render_session(format: &str, status: Option<&SessionStatus>, color: bool) -> String {
+    let style = status.map(status_style);
+    expand(format, |name| {
+        let (label, icon, fg) = style.unwrap_or(("", "", "default"));
+        match name {
+            "status" => Some(colored(label, fg, color)),
+            "icon" => Some(colored(icon, fg, color)),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(tmux_session: &str, status: SessionStatus) -> StatusEntry {
+        StatusEntry {
+            tmux_session: tmux_session.to_string(),
+            host: None,
+            status,
+        }
+    }
+
+    fn permission() -> SessionStatus {
+        SessionStatus::NeedsPermission {
+            tool_name: "Bash: ls".to_string(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_counts_and_format() {
+        let entries = vec![
+            entry("api", permission()),
+            entry("web", SessionStatus::PlanReview),
+            entry("docs", SessionStatus::Waiting),
+            entry("ops", SessionStatus::Working),
+            entry("new", SessionStatus::Unknown),
+            entry("hung", SessionStatus::Stalled),
+        ];
+        let counts = StatusCounts::from_entries(&entries);
+        assert_eq!(counts.attention(), 3);
+        assert_eq!(
+            render_summary(DEFAULT_SUMMARY_FORMAT, &counts, false),
+            "3⚠ 1⏸ 2⚙"
+        );
+        assert_eq!(
+            render_summary("#{permission}/#{total} #{stuck} #{bogus}", &counts, false),
+            "1/6 1 #{bogus}"
+        );
+    }
+
+    #[test]
+    fn test_summary_colors_only_nonzero_counts() {
+        let counts = StatusCounts {
+            permission: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            render_summary("#{attention} #{waiting}", &counts, true),
+            "#[fg=yellow]1#[fg=default] 0"
+        );
+    }
+
+    #[test]
+    fn test_session_picks_most_urgent_pane() {
+        let entries = vec![
+            entry("api", SessionStatus::Working),
+            entry("api", permission()),
+            entry("web", SessionStatus::Waiting),
+        ];
+        let status = session_status(&entries, "api");
+        assert_eq!(
+            render_session("#{icon} #{status}", status, false),
+            "⚠ permission"
+        );
+        let status = session_status(&entries, "web");
+        assert_eq!(
+            render_session("#{status}", status, true),
+            "#[fg=cyan]waiting#[fg=default]"
+        );
+        assert_eq!(
+            render_session("[#{icon}]", session_status(&entries, "none"), true),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn test_unterminated_variable_is_kept() {
+        assert_eq!(
+            render_summary("x #{waiting", &StatusCounts::default(), false),
+            "x #{waiting"
+        );
+    }
+}
@@ -1,120 +1,323 @@
 //! tmux command helpers.
 
+use crate::common::debug::debug_log;
 use crate::common::types::{TmuxPane, TmuxSession, TmuxWindow};
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::OnceLock;
 
-/// Get all tmux sessions with their windows and panes
-pub fn get_tmux_sessions() -> Result<Vec<TmuxSession>> {
-    let output = Command::new("tmux")
-        .args(["list-sessions", "-F", "#{session_name}"])
-        .output()
-        .context("Failed to list tmux sessions")?;
+/// `list-panes -a` format: one line per pane with its session and window.
+/// The path goes last so it may contain tabs.
+const PANE_LIST_FORMAT: &str = "#{session_name}\t#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_id}\t#{pane_pid}\t#{pane_current_path}";
 
-    let session_names = String::from_utf8_lossy(&output.stdout);
-    let mut sessions = Vec::new();
+/// A tmux server to run commands against
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TmuxServer {
+    /// Whatever plain `tmux` picks ($TMUX inside tmux, otherwise the default socket)
+    #[default]
+    Current,
+    /// Socket name in the tmux socket directory (`tmux -L name`)
+    Named(String),
+    /// Socket at an explicit path (`tmux -S path`)
+    Path(PathBuf),
+}
 
-    for session_name in session_names.lines() {
-        if session_name.is_empty() {
-            continue;
+impl TmuxServer {
+    /// Build a `tmux` command addressing this server
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new("tmux");
+        match self {
+            TmuxServer::Current => {}
+            TmuxServer::Named(name) => {
+                cmd.args(["-L", name]);
+            }
+            TmuxServer::Path(path) => {
+                cmd.arg("-S").arg(path);
+            }
         }
+        cmd
+    }
 
-        let windows = get_tmux_windows(session_name)?;
-        sessions.push(TmuxSession {
-            name: session_name.to_string(),
-            windows,
-        });
+    /// Identify a server from its socket path (e.g. the first field of $TMUX)
+    pub fn from_socket_path(path: &Path) -> Self {
+        let in_socket_dir = match (path.parent(), tmux_socket_dir()) {
+            (Some(parent), Some(dir)) => {
+                fs::canonicalize(parent).ok() == fs::canonicalize(dir).ok()
+            }
+            _ => false,
+        };
+        match path.file_name() {
+            Some(name) if in_socket_dir => TmuxServer::Named(name.to_string_lossy().to_string()),
+            _ => TmuxServer::Path(path.to_path_buf()),
+        }
     }
 
-    Ok(sessions)
+    /// Short name for display (socket name, or file name for explicit paths)
+    pub fn label(&self) -> String {
+        match self {
+            TmuxServer::Current => "current".to_string(),
+            TmuxServer::Named(name) => name.clone(),
+            TmuxServer::Path(path) => path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string()),
+        }
+    }
+
+    /// Server as tmux's own flags ("", "-L name" or "-S path"), for files on disk
+    pub fn to_spec(&self) -> String {
+        match self {
+            TmuxServer::Current => String::new(),
+            TmuxServer::Named(name) => format!("-L {}", name),
+            TmuxServer::Path(path) => format!("-S {}", path.display()),
+        }
+    }
+
+    /// Inverse of `to_spec` (anything unrecognized is the current server)
+    pub fn from_spec(spec: &str) -> Self {
+        if let Some(name) = spec.strip_prefix("-L ") {
+            TmuxServer::Named(name.to_string())
+        } else if let Some(path) = spec.strip_prefix("-S ") {
+            TmuxServer::Path(PathBuf::from(path))
+        } else {
+            TmuxServer::Current
+        }
+    }
 }
 
-/// Get all windows in a tmux session
-pub fn get_tmux_windows(session: &str) -> Result<Vec<TmuxWindow>> {
-    let output = Command::new("tmux")
-        .args([
-            "list-windows",
-            "-t",
-            session,
-            "-F",
-            "#{window_index}:#{window_name}",
-        ])
-        .output()
-        .context("Failed to list tmux windows")?;
+/// Key of per-session state that outlives a refresh (marks, todos, flags): the bare
+/// name on the default server, so state saved by name keeps applying, and
+/// `name.server` on other servers. tmux session names can't contain '.'.
+pub fn session_key(server: &TmuxServer, name: &str) -> String {
+    match server {
+        TmuxServer::Named(socket) if socket != "default" => format!("{}.{}", name, socket),
+        TmuxServer::Path(_) => format!("{}.{}", name, server.label()),
+        _ => name.to_string(),
+    }
+}
+
+/// Server of the tmux client we run in ($TMUX), or the implicit one outside tmux
+pub fn current_server() -> TmuxServer {
+    std::env::var("TMUX")
+        .ok()
+        .and_then(|tmux| {
+            let socket = tmux.split(',').next()?.to_string();
+            (!socket.is_empty()).then(|| TmuxServer::from_socket_path(Path::new(&socket)))
+        })
+        .unwrap_or_default()
+}
 
-    let window_list = String::from_utf8_lossy(&output.stdout);
-    let mut windows = Vec::new();
+/// tmux socket directory: $TMUX_TMPDIR (or /tmp) + tmux-$UID
+pub fn tmux_socket_dir() -> Option<&'static Path> {
+    static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    DIR.get_or_init(|| {
+        // SAFETY: getuid has no preconditions and cannot fail
+        let uid = unsafe { libc::getuid() };
+        let tmpdir = std::env::var("TMUX_TMPDIR")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| "/tmp".to_string());
+        Some(PathBuf::from(tmpdir).join(format!("tmux-{}", uid)))
+    })
+    .as_deref()
+}
 
-    for line in window_list.lines() {
-        if line.is_empty() {
-            continue;
-        }
+/// Discover live tmux servers from sockets in the tmux socket directory.
+/// "default" comes first; falls back to the current server if none are found.
+pub fn discover_tmux_servers() -> Vec<TmuxServer> {
+    let mut names: Vec<String> = tmux_socket_dir()
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.file_type().map(|t| t.is_socket()).unwrap_or(false))
+                // Stale sockets from dead servers refuse connections
+                .filter(|e| UnixStream::connect(e.path()).is_ok())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
 
-        let parts: Vec<&str> = line.split(':').collect();
-        if parts.len() >= 2 {
-            let index = parts[0].to_string();
-            let name = parts[1..].join(":");
-            let panes = get_tmux_panes(session, &index)?;
+    if names.is_empty() {
+        return vec![TmuxServer::Current];
+    }
+    names.sort_by_key(|name| (name != "default", name.clone()));
+    names.into_iter().map(TmuxServer::Named).collect()
+}
 
-            windows.push(TmuxWindow { index, name, panes });
+/// Get tmux sessions with their windows and panes from `servers`, grouped by server.
+/// A server that doesn't answer is skipped (and logged) so the others still show.
+pub fn get_tmux_sessions(servers: &[TmuxServer]) -> Vec<TmuxSession> {
+    let mut sessions = Vec::new();
+    for server in servers {
+        match get_server_sessions(server) {
+            Ok(found) => sessions.extend(found),
+            Err(e) => debug_log(&format!("TMUX: skipping {}: {:#}", server.label(), e)),
         }
     }
-
-    Ok(windows)
+    sessions
 }
 
-/// Get all panes in a tmux window
-pub fn get_tmux_panes(session: &str, window_index: &str) -> Result<Vec<TmuxPane>> {
-    let target = format!("{}:{}", session, window_index);
-    let output = Command::new("tmux")
-        .args([
-            "list-panes",
-            "-t",
-            &target,
-            "-F",
-            "#{pane_index}\t#{pane_pid}\t#{pane_current_path}",
-        ])
+/// Get all sessions on one server (single `list-panes -a` call)
+pub fn get_server_sessions(server: &TmuxServer) -> Result<Vec<TmuxSession>> {
+    let output = server
+        .command()
+        .args(["list-panes", "-a", "-F", PANE_LIST_FORMAT])
         .output()
         .context("Failed to list tmux panes")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
 
-    let pane_list = String::from_utf8_lossy(&output.stdout);
-    let mut panes = Vec::new();
+    let mut sessions = parse_pane_list(&String::from_utf8_lossy(&output.stdout));
+    for session in &mut sessions {
+        session.server = server.clone();
+    }
+    Ok(sessions)
+}
+
+/// Find a pane by id on a server, returning its (session, window, pane) target
+pub fn find_pane_by_id(
+    sessions: &[TmuxSession],
+    server: &TmuxServer,
+    pane_id: &str,
+) -> Option<(String, String, String)> {
+    find_pane(sessions, server, pane_id)
+        .map(|(s, w, p)| (s.name.clone(), w.index.clone(), p.index.clone()))
+}
+
+/// Pid of the shell in pane `pane_id` of `server`
+pub fn find_pane_pid(sessions: &[TmuxSession], server: &TmuxServer, pane_id: &str) -> Option<u32> {
+    find_pane(sessions, server, pane_id).map(|(_, _, p)| p.pid)
+}
 
-    for line in pane_list.lines() {
-        if line.is_empty() {
+/// Session, window and pane of pane `pane_id` of `server`
+fn find_pane<'a>(
+    sessions: &'a [TmuxSession],
+    server: &TmuxServer,
+    pane_id: &str,
+) -> Option<(&'a TmuxSession, &'a TmuxWindow, &'a TmuxPane)> {
+    sessions
+        .iter()
+        // Sessions from the implicit server can't be told apart, so they match any server
+        .filter(|s| s.server == *server || s.server == TmuxServer::Current)
+        .find_map(|s| {
+            s.windows
+                .iter()
+                .find_map(|w| w.panes.iter().find(|p| p.id == pane_id).map(|p| (s, w, p)))
+        })
+}
+
+/// Parse `list-panes -a -F PANE_LIST_FORMAT` output into sessions, keeping tmux's order
+pub fn parse_pane_list(output: &str) -> Vec<TmuxSession> {
+    let mut sessions: Vec<TmuxSession> = Vec::new();
+
+    for line in output.lines() {
+        let parts: Vec<&str> = line.splitn(7, '\t').collect();
+        if parts.len() < 7 {
             continue;
         }
+        let Ok(pid) = parts[5].parse::<u32>() else {
+            continue;
+        };
+        let pane = TmuxPane {
+            index: parts[3].to_string(),
+            id: parts[4].to_string(),
+            pid,
+            cwd: parts[6].to_string(),
+        };
 
-        let parts: Vec<&str> = line.split('\t').collect();
-        if parts.len() >= 3 {
-            if let Ok(pid) = parts[1].parse::<u32>() {
-                panes.push(TmuxPane {
-                    index: parts[0].to_string(),
-                    pid,
-                    cwd: parts[2].to_string(),
-                });
-            }
+        // Lines are grouped by session, then window, so only the last entry can match
+        if sessions.last().map(|s| s.name != parts[0]).unwrap_or(true) {
+            sessions.push(TmuxSession {
+                name: parts[0].to_string(),
+                windows: Vec::new(),
+                server: TmuxServer::Current,
+            });
+        }
+        let session = sessions.last_mut().expect("session just pushed");
+        if session
+            .windows
+            .last()
+            .map(|w| w.index != parts[1])
+            .unwrap_or(true)
+        {
+            session.windows.push(TmuxWindow {
+                index: parts[1].to_string(),
+                name: parts[2].to_string(),
+                panes: Vec::new(),
+            });
+        }
+        if let Some(window) = session.windows.last_mut() {
+            window.panes.push(pane);
         }
     }
 
-    Ok(panes)
+    sessions
 }
 
-/// Switch to a tmux session
-pub fn switch_to_session(session_name: &str) {
-    let _ = Command::new("tmux")
+/// Switch a client on the session's server to it.
+/// Returns false if that server has no attached client to switch.
+pub fn switch_to_session(server: &TmuxServer, session_name: &str) -> bool {
+    server
+        .command()
         .args(["switch-client", "-t", session_name])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+/// Select a pane and its window, then switch a client to it.
+/// Returns false if the switch failed (no client on that server).
+pub fn focus_pane(server: &TmuxServer, target: &str) -> bool {
+    let _ = server
+        .command()
+        .args(["select-window", "-t", target])
         .output();
+    let _ = server
+        .command()
+        .args(["select-pane", "-t", target])
+        .output();
+    server
+        .command()
+        .args(["switch-client", "-t", target])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
 }
 
 /// Send a key to a tmux pane
-pub fn send_key_to_pane(session: &str, window: &str, pane: &str, key: &str) {
+pub fn send_key_to_pane(server: &TmuxServer, session: &str, window: &str, pane: &str, key: &str) {
     let target = format!("{}:{}.{}", session, window, pane);
-    let _ = Command::new("tmux")
+    let _ = server
+        .command()
         .args(["send-keys", "-t", &target, key])
         .output();
 }
 
+/// Type literal text into a tmux pane (no key-name lookup)
+pub fn send_text_to_pane(server: &TmuxServer, session: &str, window: &str, pane: &str, text: &str) {
+    let target = format!("{}:{}.{}", session, window, pane);
+    let _ = server
+        .command()
+        .args(["send-keys", "-t", &target, "-l", text])
+        .output();
+}
+
+/// Copy text into the tmux paste buffer (and the system clipboard, if tmux is configured for it)
+pub fn copy_to_tmux_buffer(text: &str) -> bool {
+    Command::new("tmux")
+        .args(["set-buffer", "-w", text])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
 /// Get list of currently running tmux session names
 pub fn get_current_tmux_session_names() -> Vec<String> {
     Command::new("tmux")
@@ -146,11 +349,112 @@ pub fn get_current_tmux_session() -> Option<String> {
         })
 }
 
-/// Kill a tmux session
-pub fn kill_tmux_session(name: &str) -> bool {
+/// Get the active pane id (e.g. "%3") of the current client
+pub fn get_current_pane_id() -> Option<String> {
     Command::new("tmux")
+        .args(["display-message", "-p", "#{pane_id}"])
+        .output()
+        .ok()
+        .and_then(|o| {
+            let id = String::from_utf8_lossy(&o.stdout).trim().to_string();
+            if id.is_empty() {
+                None
+            } else {
+                Some(id)
+            }
+        })
+}
+
+/// Kill a tmux session
+pub fn kill_tmux_session(server: &TmuxServer, name: &str) -> bool {
+    server
+        .command()
         .args(["kill-session", "-t", name])
         .output()
         .map(|o| o.status.success())
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_spec_round_trip() {
+        for server in [
+            TmuxServer::Current,
+            TmuxServer::Named("work".to_string()),
+            TmuxServer::Path(PathBuf::from("/tmp/my sockets/ci")),
+        ] {
+            assert_eq!(TmuxServer::from_spec(&server.to_spec()), server);
+        }
+        assert_eq!(TmuxServer::from_spec("garbage"), TmuxServer::Current);
+    }
+
+    #[test]
+    fn test_parse_pane_list_groups_sessions_and_windows() {
+        let output = "work\t0\teditor\t0\t%0\t100\t/home/me/work\n\
+                      work\t0\teditor\t1\t%1\t101\t/home/me/work/src\n\
+                      work\t2\tlogs\t0\t%2\t102\t/var/log\n\
+                      api\t1\tzsh\t0\t%5\t200\t/home/me/api\n";
+        let sessions = parse_pane_list(output);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].name, "work");
+        assert_eq!(sessions[0].windows.len(), 2);
+        assert_eq!(sessions[0].windows[0].panes.len(), 2);
+        assert_eq!(sessions[0].windows[0].panes[1].pid, 101);
+        assert_eq!(sessions[0].windows[0].panes[1].id, "%1");
+        assert_eq!(sessions[0].windows[1].name, "logs");
+        assert_eq!(sessions[1].windows[0].index, "1");
+        assert_eq!(sessions[1].windows[0].panes[0].cwd, "/home/me/api");
+    }
+
+    #[test]
+    fn test_parse_pane_list_skips_malformed_lines() {
+        let output = "\nwork\t0\tzsh\t0\t%0\tnotapid\t/tmp\nshort\tline\n\
+                      ok\t0\tzsh\t0\t%1\t5\t/tmp/with\ttab\n";
+        let sessions = parse_pane_list(output);
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].windows[0].panes[0].cwd, "/tmp/with\ttab");
+    }
+
+    #[test]
+    fn test_server_from_socket_path_outside_socket_dir() {
+        let server = TmuxServer::from_socket_path(Path::new("/nonexistent/sockets/work"));
+        assert_eq!(
+            server,
+            TmuxServer::Path(PathBuf::from("/nonexistent/sockets/work"))
+        );
+        assert_eq!(server.label(), "work");
+    }
+
+    #[test]
+    fn test_find_pane_by_id_respects_server() {
+        let output = "work\t1\tzsh\t2\t%7\t100\t/tmp\n";
+        let mut sessions = parse_pane_list(output);
+        sessions[0].server = TmuxServer::Named("work".to_string());
+
+        let found = find_pane_by_id(&sessions, &TmuxServer::Named("work".to_string()), "%7");
+        assert_eq!(
+            found,
+            Some(("work".to_string(), "1".to_string(), "2".to_string()))
+        );
+        assert!(
+            find_pane_by_id(&sessions, &TmuxServer::Named("other".to_string()), "%7").is_none()
+        );
+        assert!(find_pane_by_id(&sessions, &TmuxServer::Named("work".to_string()), "%1").is_none());
+    }
+
+    #[test]
+    fn test_session_key_qualifies_non_default_servers() {
+        let named = |socket: &str| TmuxServer::Named(socket.to_string());
+        assert_eq!(session_key(&TmuxServer::Current, "work"), "work");
+        assert_eq!(session_key(&named("default"), "work"), "work");
+        assert_eq!(session_key(&named("ci"), "work"), "work.ci");
+        assert_eq!(
+            session_key(&TmuxServer::Path(PathBuf::from("/run/tmux/box")), "work"),
+            "work.box"
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! Full Claude session transcripts: prompts, replies, tool calls/results and thinking.
+
+use crate::common::jsonl::cwd_to_claude_projects_path;
+use crate::common::jsonl_tail::{read_appended, TailCursor};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a transcript item is
+#[derive(Debug, Clone, PartialEq)]
+pub enum TranscriptKind {
+    /// Prompt typed by the user
+    UserPrompt,
+    /// Assistant reply text
+    AssistantText,
+    /// Extended thinking (shown collapsed)
+    Thinking,
+    /// Tool call, with the tool name
+    ToolCall(String),
+    /// Tool output
+    ToolResult { is_error: bool },
+}
+
+/// One block of the conversation
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptItem {
+    pub kind: TranscriptKind,
+    pub text: String,
+    /// ISO 8601 timestamp of the JSONL entry
+    pub timestamp: Option<String>,
+    /// Index of the user turn this item belongs to (0 before the first prompt)
+    pub turn: usize,
+    /// Full input of a tool call, pretty-printed (shown when the item is expanded)
+    pub tool_input: Option<String>,
+}
+
+/// One-line summary of a tool call's input (the command, file or pattern)
+pub fn summarize_tool_input(name: &str, input: &serde_json::Value) -> String {
+    let field = |key: &str| input.get(key).and_then(|v| v.as_str());
+    let summary = match name {
+        "Bash" => field("command"),
+        "Read" | "Write" | "Edit" | "MultiEdit" => field("file_path"),
+        "NotebookEdit" => field("notebook_path"),
+        "Grep" | "Glob" => field("pattern"),
+        "WebFetch" => field("url"),
+        "WebSearch" => field("query"),
+        "Task" => field("description"),
+        _ => None,
+    };
+    match summary {
+        Some(s) => s.to_string(),
+        None if input.is_null() => String::new(),
+        None => input.to_string(),
+    }
+}
+
+/// Text of a tool_result's content (a string or a list of text blocks)
+fn tool_result_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Array(blocks)) => blocks
+            .iter()
+            .filter_map(|b| match b.get("type").and_then(|t| t.as_str()) {
+                Some("text") => b.get("text").and_then(|t| t.as_str()).map(str::to_string),
+                Some("image") => Some("[image]".to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Transcript items of one parsed JSONL entry
+pub fn parse_transcript_entry(entry: &serde_json::Value, turn: &mut usize) -> Vec<TranscriptItem> {
+    let entry_type = entry.get("type").and_then(|t| t.as_str()).unwrap_or("");
+    let is_meta = entry
+        .get("isMeta")
+        .and_then(|m| m.as_bool())
+        .unwrap_or(false);
+    if !matches!(entry_type, "user" | "assistant") || is_meta {
+        return Vec::new();
+    }
+    let timestamp = entry
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .map(str::to_string);
+    let content = entry.get("message").and_then(|m| m.get("content"));
+
+    let mut items = Vec::new();
+    let item = |kind: TranscriptKind, text: String, turn: usize| TranscriptItem {
+        kind,
+        text,
+        timestamp: timestamp.clone(),
+        turn,
+        tool_input: None,
+    };
+
+    match content {
+        Some(serde_json::Value::String(text)) if entry_type == "user" => {
+            *turn += 1;
+            items.push(item(TranscriptKind::UserPrompt, text.clone(), *turn));
+        }
+        Some(serde_json::Value::String(text)) => {
+            items.push(item(TranscriptKind::AssistantText, text.clone(), *turn));
+        }
+        Some(serde_json::Value::Array(blocks)) => {
+            for block in blocks {
+                let text_of = |key: &str| {
+                    block
+                        .get(key)
+                        .and_then(|t| t.as_str())
+                        .unwrap_or("")
+                        .to_string()
+                };
+                match block.get("type").and_then(|t| t.as_str()) {
+                    Some("text") if entry_type == "user" => {
+                        *turn += 1;
+                        items.push(item(TranscriptKind::UserPrompt, text_of("text"), *turn));
+                    }
+                    Some("text") => {
+                        items.push(item(TranscriptKind::AssistantText, text_of("text"), *turn))
+                    }
+                    Some("thinking") => {
+                        items.push(item(TranscriptKind::Thinking, text_of("thinking"), *turn))
+                    }
+                    Some("tool_use") => {
+                        let name = text_of("name");
+                        let input = block.get("input").cloned().unwrap_or_default();
+                        let summary = summarize_tool_input(&name, &input);
+                        items.push(TranscriptItem {
+                            tool_input: serde_json::to_string_pretty(&input)
+                                .ok()
+                                .filter(|_| !input.is_null()),
+                            ..item(TranscriptKind::ToolCall(name), summary, *turn)
+                        });
+                    }
+                    Some("tool_result") => {
+                        let is_error = block
+                            .get("is_error")
+                            .and_then(|e| e.as_bool())
+                            .unwrap_or(false);
+                        let text = tool_result_text(block.get("content"));
+                        items.push(item(TranscriptKind::ToolResult { is_error }, text, *turn));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    items.retain(|item| {
+        !item.text.trim().is_empty() || matches!(item.kind, TranscriptKind::ToolCall(_))
+    });
+    items
+}
+
+/// Parse a whole transcript file
+pub fn load_transcript(path: &Path) -> Result<Vec<TranscriptItem>> {
+    let update = read_appended(path, TailCursor::default())?;
+    let mut turn = 0;
+    Ok(update
+        .entries
+        .iter()
+        .flat_map(|entry| parse_transcript_entry(entry, &mut turn))
+        .collect())
+}
+
+/// Most recently modified transcript in a projects directory
+fn find_latest_jsonl(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .max_by_key(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .map(|e| e.path())
+}
+
+/// Transcript file of a session: the path its hooks reported, else
+/// `<session_id>.jsonl` in the cwd's projects dir, or the newest transcript there
+/// when the id is unknown
+pub fn find_transcript(
+    cwd: &str,
+    session_id: Option<&str>,
+    transcript_path: Option<&str>,
+) -> Option<PathBuf> {
+    if let Some(path) = transcript_path.map(PathBuf::from) {
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+    let dir = cwd_to_claude_projects_path(cwd);
+    if let Some(id) = session_id {
+        let path = dir.join(format!("{}.jsonl", id));
+        if path.is_file() {
+            return Some(path);
+        }
+    }
+    find_latest_jsonl(&dir)
+}
+
+/// Text of a prompt the user typed; None for other items and for slash commands
+/// and their output, which are recorded as tagged prompts
+pub fn typed_prompt(item: &TranscriptItem) -> Option<&str> {
+    let text = item.text.trim();
+    (item.kind == TranscriptKind::UserPrompt && !text.is_empty() && !text.starts_with('<'))
+        .then_some(text)
+}
+
+/// One past conversation of a project, for the history view
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptSummary {
+    pub path: PathBuf,
+    pub session_id: String,
+    /// First prompt typed by the user (slash commands and their output skipped)
+    pub first_prompt: Option<String>,
+    /// Prompts typed by the user
+    pub turns: usize,
+    pub last_timestamp: Option<String>,
+    pub size: u64,
+}
+
+impl TranscriptSummary {
+    /// Summarize parsed transcript items
+    pub fn from_items(path: PathBuf, size: u64, items: &[TranscriptItem]) -> Self {
+        let prompts: Vec<&str> = items.iter().filter_map(typed_prompt).collect();
+        Self {
+            session_id: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path,
+            first_prompt: prompts.first().map(|text| text.to_string()),
+            turns: prompts.len(),
+            last_timestamp: items.iter().rev().find_map(|item| item.timestamp.clone()),
+            size,
+        }
+    }
+}
+
+/// Past conversations of the project in `cwd`, most recently modified first, parsed
+/// from the files (for when the daemon's index isn't available)
+pub fn list_transcripts(cwd: &str) -> Vec<TranscriptSummary> {
+    let Ok(entries) = fs::read_dir(cwd_to_claude_projects_path(cwd)) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, fs::Metadata)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|e| Some((e.path(), e.metadata().ok()?)))
+        .collect();
+    files.sort_by_key(|(_, meta)| std::cmp::Reverse(meta.modified().ok()));
+    files
+        .into_iter()
+        .filter_map(|(path, meta)| {
+            let items = load_transcript(&path).ok()?;
+            Some(TranscriptSummary::from_items(path, meta.len(), &items))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Transcript items of JSONL lines (unparseable lines skipped)
+    fn parse_lines(lines: &[&str]) -> Vec<TranscriptItem> {
+        let mut turn = 0;
+        lines
+            .iter()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .flat_map(|entry| parse_transcript_entry(&entry, &mut turn))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_conversation() {
+        let lines = [
+            r#"{"type":"summary","summary":"Fix tests"}"#,
+            r#"{"type":"user","isMeta":true,"message":{"role":"user","content":"<command-name>/clear</command-name>"}}"#,
+            r#"{"type":"user","timestamp":"2026-01-01T10:00:00Z","message":{"role":"user","content":"fix the flaky test"}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"thinking","thinking":"Look at CI first"},{"type":"text","text":"Let me run it."}]}}"#,
+            r#"{"type":"assistant","message":{"role":"assistant","content":[{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"cargo test","description":"Run tests"}}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","is_error":true,"content":[{"type":"text","text":"1 failed"}]}]}}"#,
+            r#"{"type":"user","message":{"role":"user","content":[{"type":"text","text":"try again"}]}}"#,
+            "not json",
+        ];
+        let items = parse_lines(&lines);
+        let kinds: Vec<&TranscriptKind> = items.iter().map(|i| &i.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                &TranscriptKind::UserPrompt,
+                &TranscriptKind::Thinking,
+                &TranscriptKind::AssistantText,
+                &TranscriptKind::ToolCall("Bash".to_string()),
+                &TranscriptKind::ToolResult { is_error: true },
+                &TranscriptKind::UserPrompt,
+            ]
+        );
+        assert_eq!(items[0].timestamp.as_deref(), Some("2026-01-01T10:00:00Z"));
+        assert_eq!(items[3].text, "cargo test");
+        let input = items[3].tool_input.as_deref().unwrap();
+        assert!(input.contains(r#""description": "Run tests""#));
+        assert_eq!(items[4].tool_input, None);
+        assert_eq!(items[4].text, "1 failed");
+        assert_eq!((items[4].turn, items[5].turn), (1, 2));
+    }
+
+    #[test]
+    fn test_summary_skips_slash_commands() {
+        let lines = [
+            r#"{"type":"user","message":{"content":"<command-name>/model</command-name>"}}"#,
+            r#"{"type":"user","timestamp":"2026-01-01T10:00:00Z","message":{"content":"add dark mode"}}"#,
+            r#"{"type":"assistant","timestamp":"2026-01-01T10:05:00Z","message":{"content":[{"type":"text","text":"Done."}]}}"#,
+            r#"{"type":"user","message":{"content":"now the settings page"}}"#,
+        ];
+        let items = parse_lines(&lines);
+        let summary = TranscriptSummary::from_items(PathBuf::from("/p/abc.jsonl"), 42, &items);
+        assert_eq!(summary.session_id, "abc");
+        assert_eq!(summary.first_prompt.as_deref(), Some("add dark mode"));
+        assert_eq!(summary.turns, 2);
+        assert_eq!(
+            summary.last_timestamp.as_deref(),
+            Some("2026-01-01T10:05:00Z")
+        );
+    }
+
+    #[test]
+    fn test_summarize_tool_input() {
+        let input = serde_json::json!({"file_path": "/src/lib.rs", "old_string": "a"});
+        assert_eq!(summarize_tool_input("Edit", &input), "/src/lib.rs");
+        let input = serde_json::json!({"server": "x"});
+        assert_eq!(
+            summarize_tool_input("mcp__x__y", &input),
+            r#"{"server":"x"}"#
+        );
+    }
+}
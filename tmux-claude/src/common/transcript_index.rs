@@ -0,0 +1,488 @@
+//! Full-text index over every Claude transcript under `~/.claude/projects`.
+//!
+//! The daemon keeps it current from what `JsonlTailer` reads appended to each
+//! JSONL, and persists it (with the tail cursors) so a restart doesn't
+//! reindex everything. Only prompts, assistant text and tool commands are
+//! kept; tool output and thinking are left out. Token usage is accumulated
+//! from the same entries.
+
+use crate::common::jsonl::cwd_to_claude_projects_path;
+use crate::common::jsonl_tail::{TailCursor, TailRound, TailUpdate};
+use crate::common::transcript::{
+    parse_transcript_entry, typed_prompt, TranscriptKind, TranscriptSummary,
+};
+use crate::common::usage::{usage_record, UsageLedger};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Longest text kept per indexed block
+const MAX_DOC_CHARS: usize = 2000;
+
+/// Characters of context shown around a match
+const SNIPPET_CHARS: usize = 100;
+
+/// Matching blocks shown per session
+const MAX_SNIPPETS: usize = 3;
+
+/// Bumped when the index gains data older indexes lack, forcing a reindex
+const INDEX_VERSION: u32 = 4;
+
+/// Index file path
+pub fn get_transcript_index_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("tmux-claude")
+        .join("transcript-index.json")
+}
+
+/// What an indexed block is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DocKind {
+    Prompt,
+    Reply,
+    Command,
+}
+
+impl DocKind {
+    /// Ranking weight: a match in a prompt says most about what a session was about
+    fn weight(self) -> u32 {
+        match self {
+            DocKind::Prompt => 3,
+            DocKind::Command => 2,
+            DocKind::Reply => 1,
+        }
+    }
+}
+
+/// One searchable block of a transcript
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedDoc {
+    pub kind: DocKind,
+    pub text: String,
+    pub turn: usize,
+    /// `text` lowercased for matching (not persisted, rebuilt on load)
+    #[serde(skip)]
+    lower: String,
+}
+
+impl IndexedDoc {
+    pub fn new(kind: DocKind, text: String, turn: usize) -> Self {
+        Self {
+            lower: text.to_lowercase(),
+            kind,
+            text,
+            turn,
+        }
+    }
+}
+
+/// Indexed content of one transcript file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// Project working directory (from the transcript, else the directory name)
+    pub project: String,
+    /// Where reading stopped
+    pub cursor: TailCursor,
+    pub turns: usize,
+    /// Prompts typed by the user (`turns` also counts slash commands)
+    pub prompts: usize,
+    pub last_timestamp: Option<String>,
+    pub docs: Vec<IndexedDoc>,
+    #[serde(default)]
+    pub usage: UsageLedger,
+}
+
+/// Transcript blocks by file path
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptIndex {
+    #[serde(default)]
+    pub version: u32,
+    pub files: HashMap<String, IndexedFile>,
+}
+
+impl Default for TranscriptIndex {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            files: HashMap::new(),
+        }
+    }
+}
+
+/// Matching block shown under a search hit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HitSnippet {
+    pub kind: DocKind,
+    pub turn: usize,
+    pub text: String,
+}
+
+/// A session matching a transcript search
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptHit {
+    pub path: PathBuf,
+    pub project: String,
+    pub session_id: String,
+    pub last_timestamp: Option<String>,
+    pub score: u32,
+    pub match_count: usize,
+    pub snippets: Vec<HitSnippet>,
+}
+
+/// `text` flattened to one line, cut to about `SNIPPET_CHARS` around byte `at`
+fn snippet(text: &str, at: usize) -> String {
+    let flat: String = text
+        .chars()
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    let at = text.get(..at).map_or(0, |before| before.chars().count());
+    let start = at.saturating_sub(SNIPPET_CHARS / 3);
+    let total = flat.chars().count();
+    let end = (start + SNIPPET_CHARS).min(total);
+    let mut out: String = flat.chars().skip(start).take(end - start).collect();
+    if start > 0 {
+        out.insert(0, '…');
+    }
+    if end < total {
+        out.push('…');
+    }
+    out
+}
+
+/// Score of one block: zero unless every term appears
+fn doc_score(doc: &IndexedDoc, terms: &[String], phrase: &str) -> (u32, usize) {
+    let lower = &doc.lower;
+    // `to_lowercase` can change byte lengths; positions are only used when it didn't
+    let same_len = lower.len() == doc.text.len();
+    let mut first = None;
+    for term in terms {
+        let pos = lower
+            .find(term.as_str())
+            .map(|p| if same_len { p } else { 0 });
+        match pos {
+            Some(p) => first = Some(first.map_or(p, |f: usize| f.min(p))),
+            None => return (0, 0),
+        }
+    }
+    let bonus = if terms.len() > 1 && lower.contains(phrase) {
+        2
+    } else {
+        0
+    };
+    (doc.kind.weight() + bonus, first.unwrap_or(0))
+}
+
+impl TranscriptIndex {
+    /// Load the persisted index (empty if missing or unreadable)
+    pub fn load() -> Self {
+        let mut index = fs::read_to_string(get_transcript_index_path())
+            .ok()
+            .and_then(|content| serde_json::from_str::<Self>(&content).ok())
+            .filter(|index| index.version == INDEX_VERSION)
+            .unwrap_or_default();
+        for doc in index.files.values_mut().flat_map(|file| &mut file.docs) {
+            doc.lower = doc.text.to_lowercase();
+        }
+        index
+    }
+
+    /// Tail cursors of the indexed files, to resume reading where the index stops
+    pub fn cursors(&self) -> HashMap<PathBuf, TailCursor> {
+        self.files
+            .iter()
+            .map(|(path, file)| (PathBuf::from(path), file.cursor))
+            .collect()
+    }
+
+    /// Store what a tail round read; returns whether anything changed
+    pub fn apply_round(&mut self, round: TailRound) -> bool {
+        let changed = !round.is_empty();
+        for path in &round.removed {
+            self.files.remove(path.to_string_lossy().as_ref());
+        }
+        for update in round.updates {
+            self.apply(update);
+        }
+        changed
+    }
+
+    /// Index the entries appended to one file
+    pub fn apply(&mut self, update: TailUpdate) {
+        let key = update.path.to_string_lossy().to_string();
+        let file = self.files.entry(key).or_default();
+        if update.reset {
+            *file = IndexedFile::default();
+        }
+        for entry in &update.entries {
+            if file.project.is_empty() {
+                if let Some(cwd) = entry.get("cwd").and_then(|c| c.as_str()) {
+                    file.project = cwd.to_string();
+                }
+            }
+            if let Some(record) = usage_record(entry) {
+                file.usage.record(record);
+            }
+            for item in parse_transcript_entry(entry, &mut file.turns) {
+                if typed_prompt(&item).is_some() {
+                    file.prompts += 1;
+                }
+                let (kind, text) = match item.kind {
+                    TranscriptKind::UserPrompt => (DocKind::Prompt, item.text),
+                    TranscriptKind::AssistantText => (DocKind::Reply, item.text),
+                    TranscriptKind::ToolCall(name) => {
+                        (DocKind::Command, format!("{}: {}", name, item.text))
+                    }
+                    _ => continue,
+                };
+                if item.timestamp.is_some() {
+                    file.last_timestamp = item.timestamp;
+                }
+                let text = text.chars().take(MAX_DOC_CHARS).collect();
+                file.docs.push(IndexedDoc::new(kind, text, item.turn));
+            }
+        }
+        if file.project.is_empty() {
+            file.project = update
+                .path
+                .parent()
+                .and_then(|dir| dir.file_name())
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+        }
+        file.cursor = update.cursor;
+    }
+
+    /// Past conversations of the project in `cwd`, most recent first
+    pub fn project_transcripts(&self, cwd: &str) -> Vec<TranscriptSummary> {
+        let dir = cwd_to_claude_projects_path(cwd);
+        let mut summaries: Vec<TranscriptSummary> = self
+            .files
+            .iter()
+            .map(|(path, file)| (PathBuf::from(path), file))
+            .filter(|(path, _)| path.parent() == Some(dir.as_path()))
+            .map(|(path, file)| TranscriptSummary {
+                session_id: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                path,
+                first_prompt: file
+                    .docs
+                    .iter()
+                    .filter(|doc| doc.kind == DocKind::Prompt)
+                    .map(|doc| doc.text.trim())
+                    .find(|text| !text.is_empty() && !text.starts_with('<'))
+                    .map(str::to_string),
+                turns: file.prompts,
+                last_timestamp: file.last_timestamp.clone(),
+                size: file.cursor.size,
+            })
+            .collect();
+        summaries.sort_by_key(|summary| {
+            std::cmp::Reverse(
+                summary
+                    .last_timestamp
+                    .as_deref()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok()),
+            )
+        });
+        summaries
+    }
+
+    /// Sessions whose blocks contain every word of `query`, grouped by project
+    /// (best project first) and ranked within it; at most `limit` sessions
+    pub fn search(&self, query: &str, limit: usize) -> Vec<TranscriptHit> {
+        let phrase = query.trim().to_lowercase();
+        let terms: Vec<String> = phrase.split_whitespace().map(str::to_string).collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let mut hits: Vec<TranscriptHit> = self
+            .files
+            .iter()
+            .filter_map(|(path, file)| {
+                let mut score = 0;
+                let mut match_count = 0;
+                let mut snippets = Vec::new();
+                for doc in &file.docs {
+                    let (doc_score, at) = doc_score(doc, &terms, &phrase);
+                    if doc_score == 0 {
+                        continue;
+                    }
+                    score += doc_score;
+                    match_count += 1;
+                    snippets.push((doc_score, doc.kind, doc.turn, at, &doc.text));
+                }
+                if match_count == 0 {
+                    return None;
+                }
+                // Best blocks first, earliest first among equals
+                snippets.sort_by_key(|&(score, _, turn, _, _)| (std::cmp::Reverse(score), turn));
+                let path = PathBuf::from(path);
+                Some(TranscriptHit {
+                    session_id: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    path,
+                    project: file.project.clone(),
+                    last_timestamp: file.last_timestamp.clone(),
+                    score,
+                    match_count,
+                    snippets: snippets
+                        .into_iter()
+                        .take(MAX_SNIPPETS)
+                        .map(|(_, kind, turn, at, text)| HitSnippet {
+                            kind,
+                            turn,
+                            text: snippet(text, at),
+                        })
+                        .collect(),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| b.last_timestamp.cmp(&a.last_timestamp))
+        });
+        hits.truncate(limit);
+
+        // Group by project, keeping the projects in order of their best session
+        let mut project_rank: HashMap<String, usize> = HashMap::new();
+        for hit in &hits {
+            let next = project_rank.len();
+            project_rank.entry(hit.project.clone()).or_insert(next);
+        }
+        hits.sort_by_key(|hit| project_rank[&hit.project]);
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(kind: &str, content: &str) -> String {
+        format!(
+            r#"{{"type":"{}","cwd":"/work/api","timestamp":"2026-01-01T10:00:00Z","message":{{"content":{}}}}}"#,
+            kind, content
+        )
+    }
+
+    #[test]
+    fn test_apply_tail_updates() {
+        let entry = |kind: &str, content: &str| -> serde_json::Value {
+            serde_json::from_str(&line(kind, content)).unwrap()
+        };
+        let path = cwd_to_claude_projects_path("/work/api").join("abc.jsonl");
+        let update = |entries, reset, offset| TailUpdate {
+            path: path.clone(),
+            entries,
+            reset,
+            cursor: TailCursor {
+                inode: 7,
+                size: offset,
+                offset,
+            },
+        };
+        let key = path.to_string_lossy().to_string();
+
+        let mut index = TranscriptIndex::default();
+        let slash = entry("user", r#""<command-name>/model</command-name>""#);
+        let prompt = entry("user", r#""fix the flaky login test""#);
+        index.apply(update(vec![slash, prompt], true, 100));
+        let file = &index.files[&key];
+        assert_eq!((file.project.as_str(), file.docs.len()), ("/work/api", 2));
+        assert_eq!((file.turns, file.prompts), (2, 1));
+
+        let tool = entry(
+            "assistant",
+            r#"[{"type":"tool_use","name":"Bash","input":{"command":"cargo test login"}}]"#,
+        );
+        index.apply(update(vec![tool], false, 200));
+        let file = &index.files[&key];
+        assert_eq!(file.docs[2].text, "Bash: cargo test login");
+        assert_eq!(file.docs[2].turn, 2);
+        assert_eq!(index.cursors()[&path].offset, 200);
+
+        let listed = index.project_transcripts("/work/api");
+        assert!(index.project_transcripts("/work/web").is_empty());
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].session_id, "abc");
+        assert_eq!(
+            listed[0].first_prompt.as_deref(),
+            Some("fix the flaky login test")
+        );
+        assert_eq!((listed[0].turns, listed[0].size), (1, 200));
+
+        // Rewritten file: reindexed from scratch
+        index.apply(update(Vec::new(), true, 1));
+        assert!(index.files[&key].docs.is_empty());
+
+        let removed = TailRound {
+            updates: Vec::new(),
+            removed: vec![path.clone()],
+        };
+        assert!(index.apply_round(removed));
+        assert!(index.files.is_empty());
+    }
+
+    #[test]
+    fn test_search_ranks_and_groups() {
+        let doc = |kind, text: &str| IndexedDoc::new(kind, text.to_string(), 1);
+        let file = |project: &str, docs| IndexedFile {
+            project: project.to_string(),
+            docs,
+            ..Default::default()
+        };
+        let mut index = TranscriptIndex::default();
+        index.files.insert(
+            "/p/api/1.jsonl".to_string(),
+            file("/api", vec![doc(DocKind::Reply, "The login test is flaky")]),
+        );
+        index.files.insert(
+            "/p/web/2.jsonl".to_string(),
+            file(
+                "/web",
+                vec![
+                    doc(DocKind::Prompt, "fix the flaky login test"),
+                    doc(DocKind::Command, "Bash: npm test login"),
+                ],
+            ),
+        );
+        index.files.insert(
+            "/p/api/3.jsonl".to_string(),
+            file(
+                "/api",
+                vec![doc(DocKind::Prompt, "Login TEST keeps failing")],
+            ),
+        );
+        index.files.insert(
+            "/p/api/4.jsonl".to_string(),
+            file("/api", vec![doc(DocKind::Prompt, "add a login page")]),
+        );
+
+        let hits = index.search("login test", 10);
+        let order: Vec<&str> = hits.iter().map(|h| h.session_id.as_str()).collect();
+        // web has the best session; api's two sessions stay together
+        assert_eq!(order, vec!["2", "3", "1"]);
+        assert_eq!(hits[0].match_count, 2);
+        assert_eq!(hits[0].snippets[0].kind, DocKind::Prompt);
+        assert!(index.search("  ", 10).is_empty());
+        assert_eq!(index.search("login", 1).len(), 1);
+    }
+
+    #[test]
+    fn test_snippet_centers_on_match() {
+        let text = format!("{}needle\nrest", "x".repeat(200));
+        let s = snippet(&text, 200);
+        assert!(s.starts_with('…') && s.contains("needle rest"));
+        assert_eq!(snippet("short needle", 6), "short needle");
+    }
+}
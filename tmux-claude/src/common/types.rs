@@ -1,18 +1,29 @@
 //! Core types used throughout the application.
 
+use crate::common::agent_todos::AgentTodo;
+use crate::common::api_error::format_countdown;
+use crate::common::git::GitStatus;
 use crate::common::ports::ListeningPort;
+use crate::common::risk::Risk;
+use crate::common::tmux::{session_key, TmuxServer};
+use crate::common::usage::SessionUsage;
+use crate::ipc::messages::{PendingTool, ResourceAlert};
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 /// tmux pane information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmuxPane {
     pub index: String,
+    /// Server-unique pane id (e.g. "%3", matches $TMUX_PANE)
+    #[serde(default)]
+    pub id: String,
     pub pid: u32,
     pub cwd: String,
 }
 
 /// tmux window information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmuxWindow {
     pub index: String,
     #[allow(dead_code)]
@@ -21,10 +32,13 @@ pub struct TmuxWindow {
 }
 
 /// tmux session information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TmuxSession {
     pub name: String,
     pub windows: Vec<TmuxWindow>,
+    /// Server this session lives on
+    #[serde(default)]
+    pub server: TmuxServer,
 }
 
 /// Process resource information
@@ -32,6 +46,8 @@ pub struct TmuxSession {
 pub struct ProcessInfo {
     #[allow(dead_code)]
     pub pid: u32,
+    /// Parent PID (used to build the process tree in the detail view)
+    pub parent_pid: Option<u32>,
     pub name: String,
     pub cpu_percent: f32,
     pub memory_kb: u64,
@@ -51,10 +67,36 @@ pub enum ClaudeStatus {
     PlanReview,
     /// Claude asked a question via AskUserQuestion
     QuestionAsked,
+    /// Working, but silent for too long
+    Stalled,
+    /// Claude exited without finishing its turn
+    Crashed,
+    /// Waiting for a rate or usage limit to reset (when, if known)
+    RateLimited(Option<DateTime<Utc>>),
+    /// API error (overloaded, server error, timeout)
+    ApiError(String),
     /// Working or unknown state
     Unknown,
 }
 
+impl ClaudeStatus {
+    /// Whether Claude is blocked on the user (permission, edit, plan or question)
+    /// or needs a look because it stalled, crashed or hit an API error or limit
+    pub fn needs_attention(&self) -> bool {
+        matches!(
+            self,
+            ClaudeStatus::NeedsPermission(_, _)
+                | ClaudeStatus::EditApproval(_)
+                | ClaudeStatus::PlanReview
+                | ClaudeStatus::QuestionAsked
+                | ClaudeStatus::Stalled
+                | ClaudeStatus::Crashed
+                | ClaudeStatus::RateLimited(_)
+                | ClaudeStatus::ApiError(_)
+        )
+    }
+}
+
 impl std::fmt::Display for ClaudeStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -63,15 +105,26 @@ impl std::fmt::Display for ClaudeStatus {
             ClaudeStatus::EditApproval(file) => write!(f, "edit: {}", file),
             ClaudeStatus::PlanReview => write!(f, "plan ready"),
             ClaudeStatus::QuestionAsked => write!(f, "question asked"),
+            ClaudeStatus::Stalled => write!(f, "stalled"),
+            ClaudeStatus::Crashed => write!(f, "crashed"),
+            ClaudeStatus::RateLimited(Some(resets_at)) => write!(
+                f,
+                "rate limited, resets in {}",
+                format_countdown(resets_at, Utc::now())
+            ),
+            ClaudeStatus::RateLimited(None) => write!(f, "rate limited"),
+            ClaudeStatus::ApiError(message) => write!(f, "API error: {}", message),
             ClaudeStatus::Unknown => write!(f, "working"),
         }
     }
 }
 
 /// Info about a displayed session for interactive mode
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct SessionInfo {
     pub name: String,
+    /// tmux server the session lives on
+    pub server: TmuxServer,
     pub claude_status: Option<ClaudeStatus>,
     /// (session, window, pane) for sending keys
     pub claude_pane: Option<(String, String, String)>,
@@ -87,11 +140,56 @@ pub struct SessionInfo {
     pub cwd: Option<String>,
     /// Listening TCP ports in this session's process tree
     pub listening_ports: Vec<ListeningPort>,
+    /// Active resource alert from the daemon (runaway CPU, memory, process count)
+    pub resource_alert: Option<ResourceAlert>,
+    /// Remote host for sessions pulled from another daemon (None = local)
+    pub host: Option<String>,
+    /// Daemon session id (routes approvals of remote sessions through the daemon)
+    pub daemon_session_id: Option<String>,
+    /// Transcript JSONL reported by the session's hooks
+    pub transcript_path: Option<String>,
+    /// Claude's own task list from its latest `TodoWrite` call
+    pub agent_todos: Vec<AgentTodo>,
+    /// Git status of `cwd`, computed by the daemon (None outside a repo or without daemon)
+    pub git: Option<GitStatus>,
+    /// Risk of the pending permission request, classified by the daemon
+    pub risk: Option<Risk>,
+    /// Tool call of the pending permission request
+    pub pending_tool: Option<PendingTool>,
+    /// Token usage and cost of the Claude session, from the daemon
+    pub usage: Option<SessionUsage>,
+}
+
+impl SessionInfo {
+    /// Key of this session's TUI state (see `session_key`)
+    pub fn key(&self) -> String {
+        session_key(&self.server, &self.name)
+    }
 }
 
+/// Session CPU% at which the list turns yellow
+pub const SESSION_CPU_WARN: f32 = 20.0;
+/// Session CPU% at which the list turns red
+pub const SESSION_CPU_HIGH: f32 = 100.0;
+/// Session memory (KB) at which the list turns yellow (~500M)
+pub const SESSION_MEM_WARN_KB: u64 = 512000;
+/// Session memory (KB) at which the list turns red (~2G)
+pub const SESSION_MEM_HIGH_KB: u64 = 2048000;
+/// Per-process CPU% at which the detail view turns yellow (same as tmux-ps colorize_cpu)
+pub const PROC_CPU_WARN: f32 = 10.0;
+/// Per-process CPU% at which the detail view turns red (same as tmux-ps colorize_cpu)
+pub const PROC_CPU_HIGH: f32 = 50.0;
+/// Per-process memory (KB) at which the detail view turns yellow (100M)
+pub const PROC_MEM_WARN_KB: u64 = 102400;
+/// Per-process memory (KB) at which the detail view turns red (500M)
+pub const PROC_MEM_HIGH_KB: u64 = 512000;
+
 /// Letter sequence for permission keys (avoiding 'r' for refresh, 'q' for quit, 'u' for unparked, 'p' for park)
 pub const PERMISSION_KEYS: [char; 6] = ['y', 'z', 'x', 'w', 'v', 't'];
 
+/// Tools that only read, safe to approve in bulk ("approve all reads")
+pub const READ_ONLY_TOOLS: [&str; 5] = ["Read", "Grep", "Glob", "LS", "NotebookRead"];
+
 /// Truncate a command string for display
 pub fn truncate_command(cmd: &str, max_len: usize) -> String {
     if cmd.len() <= max_len {
@@ -150,7 +248,9 @@ pub fn format_rate(bytes: u64) -> String {
 pub fn matches_filter(session_name: &str, filter: &Option<String>) -> bool {
     match filter {
         None => true,
-        Some(pattern) => session_name.to_lowercase().contains(&pattern.to_lowercase()),
+        Some(pattern) => session_name
+            .to_lowercase()
+            .contains(&pattern.to_lowercase()),
     }
 }
 
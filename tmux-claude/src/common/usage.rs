@@ -0,0 +1,379 @@
+//! Token usage and cost, accumulated from the assistant entries of Claude transcripts.
+//!
+//! Claude writes one JSONL entry per content block of a response, each carrying
+//! the response's `usage`, so entries are deduplicated by `message.id`. The latest
+//! main-thread response also tells how full the context window is.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Token counts of one or more API responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub input: u64,
+    pub output: u64,
+    pub cache_read: u64,
+    pub cache_write: u64,
+}
+
+impl Usage {
+    pub fn add(&mut self, other: &Usage) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_read += other.cache_read;
+        self.cache_write += other.cache_write;
+    }
+
+    fn subtract(&mut self, other: &Usage) {
+        self.input = self.input.saturating_sub(other.input);
+        self.output = self.output.saturating_sub(other.output);
+        self.cache_read = self.cache_read.saturating_sub(other.cache_read);
+        self.cache_write = self.cache_write.saturating_sub(other.cache_write);
+    }
+
+    pub fn total(&self) -> u64 {
+        self.input + self.output + self.cache_read + self.cache_write
+    }
+}
+
+/// Context window of models without a configured or observed larger one
+pub const DEFAULT_CONTEXT_WINDOW: u64 = 200_000;
+
+/// Context window of long-context (1M) model variants
+const LONG_CONTEXT_WINDOW: u64 = 1_000_000;
+
+/// Context window of a model: the longest configured key contained in the model id,
+/// else 200k, or 1M once a response used more than 200k
+pub fn context_window(model: &str, tokens: u64, configured: &HashMap<String, u64>) -> u64 {
+    let model = model.to_lowercase();
+    configured
+        .iter()
+        .filter(|(key, _)| model.contains(&key.to_lowercase()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, window)| *window)
+        .unwrap_or(if tokens > DEFAULT_CONTEXT_WINDOW {
+            LONG_CONTEXT_WINDOW
+        } else {
+            DEFAULT_CONTEXT_WINDOW
+        })
+}
+
+/// Context size after the latest main-thread response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ContextSample {
+    pub model: String,
+    /// Prompt (input and cache) plus output tokens: what the next request starts from
+    pub tokens: u64,
+}
+
+/// USD per million tokens
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    /// Default: a tenth of the input price
+    #[serde(default)]
+    pub cache_read: Option<f64>,
+    /// Default: 1.25 times the input price (5 minute cache writes)
+    #[serde(default)]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPrice {
+    const fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_read: None,
+            cache_write: None,
+        }
+    }
+
+    /// Cost of `usage` in USD
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let cache_read = self.cache_read.unwrap_or(self.input * 0.1);
+        let cache_write = self.cache_write.unwrap_or(self.input * 1.25);
+        (usage.input as f64 * self.input
+            + usage.output as f64 * self.output
+            + usage.cache_read as f64 * cache_read
+            + usage.cache_write as f64 * cache_write)
+            / 1_000_000.0
+    }
+}
+
+/// Built-in prices by model id fragment, used when the config has no match
+const DEFAULT_PRICES: [(&str, ModelPrice); 5] = [
+    ("opus-4-5", ModelPrice::new(5.0, 25.0)),
+    ("opus", ModelPrice::new(15.0, 75.0)),
+    ("sonnet", ModelPrice::new(3.0, 15.0)),
+    ("haiku-4-5", ModelPrice::new(1.0, 5.0)),
+    ("haiku", ModelPrice::new(0.8, 4.0)),
+];
+
+/// Price of a model: the longest configured key contained in the model id,
+/// else the longest built-in one
+pub fn price_for(model: &str, prices: &HashMap<String, ModelPrice>) -> Option<ModelPrice> {
+    let model = model.to_lowercase();
+    let configured = prices
+        .iter()
+        .filter(|(key, _)| model.contains(&key.to_lowercase()))
+        .max_by_key(|(key, _)| key.len())
+        .map(|(_, price)| *price);
+    configured.or_else(|| {
+        DEFAULT_PRICES
+            .iter()
+            .filter(|(key, _)| model.contains(key))
+            .max_by_key(|(key, _)| key.len())
+            .map(|(_, price)| *price)
+    })
+}
+
+/// Cost in USD of usage by model (models without a price count as free)
+pub fn cost_of(by_model: &HashMap<String, Usage>, prices: &HashMap<String, ModelPrice>) -> f64 {
+    by_model
+        .iter()
+        .filter_map(|(model, usage)| Some(price_for(model, prices)?.cost(usage)))
+        .sum()
+}
+
+/// Usage of one API response
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsageRecord {
+    pub message_id: String,
+    pub model: String,
+    /// UTC day of the response (YYYY-MM-DD)
+    pub day: String,
+    pub usage: Usage,
+    /// Response of a subagent, whose context is separate from the session's
+    pub sidechain: bool,
+}
+
+/// Usage of an assistant entry (None for other entries and synthetic messages)
+pub fn usage_record(entry: &serde_json::Value) -> Option<UsageRecord> {
+    if entry.get("type")?.as_str()? != "assistant" {
+        return None;
+    }
+    let message = entry.get("message")?;
+    let model = message.get("model")?.as_str()?;
+    if model.starts_with('<') {
+        return None;
+    }
+    let usage = message.get("usage")?;
+    let count = |key: &str| usage.get(key).and_then(|v| v.as_u64()).unwrap_or(0);
+    Some(UsageRecord {
+        message_id: message.get("id")?.as_str()?.to_string(),
+        model: model.to_string(),
+        day: entry
+            .get("timestamp")
+            .and_then(|t| t.as_str())
+            .and_then(|t| t.get(..10))
+            .unwrap_or_default()
+            .to_string(),
+        usage: Usage {
+            input: count("input_tokens"),
+            output: count("output_tokens"),
+            cache_read: count("cache_read_input_tokens"),
+            cache_write: count("cache_creation_input_tokens"),
+        },
+        sidechain: entry
+            .get("isSidechain")
+            .and_then(|s| s.as_bool())
+            .unwrap_or(false),
+    })
+}
+
+/// Usage of one transcript by day and model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageLedger {
+    /// UTC day (YYYY-MM-DD) -> model -> usage
+    pub days: BTreeMap<String, HashMap<String, Usage>>,
+    /// Last response counted, replaced rather than added when its id repeats
+    #[serde(default)]
+    last: Option<(String, String, String, Usage)>,
+    /// Context size after the latest main-thread response
+    #[serde(default)]
+    pub context: Option<ContextSample>,
+}
+
+impl UsageLedger {
+    /// Count a response once, however many content-block entries repeat it
+    pub fn record(&mut self, record: UsageRecord) {
+        if let Some((id, day, model, previous)) = self.last.take() {
+            if id == record.message_id {
+                if let Some(usage) = self.days.get_mut(&day).and_then(|m| m.get_mut(&model)) {
+                    usage.subtract(&previous);
+                }
+            }
+        }
+        self.days
+            .entry(record.day.clone())
+            .or_default()
+            .entry(record.model.clone())
+            .or_default()
+            .add(&record.usage);
+        if !record.sidechain {
+            self.context = Some(ContextSample {
+                model: record.model.clone(),
+                tokens: record.usage.total(),
+            });
+        }
+        self.last = Some((record.message_id, record.day, record.model, record.usage));
+    }
+
+    /// Usage by model on days from `since` (YYYY-MM-DD, inclusive; None = all)
+    pub fn by_model(&self, since: Option<&str>) -> HashMap<String, Usage> {
+        let mut totals: HashMap<String, Usage> = HashMap::new();
+        for (day, models) in &self.days {
+            if since.is_some_and(|since| day.as_str() < since) {
+                continue;
+            }
+            for (model, usage) in models {
+                totals.entry(model.clone()).or_default().add(usage);
+            }
+        }
+        totals
+    }
+}
+
+/// Usage and cost of a Claude session, with its budget and context fill
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionUsage {
+    pub tokens: Usage,
+    pub cost_usd: f64,
+    pub budget_usd: Option<f64>,
+    /// Tokens in the context window after the latest response
+    #[serde(default)]
+    pub context_tokens: u64,
+    /// Context window of the session's model (0 = unknown)
+    #[serde(default)]
+    pub context_window: u64,
+}
+
+impl SessionUsage {
+    pub fn over_budget(&self) -> bool {
+        self.budget_usd.is_some_and(|budget| self.cost_usd > budget)
+    }
+
+    /// How full the context window is (0-100), if known
+    pub fn context_percent(&self) -> Option<f64> {
+        (self.context_window > 0)
+            .then(|| (self.context_tokens as f64 * 100.0 / self.context_window as f64).min(100.0))
+    }
+}
+
+/// "950", "12k", "3.4M"
+pub fn format_tokens(count: u64) -> String {
+    if count < 1000 {
+        count.to_string()
+    } else if count < 1_000_000 {
+        format!("{}k", count / 1000)
+    } else {
+        format!("{:.1}M", count as f64 / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, output: u64) -> serde_json::Value {
+        serde_json::json!({
+            "type": "assistant",
+            "timestamp": "2026-03-02T10:00:00Z",
+            "message": {
+                "id": id,
+                "model": "claude-sonnet-4-5",
+                "usage": {
+                    "input_tokens": 100,
+                    "output_tokens": output,
+                    "cache_read_input_tokens": 1000,
+                    "cache_creation_input_tokens": 0
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_ledger_counts_each_message_once() {
+        let mut ledger = UsageLedger::default();
+        // One response split over three content-block entries, the last with final counts
+        for output in [10, 10, 50] {
+            ledger.record(usage_record(&entry("msg_1", output)).unwrap());
+        }
+        ledger.record(usage_record(&entry("msg_2", 5)).unwrap());
+        let totals = ledger.by_model(None);
+        let usage = totals["claude-sonnet-4-5"];
+        assert_eq!(
+            (usage.input, usage.output, usage.cache_read),
+            (200, 55, 2000)
+        );
+        assert!(ledger.by_model(Some("2026-03-03")).is_empty());
+        assert_eq!(ledger.by_model(Some("2026-03-02")).len(), 1);
+
+        let mut synthetic = entry("msg_3", 1);
+        synthetic["message"]["model"] = "<synthetic>".into();
+        assert_eq!(usage_record(&synthetic), None);
+    }
+
+    #[test]
+    fn test_context_follows_main_thread() {
+        let mut ledger = UsageLedger::default();
+        ledger.record(usage_record(&entry("msg_1", 50)).unwrap());
+        assert_eq!(ledger.context.as_ref().unwrap().tokens, 1150);
+
+        // A subagent's response doesn't change the session's context
+        let mut subagent = entry("msg_2", 7);
+        subagent["isSidechain"] = true.into();
+        ledger.record(usage_record(&subagent).unwrap());
+        assert_eq!(ledger.context.as_ref().unwrap().tokens, 1150);
+
+        let mut configured = HashMap::new();
+        assert_eq!(
+            context_window("claude-sonnet-4-5", 150_000, &configured),
+            200_000
+        );
+        assert_eq!(
+            context_window("claude-sonnet-4-5", 250_000, &configured),
+            1_000_000
+        );
+        configured.insert("sonnet".to_string(), 500_000);
+        assert_eq!(
+            context_window("claude-sonnet-4-5", 250_000, &configured),
+            500_000
+        );
+
+        let usage = SessionUsage {
+            context_tokens: 170_000,
+            context_window: 200_000,
+            ..Default::default()
+        };
+        assert_eq!(usage.context_percent(), Some(85.0));
+    }
+
+    #[test]
+    fn test_prices_prefer_longest_configured_match() {
+        let mut prices = HashMap::new();
+        assert_eq!(
+            price_for("claude-opus-4-5-20251101", &prices)
+                .unwrap()
+                .input,
+            5.0
+        );
+        assert_eq!(price_for("claude-opus-4-1", &prices).unwrap().input, 15.0);
+        assert_eq!(price_for("gpt-4", &prices), None);
+
+        prices.insert("claude-opus-4-1".to_string(), ModelPrice::new(10.0, 50.0));
+        prices.insert("opus".to_string(), ModelPrice::new(1.0, 1.0));
+        assert_eq!(price_for("claude-opus-4-1", &prices).unwrap().input, 10.0);
+
+        // 1M input at $3, 1M cache reads at $0.30
+        let usage = Usage {
+            input: 1_000_000,
+            cache_read: 1_000_000,
+            ..Default::default()
+        };
+        let cost = ModelPrice::new(3.0, 15.0).cost(&usage);
+        assert!((cost - 3.3).abs() < 1e-9);
+        assert_eq!(format_tokens(3_400_000), "3.4M");
+    }
+}
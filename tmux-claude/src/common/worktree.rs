@@ -0,0 +1,285 @@
+//! Git worktrees created per agent session, and the checks before removing them.
+
+use crate::common::git::git;
+use crate::common::tmux::{current_server, TmuxServer};
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+
+/// A worktree a session was started in
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionWorktree {
+    /// Main checkout of the repository
+    pub repo: PathBuf,
+    /// Branch checked out in the worktree
+    pub branch: String,
+    /// Worktree directory
+    pub path: PathBuf,
+    /// tmux server the session was started on (where it's reopened)
+    pub server: TmuxServer,
+}
+
+/// Branch name as a directory/session suffix ("feat/login" -> "feat-login")
+pub fn branch_slug(branch: &str) -> String {
+    branch
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('-')
+        .to_string()
+}
+
+/// Where the worktree for `branch` goes: `<root>/<repo>-<branch>`, with the
+/// repository's parent directory as the default root
+pub fn worktree_path(repo: &Path, branch: &str, root: Option<&Path>) -> PathBuf {
+    let repo_name = repo
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "repo".to_string());
+    let dir_name = format!("{}-{}", repo_name, branch_slug(branch));
+    match root {
+        Some(root) => root.join(dir_name),
+        None => repo.parent().unwrap_or(repo).join(dir_name),
+    }
+}
+
+/// Branch checked out in the worktree at `path`, from `git worktree list --porcelain`
+/// (None if `path` isn't a worktree of the repo; Some("") for a detached HEAD)
+fn worktree_branch(porcelain: &str, path: &Path) -> Option<String> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    porcelain.split("\n\n").find_map(|entry| {
+        let mut lines = entry.lines();
+        let listed = lines.next()?.strip_prefix("worktree ")?;
+        let listed = std::fs::canonicalize(listed).unwrap_or_else(|_| PathBuf::from(listed));
+        if listed != path {
+            return None;
+        }
+        let branch = lines
+            .find_map(|line| line.strip_prefix("branch refs/heads/"))
+            .unwrap_or_default();
+        Some(branch.to_string())
+    })
+}
+
+/// Worktree for `branch` of the repo containing `dir`, for a session on the current
+/// tmux server. Reuses an existing worktree of that branch, checks out an existing
+/// branch, or creates a new branch from HEAD.
+pub fn create_worktree(dir: &Path, branch: &str, root: Option<&Path>) -> Result<SessionWorktree> {
+    let repo = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?);
+    let path = worktree_path(&repo, branch, root);
+    if path.exists() {
+        // Only reuse the directory if it's this repo's worktree of this branch
+        let porcelain = git(&repo, &["worktree", "list", "--porcelain"])?;
+        match worktree_branch(&porcelain, &path) {
+            Some(existing) if existing == branch => {}
+            Some(existing) if existing.is_empty() => {
+                bail!("{} is a worktree with a detached HEAD", path.display())
+            }
+            Some(existing) => bail!(
+                "{} is a worktree of branch '{}', not '{}'",
+                path.display(),
+                existing,
+                branch
+            ),
+            None => bail!(
+                "{} exists and is not a worktree of {}",
+                path.display(),
+                repo.display()
+            ),
+        }
+    } else {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Cannot create {}", parent.display()))?;
+        }
+        let path_str = path.to_string_lossy().to_string();
+        let branch_ref = format!("refs/heads/{}", branch);
+        if git(&repo, &["rev-parse", "--verify", "--quiet", &branch_ref]).is_ok() {
+            git(&repo, &["worktree", "add", &path_str, branch])?;
+        } else {
+            git(&repo, &["worktree", "add", "-b", branch, &path_str])?;
+        }
+    }
+    Ok(SessionWorktree {
+        repo,
+        branch: branch.to_string(),
+        path,
+        server: current_server(),
+    })
+}
+
+/// Why the worktree must be kept, or None if it's clean and its branch is
+/// merged into the main checkout's HEAD (so removing it loses nothing)
+pub fn removal_blocker(worktree: &SessionWorktree) -> Option<String> {
+    if worktree.path.is_dir() {
+        match git(&worktree.path, &["status", "--porcelain"]) {
+            Ok(status) if !status.is_empty() => return Some("uncommitted changes".to_string()),
+            Ok(_) => {}
+            Err(e) => return Some(format!("{:#}", e)),
+        }
+    }
+    let merged = git(
+        &worktree.repo,
+        &["merge-base", "--is-ancestor", &worktree.branch, "HEAD"],
+    );
+    if merged.is_err() {
+        return Some(format!("branch '{}' is not merged", worktree.branch));
+    }
+    None
+}
+
+/// Remove the worktree and its (merged) branch
+pub fn remove_worktree(worktree: &SessionWorktree) -> Result<()> {
+    if worktree.path.is_dir() {
+        let path = worktree.path.to_string_lossy().to_string();
+        git(&worktree.repo, &["worktree", "remove", &path])?;
+    } else {
+        let _ = git(&worktree.repo, &["worktree", "prune"]);
+    }
+    // -d refuses unmerged branches, so this never loses commits
+    let _ = git(&worktree.repo, &["branch", "-d", &worktree.branch]);
+    Ok(())
+}
+
+/// Recreate a parked worktree session's tmux session and switch to it
+pub fn reopen_worktree_session(name: &str, worktree: &SessionWorktree) -> bool {
+    if !worktree.path.is_dir() {
+        return false;
+    }
+    let server = &worktree.server;
+    let created = server
+        .command()
+        .args(["new-session", "-d", "-s", name, "-c"])
+        .arg(&worktree.path)
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if created {
+        let _ = server
+            .command()
+            .args(["switch-client", "-t", name])
+            .output();
+    }
+    created
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    /// Run git in a test repo with a fixed identity
+    fn test_git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_branch_slug_and_path() {
+        assert_eq!(branch_slug("feat/login.page"), "feat-login-page");
+        assert_eq!(branch_slug("/fix/"), "fix");
+        let repo = Path::new("/work/api");
+        assert_eq!(
+            worktree_path(repo, "feat/x", None),
+            PathBuf::from("/work/api-feat-x")
+        );
+        assert_eq!(
+            worktree_path(repo, "feat/x", Some(Path::new("/trees"))),
+            PathBuf::from("/trees/api-feat-x")
+        );
+    }
+
+    #[test]
+    fn test_removal_requires_clean_and_merged() {
+        let base = std::env::temp_dir().join(format!("tmux-claude-wt-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let repo = base.join("repo");
+        std::fs::create_dir_all(&repo).unwrap();
+        test_git(&repo, &["init", "-q"]);
+        test_git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+
+        let worktree = create_worktree(&repo, "agent/one", Some(&base.join("trees"))).unwrap();
+        assert_eq!(worktree.path, base.join("trees").join("repo-agent-one"));
+        assert_eq!(removal_blocker(&worktree), None);
+
+        std::fs::write(worktree.path.join("notes.txt"), "wip").unwrap();
+        assert_eq!(
+            removal_blocker(&worktree).as_deref(),
+            Some("uncommitted changes")
+        );
+
+        test_git(&worktree.path, &["add", "notes.txt"]);
+        test_git(&worktree.path, &["commit", "-q", "-m", "wip"]);
+        assert_eq!(
+            removal_blocker(&worktree).as_deref(),
+            Some("branch 'agent/one' is not merged")
+        );
+
+        test_git(&repo, &["merge", "-q", "--ff-only", "agent/one"]);
+        assert_eq!(removal_blocker(&worktree), None);
+        remove_worktree(&worktree).unwrap();
+        assert!(!worktree.path.exists());
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_existing_directory_must_be_the_branch_worktree() {
+        let base =
+            std::env::temp_dir().join(format!("tmux-claude-wt-reuse-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&base);
+        let repo = base.join("repo");
+        let trees = base.join("trees");
+        std::fs::create_dir_all(&repo).unwrap();
+        test_git(&repo, &["init", "-q"]);
+        test_git(&repo, &["commit", "-q", "--allow-empty", "-m", "init"]);
+
+        // Same branch again reuses the worktree
+        let first = create_worktree(&repo, "agent/one", Some(&trees)).unwrap();
+        let again = create_worktree(&repo, "agent/one", Some(&trees)).unwrap();
+        assert_eq!(again.path, first.path);
+
+        // A plain directory in the way is not taken over
+        std::fs::create_dir_all(trees.join("repo-agent-two")).unwrap();
+        let err = create_worktree(&repo, "agent/two", Some(&trees)).unwrap_err();
+        assert!(err.to_string().contains("is not a worktree"), "{:#}", err);
+
+        // Nor is a worktree of another branch that maps to the same directory
+        let other = trees.join("repo-agent-three").to_string_lossy().to_string();
+        test_git(
+            &repo,
+            &["worktree", "add", "-q", "-b", "agent-three", &other],
+        );
+        let err = create_worktree(&repo, "agent/three", Some(&trees)).unwrap_err();
+        assert!(
+            err.to_string().contains("branch 'agent-three'"),
+            "{:#}",
+            err
+        );
+
+        let _ = std::fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn test_worktree_branch_parses_porcelain() {
+        let porcelain = "worktree /nonexistent/repo\nHEAD 1111\nbranch refs/heads/main\n\n\
+                         worktree /nonexistent/repo-x\nHEAD 2222\nbranch refs/heads/feat/x\n\n\
+                         worktree /nonexistent/repo-d\nHEAD 3333\ndetached\n";
+        let branch = |p: &str| worktree_branch(porcelain, Path::new(p));
+        assert_eq!(branch("/nonexistent/repo-x").as_deref(), Some("feat/x"));
+        assert_eq!(branch("/nonexistent/repo-d").as_deref(), Some(""));
+        assert_eq!(branch("/nonexistent/other"), None);
+    }
+}
@@ -0,0 +1,325 @@
+//! Resource alert evaluation (runaway CPU, memory, process count) for the daemon.
+
+use crate::common::config::{AlertConfig, AlertRule};
+use crate::common::tmux::TmuxServer;
+use crate::common::types::ProcessInfo;
+use crate::ipc::messages::{AlertKind, ResourceAlert};
+use std::collections::{HashMap, HashSet};
+use std::mem::discriminant;
+use std::time::{Duration, Instant};
+
+/// Tracks sustained CPU usage and the currently active alert per session
+#[derive(Debug, Default)]
+pub struct AlertTracker {
+    /// (server, session, pid) -> when the process first went above the CPU threshold
+    cpu_over_since: HashMap<(TmuxServer, String, u32), Instant>,
+    /// Active alert per tmux session (server, session name)
+    active: HashMap<(TmuxServer, String), ResourceAlert>,
+}
+
+impl AlertTracker {
+    /// Evaluate one metrics sample (processes grouped by tmux server and session name).
+    /// Returns the alerts raised by this sample that were not already active.
+    pub fn evaluate(
+        &mut self,
+        config: &AlertConfig,
+        sessions: &[(TmuxServer, String, Vec<ProcessInfo>)],
+        now: Instant,
+    ) -> Vec<ResourceAlert> {
+        if !config.enabled {
+            self.cpu_over_since.clear();
+            self.active.clear();
+            return Vec::new();
+        }
+
+        let mut seen_cpu = HashSet::new();
+        let mut active = HashMap::new();
+        let mut raised = Vec::new();
+
+        for (server, name, processes) in sessions {
+            let rule = config.rule_for(name);
+            let Some(alert) =
+                self.check_session(server, name, &rule, processes, now, &mut seen_cpu)
+            else {
+                continue;
+            };
+            let key = (server.clone(), name.clone());
+
+            // Only notify when the alert is new (or now points at a different process/kind)
+            let is_new = self
+                .active
+                .get(&key)
+                .map(|prev| {
+                    prev.pid != alert.pid || discriminant(&prev.kind) != discriminant(&alert.kind)
+                })
+                .unwrap_or(true);
+            if is_new {
+                raised.push(alert.clone());
+            }
+            active.insert(key, alert);
+        }
+
+        // Forget processes that dropped below the threshold or exited
+        self.cpu_over_since.retain(|key, _| seen_cpu.contains(key));
+        self.active = active;
+        raised
+    }
+
+    /// Check a single session against its rule (CPU, then RSS, then process count)
+    fn check_session(
+        &mut self,
+        server: &TmuxServer,
+        name: &str,
+        rule: &AlertRule,
+        processes: &[ProcessInfo],
+        now: Instant,
+        seen_cpu: &mut HashSet<(TmuxServer, String, u32)>,
+    ) -> Option<ResourceAlert> {
+        let mut cpu_alert: Option<(&ProcessInfo, u64)> = None;
+        if let Some(threshold) = rule.cpu_percent {
+            let sustained = Duration::from_secs(rule.cpu_sustained_secs.unwrap_or(0));
+            for proc in processes.iter().filter(|p| p.cpu_percent >= threshold) {
+                let key = (server.clone(), name.to_string(), proc.pid);
+                let since = *self.cpu_over_since.entry(key.clone()).or_insert(now);
+                seen_cpu.insert(key);
+                let elapsed = now.saturating_duration_since(since);
+                if elapsed >= sustained
+                    && cpu_alert.is_none_or(|(p, _)| proc.cpu_percent > p.cpu_percent)
+                {
+                    cpu_alert = Some((proc, elapsed.as_secs()));
+                }
+            }
+        }
+
+        let alert = |proc: &ProcessInfo, kind: AlertKind| ResourceAlert {
+            session: name.to_string(),
+            server: server.clone(),
+            pid: proc.pid,
+            process_name: proc.name.clone(),
+            kind,
+        };
+
+        if let Some((proc, secs)) = cpu_alert {
+            return Some(alert(
+                proc,
+                AlertKind::Cpu {
+                    percent: proc.cpu_percent,
+                    sustained_secs: secs,
+                },
+            ));
+        }
+
+        if let Some(limit) = rule.rss_kb {
+            if let Some(proc) = processes
+                .iter()
+                .filter(|p| p.memory_kb > limit)
+                .max_by_key(|p| p.memory_kb)
+            {
+                return Some(alert(proc, AlertKind::Rss { kb: proc.memory_kb }));
+            }
+        }
+
+        if let Some(limit) = rule.max_processes {
+            if processes.len() > limit {
+                let busiest = processes.iter().max_by(|a, b| {
+                    a.cpu_percent
+                        .partial_cmp(&b.cpu_percent)
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })?;
+                return Some(alert(
+                    busiest,
+                    AlertKind::ProcessCount {
+                        count: processes.len(),
+                    },
+                ));
+            }
+        }
+
+        None
+    }
+
+    /// Get all active alerts, sorted by session name
+    pub fn active_alerts(&self) -> Vec<ResourceAlert> {
+        let mut alerts: Vec<ResourceAlert> = self.active.values().cloned().collect();
+        alerts.sort_by(|a, b| a.session.cmp(&b.session));
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn proc(pid: u32, cpu: f32, mem_kb: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            parent_pid: None,
+            name: "node".to_string(),
+            cpu_percent: cpu,
+            memory_kb: mem_kb,
+            command: "node test.js".to_string(),
+        }
+    }
+
+    fn session(name: &str, procs: Vec<ProcessInfo>) -> (TmuxServer, String, Vec<ProcessInfo>) {
+        (TmuxServer::Current, name.to_string(), procs)
+    }
+
+    fn config(rule: AlertRule) -> AlertConfig {
+        AlertConfig {
+            enabled: true,
+            global: rule,
+            sessions: HashMap::new(),
+            stall_after_secs: None,
+        }
+    }
+
+    fn cpu_rule() -> AlertRule {
+        AlertRule {
+            cpu_percent: Some(100.0),
+            cpu_sustained_secs: Some(60),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_cpu_alert_requires_sustained_usage() {
+        let mut tracker = AlertTracker::default();
+        let cfg = config(cpu_rule());
+        let start = Instant::now();
+        let sample = vec![session("work", vec![proc(42, 400.0, 1000)])];
+
+        assert!(tracker.evaluate(&cfg, &sample, start).is_empty());
+        assert!(tracker
+            .evaluate(&cfg, &sample, start + Duration::from_secs(30))
+            .is_empty());
+
+        let raised = tracker.evaluate(&cfg, &sample, start + Duration::from_secs(60));
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].pid, 42);
+        assert!(matches!(raised[0].kind, AlertKind::Cpu { .. }));
+    }
+
+    #[test]
+    fn test_cpu_dip_resets_sustained_timer() {
+        let mut tracker = AlertTracker::default();
+        let cfg = config(cpu_rule());
+        let start = Instant::now();
+        let hot = vec![session("work", vec![proc(42, 400.0, 1000)])];
+        let cool = vec![session("work", vec![proc(42, 5.0, 1000)])];
+
+        tracker.evaluate(&cfg, &hot, start);
+        tracker.evaluate(&cfg, &cool, start + Duration::from_secs(30));
+        assert!(tracker
+            .evaluate(&cfg, &hot, start + Duration::from_secs(61))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_cpu_timer_is_per_server() {
+        let mut tracker = AlertTracker::default();
+        let cfg = config(cpu_rule());
+        let start = Instant::now();
+        let other = TmuxServer::Named("other".to_string());
+        let here = vec![session("work", vec![proc(42, 400.0, 1000)])];
+        let both = vec![
+            session("work", vec![proc(42, 400.0, 1000)]),
+            (
+                other.clone(),
+                "work".to_string(),
+                vec![proc(42, 400.0, 1000)],
+            ),
+        ];
+
+        tracker.evaluate(&cfg, &here, start);
+        let raised = tracker.evaluate(&cfg, &both, start + Duration::from_secs(60));
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].server, TmuxServer::Current);
+    }
+
+    #[test]
+    fn test_alert_only_raised_once_and_clears() {
+        let mut tracker = AlertTracker::default();
+        let cfg = config(AlertRule {
+            rss_kb: Some(1000),
+            ..Default::default()
+        });
+        let now = Instant::now();
+        let big = vec![session("work", vec![proc(7, 0.0, 5000)])];
+
+        assert_eq!(tracker.evaluate(&cfg, &big, now).len(), 1);
+        assert!(tracker.evaluate(&cfg, &big, now).is_empty());
+        assert_eq!(tracker.active_alerts().len(), 1);
+
+        let small = vec![session("work", vec![proc(7, 0.0, 10)])];
+        tracker.evaluate(&cfg, &small, now);
+        assert!(tracker.active_alerts().is_empty());
+    }
+
+    #[test]
+    fn test_process_count_alert_names_busiest_process() {
+        let mut tracker = AlertTracker::default();
+        let cfg = config(AlertRule {
+            max_processes: Some(2),
+            ..Default::default()
+        });
+        let sample = vec![session(
+            "work",
+            vec![proc(1, 1.0, 10), proc(2, 30.0, 10), proc(3, 2.0, 10)],
+        )];
+        let raised = tracker.evaluate(&cfg, &sample, Instant::now());
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].pid, 2);
+        assert_eq!(raised[0].kind, AlertKind::ProcessCount { count: 3 });
+    }
+
+    #[test]
+    fn test_session_override_raises_threshold() {
+        let mut tracker = AlertTracker::default();
+        let mut cfg = config(AlertRule {
+            rss_kb: Some(1000),
+            ..Default::default()
+        });
+        cfg.sessions.insert(
+            "big".to_string(),
+            AlertRule {
+                rss_kb: Some(100_000),
+                ..Default::default()
+            },
+        );
+        let sample = vec![
+            session("big", vec![proc(1, 0.0, 5000)]),
+            session("small", vec![proc(2, 0.0, 5000)]),
+        ];
+        let raised = tracker.evaluate(&cfg, &sample, Instant::now());
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].session, "small");
+    }
+
+    #[test]
+    fn test_session_override_zero_disables_check() {
+        let mut tracker = AlertTracker::default();
+        let mut cfg = config(AlertRule {
+            rss_kb: Some(1000),
+            max_processes: Some(1),
+            ..Default::default()
+        });
+        cfg.sessions.insert(
+            "build".to_string(),
+            AlertRule {
+                rss_kb: Some(0),
+                ..Default::default()
+            },
+        );
+        assert_eq!(cfg.rule_for("build").rss_kb, None);
+        assert_eq!(cfg.rule_for("build").max_processes, Some(1));
+
+        let sample = vec![
+            session("build", vec![proc(1, 0.0, 5000)]),
+            session("other", vec![proc(2, 0.0, 5000)]),
+        ];
+        let raised = tracker.evaluate(&cfg, &sample, Instant::now());
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].session, "other");
+    }
+}
@@ -0,0 +1,156 @@
+//! Background git status per session cwd, cached and rate limited.
+//!
+//! Agents commit and edit constantly, so statuses are refreshed on a timer
+//! rather than on demand, with a cap on git invocations per round so a long
+//! session list can't turn the daemon into a git process storm.
+
+use crate::common::git::{git_status, GitStatus};
+use crate::daemon::state::DaemonState;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// How often the cache looks for stale entries
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Minimum age before a cwd's status is recomputed
+const REFRESH_AFTER: Duration = Duration::from_secs(15);
+
+/// Most cwds refreshed per round (each costs two git processes)
+const MAX_PER_ROUND: usize = 8;
+
+/// Last computed status of one cwd
+#[derive(Debug, Clone)]
+struct CachedStatus {
+    /// None when the cwd isn't inside a git repository
+    status: Option<GitStatus>,
+    checked: Instant,
+}
+
+/// Git statuses by cwd
+#[derive(Debug, Default)]
+pub struct GitCache {
+    entries: HashMap<String, CachedStatus>,
+}
+
+impl GitCache {
+    /// Cwds that were never checked or are older than `REFRESH_AFTER`, never-checked
+    /// and oldest first, at most `limit`
+    pub fn due(&self, cwds: &HashSet<String>, now: Instant, limit: usize) -> Vec<String> {
+        let mut due: Vec<(Option<Instant>, &String)> = cwds
+            .iter()
+            .filter_map(|cwd| match self.entries.get(cwd) {
+                None => Some((None, cwd)),
+                Some(entry) if now.duration_since(entry.checked) >= REFRESH_AFTER => {
+                    Some((Some(entry.checked), cwd))
+                }
+                Some(_) => None,
+            })
+            .collect();
+        due.sort();
+        due.into_iter()
+            .take(limit)
+            .map(|(_, cwd)| cwd.clone())
+            .collect()
+    }
+
+    /// Store a freshly computed status
+    pub fn update(&mut self, cwd: String, status: Option<GitStatus>, now: Instant) {
+        self.entries.insert(
+            cwd,
+            CachedStatus {
+                status,
+                checked: now,
+            },
+        );
+    }
+
+    /// Forget cwds no session uses any more
+    pub fn retain(&mut self, cwds: &HashSet<String>) {
+        self.entries.retain(|cwd, _| cwds.contains(cwd));
+    }
+
+    /// Known statuses of cwds inside a repository
+    pub fn statuses(&self) -> HashMap<String, GitStatus> {
+        self.entries
+            .iter()
+            .filter_map(|(cwd, entry)| Some((cwd.clone(), entry.status.clone()?)))
+            .collect()
+    }
+}
+
+/// Cwds worth tracking: each tmux session's first pane (what the TUI shows) and Claude sessions
+fn watched_cwds(state: &DaemonState) -> HashSet<String> {
+    let mut cwds: HashSet<String> = state
+        .tmux_sessions
+        .iter()
+        .flatten()
+        .filter_map(|session| session.windows.first()?.panes.first())
+        .map(|pane| pane.cwd.clone())
+        .collect();
+    cwds.extend(state.sessions.values().map(|s| s.cwd.clone()));
+    cwds.retain(|cwd| !cwd.is_empty());
+    cwds
+}
+
+/// Keep `state.git` fresh for all watched cwds (runs forever)
+pub async fn run(state: Arc<RwLock<DaemonState>>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let due = {
+            let mut state = state.write().await;
+            let cwds = watched_cwds(&state);
+            state.git.retain(&cwds);
+            state.git.due(&cwds, Instant::now(), MAX_PER_ROUND)
+        };
+
+        for cwd in due {
+            let path = PathBuf::from(&cwd);
+            let status = tokio::task::spawn_blocking(move || git_status(&path))
+                .await
+                .ok()
+                .flatten();
+            state.write().await.git.update(cwd, status, Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_due_prefers_unchecked_then_oldest_and_limits() {
+        let start = Instant::now();
+        let mut cache = GitCache::default();
+        cache.update("/a".to_string(), None, start);
+        cache.update(
+            "/b".to_string(),
+            Some(GitStatus::default()),
+            start + Duration::from_secs(5),
+        );
+        cache.update("/c".to_string(), None, start + Duration::from_secs(10));
+        let cwds: HashSet<String> = ["/a", "/b", "/c", "/new"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        // Only the unchecked cwd is due before anything goes stale
+        assert_eq!(
+            cache.due(&cwds, start + Duration::from_secs(12), 8),
+            vec!["/new"]
+        );
+
+        let later = start + Duration::from_secs(21);
+        assert_eq!(cache.due(&cwds, later, 8), vec!["/new", "/a", "/b"]);
+        assert_eq!(cache.due(&cwds, later, 2), vec!["/new", "/a"]);
+
+        // Non-repos are cached but not reported
+        assert_eq!(cache.statuses().len(), 1);
+        cache.retain(&["/a".to_string()].into_iter().collect());
+        assert!(cache.statuses().is_empty());
+    }
+}
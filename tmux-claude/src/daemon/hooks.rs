@@ -1,17 +1,33 @@
 //! Hook event handlers for the daemon.
 
+use crate::common::agent_todos::AgentTodo;
+use crate::common::api_error::classify_message;
+use crate::common::config::RiskRule;
+use crate::common::risk::classify_tool;
+use crate::common::tmux::{find_pane_by_id, TmuxServer};
 use crate::daemon::state::DaemonState;
-use crate::ipc::messages::{HookEvent, InputSource, SessionState, SessionStatus};
-use chrono::Utc;
+use crate::ipc::messages::{
+    HookEvent, InputSource, PendingTool, SessionState, SessionStatus, TmuxLocation,
+};
+use chrono::{Local, Utc};
+use std::path::Path;
 
 /// Handle a hook event and update daemon state
-pub fn handle_hook_event(state: &mut DaemonState, event: HookEvent) -> Option<SessionState> {
+pub fn handle_hook_event(
+    state: &mut DaemonState,
+    event: HookEvent,
+    risk_rules: &[RiskRule],
+) -> Option<SessionState> {
     let session_id = event.session_id().to_string();
     let cwd = event.cwd().to_string();
     let now = Utc::now().to_rfc3339();
 
     // Ensure session exists
     if !state.sessions.contains_key(&session_id) {
+        // The first hook of a session started by `new` replaces its placeholder
+        if let Some(location) = event.tmux_location() {
+            state.remove_session(&pending_session_id(location));
+        }
         // Create a placeholder session - tmux info will be populated on next refresh
         let session = SessionState::new(
             session_id.clone(),
@@ -23,14 +39,46 @@ pub fn handle_hook_event(state: &mut DaemonState, event: HookEvent) -> Option<Se
         state.upsert_session(session);
     }
 
+    // Record which server/pane the session runs in so approvals and notifications reach it
+    if let Some(location) = event.tmux_location() {
+        let server = TmuxServer::from_socket_path(Path::new(&location.socket));
+        let target = state
+            .tmux_sessions
+            .as_deref()
+            .and_then(|sessions| find_pane_by_id(sessions, &server, &location.pane_id));
+        if let Some(session) = state.get_session_mut(&session_id) {
+            session.tmux_server = server;
+            session.tmux_pane_id = location.pane_id.clone();
+            if let Some((tmux_session, tmux_window, tmux_pane)) = target {
+                session.tmux_session = tmux_session;
+                session.tmux_window = tmux_window;
+                session.tmux_pane = tmux_pane;
+            }
+        }
+    }
+
+    // Remember the transcript so it's found even where the cwd encoding doesn't match
+    if let Some(path) = event.transcript_path().filter(|path| !path.is_empty()) {
+        if let Some(session) = state.get_session_mut(&session_id) {
+            session.transcript_path = Some(path.to_string());
+        }
+    }
+
     // Compute new status and fields based on the event
     let (new_status, new_needs_attention, new_input_source, clear_approval) = match &event {
-        HookEvent::Stop { .. } => (
-            Some(SessionStatus::Waiting),
-            Some(false),
-            None,
-            false,
-        ),
+        // A turn that ended on an API error or limit keeps showing it
+        HookEvent::Stop { .. }
+            if state.get_session(&session_id).is_some_and(|s| {
+                matches!(
+                    s.status,
+                    SessionStatus::RateLimited { .. } | SessionStatus::ApiError { .. }
+                )
+            }) =>
+        {
+            (None, None, None, false)
+        }
+
+        HookEvent::Stop { .. } => (Some(SessionStatus::Waiting), Some(false), None, false),
 
         HookEvent::PreToolUse { tool_name, .. } => {
             // PreToolUse just means a tool is being used - check for special cases
@@ -75,7 +123,7 @@ pub fn handle_hook_event(state: &mut DaemonState, event: HookEvent) -> Option<Se
                         description,
                     }
                 }
-                "Write" | "Edit" => {
+                "Write" | "Edit" | "MultiEdit" => {
                     let filename = tool_input
                         .as_ref()
                         .and_then(|input| {
@@ -87,10 +135,21 @@ pub fn handle_hook_event(state: &mut DaemonState, event: HookEvent) -> Option<Se
                         .unwrap_or_else(|| "file".to_string());
                     SessionStatus::EditApproval { filename }
                 }
-                _ => SessionStatus::NeedsPermission {
-                    tool_name: format!("{}: ...", tool_name),
-                    description: None,
-                },
+                _ => {
+                    // Show what the tool touches (file, pattern, URL) when the input says
+                    let detail = tool_input
+                        .as_ref()
+                        .and_then(|input| {
+                            ["file_path", "pattern", "path", "url", "query"]
+                                .iter()
+                                .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+                        })
+                        .unwrap_or("...");
+                    SessionStatus::NeedsPermission {
+                        tool_name: format!("{}: {}", tool_name, truncate(detail, 60)),
+                        description: None,
+                    }
+                }
             };
             (Some(status), Some(true), None, false)
         }
@@ -117,9 +176,12 @@ pub fn handle_hook_event(state: &mut DaemonState, event: HookEvent) -> Option<Se
             )
         }
 
-        HookEvent::Notification { .. } => {
-            // Notifications don't change status, but we update last activity
-            (None, None, None, false)
+        HookEvent::Notification { message, .. } => {
+            // Only API errors and limits change status; other notifications just count as activity
+            match classify_message(message, None, Local::now()) {
+                Some(problem) => (Some(problem.into_status()), Some(true), None, false),
+                None => (None, None, None, false),
+            }
         }
     };
 
@@ -130,27 +192,114 @@ pub fn handle_hook_event(state: &mut DaemonState, event: HookEvent) -> Option<Se
 
     // Now update the session
     let session = state.get_session_mut(&session_id)?;
-    session.last_activity = Some(now);
+    session.last_activity = Some(now.clone());
 
     if let Some(status) = new_status {
         session.status = status;
     }
     if let Some(needs_attention) = new_needs_attention {
+        // Keep the original timestamp while the request is still open
+        if !needs_attention {
+            session.attention_since = None;
+        } else if !session.needs_attention || session.attention_since.is_none() {
+            session.attention_since = Some(now.clone());
+        }
         session.needs_attention = needs_attention;
     }
     if let Some(input_source) = new_input_source {
         session.last_input_source = input_source;
     }
+    // Keep the tool call of an open permission request (notifications don't resolve it)
+    match &event {
+        HookEvent::PermissionRequest {
+            tool_name,
+            tool_input,
+            ..
+        } => {
+            session.pending_tool = Some(Box::new(PendingTool {
+                tool_name: tool_name.clone(),
+                tool_input: tool_input.clone().unwrap_or_default(),
+                requested_at: Some(now),
+            }));
+            session.risk = Some(classify_tool(
+                tool_name,
+                tool_input.as_ref(),
+                Path::new(&cwd),
+                risk_rules,
+            ));
+        }
+        HookEvent::Notification { .. } => {}
+        _ => {
+            session.pending_tool = None;
+            session.risk = None;
+        }
+    }
+
+    // Keep Claude's latest task list to show its progress
+    if let HookEvent::PreToolUse {
+        tool_name,
+        tool_input: Some(input),
+        ..
+    } = &event
+    {
+        if tool_name == "TodoWrite" {
+            if let Some(todos) = AgentTodo::list_from_input(input) {
+                session.agent_todos = todos.into_boxed_slice();
+            }
+        }
+    }
 
     Some(session.clone())
 }
 
+/// Placeholder id for a pane started by `new` (until its first hook reports the real id)
+pub fn pending_session_id(location: &TmuxLocation) -> String {
+    format!("pending:{}@{}", location.pane_id, location.socket)
+}
+
+/// Track a just-started Claude pane so it shows up before any hook fires
+pub fn handle_register_session(
+    state: &mut DaemonState,
+    cwd: String,
+    tmux_session: String,
+    location: &TmuxLocation,
+    prompted: bool,
+) -> SessionState {
+    let server = TmuxServer::from_socket_path(Path::new(&location.socket));
+    let (window, pane) = state
+        .tmux_sessions
+        .as_deref()
+        .and_then(|sessions| find_pane_by_id(sessions, &server, &location.pane_id))
+        .map(|(_, window, pane)| (window, pane))
+        .unwrap_or_else(|| ("0".to_string(), "0".to_string()));
+
+    let mut session = SessionState::new(
+        pending_session_id(location),
+        tmux_session,
+        window,
+        pane,
+        cwd,
+    );
+    session.tmux_server = server;
+    session.tmux_pane_id = location.pane_id.clone();
+    // With a prompt Claude starts working right away; otherwise it waits for input
+    session.status = if prompted {
+        SessionStatus::Working
+    } else {
+        SessionStatus::Waiting
+    };
+    session.last_activity = Some(Utc::now().to_rfc3339());
+    state.upsert_session(session.clone());
+    session
+}
+
 /// Truncate a string to max length with ellipsis
 fn truncate(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+    if s.chars().count() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len - 3])
+        let head: String = s.chars().take(max_len.saturating_sub(3)).collect();
+        format!("{}...", head)
     }
 }
 
@@ -158,3 +307,219 @@ fn truncate(s: &str, max_len: usize) -> String {
 fn extract_filename(path: &str) -> String {
     path.rsplit('/').next().unwrap_or(path).to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::agent_todos::todo_progress;
+    use crate::common::risk::RiskLevel;
+
+    #[test]
+    fn test_first_hook_replaces_registered_placeholder() {
+        let mut state = DaemonState::new();
+        let location = TmuxLocation {
+            socket: "/tmp/tmux-test/nonexistent".to_string(),
+            pane_id: "%7".to_string(),
+        };
+        let placeholder = handle_register_session(
+            &mut state,
+            "/work/api".to_string(),
+            "api".to_string(),
+            &location,
+            true,
+        );
+        assert_eq!(placeholder.status, SessionStatus::Working);
+        assert_eq!(placeholder.tmux_target(), "%7");
+        assert!(state.get_session(&pending_session_id(&location)).is_some());
+
+        let event = HookEvent::Stop {
+            session_id: "real".to_string(),
+            cwd: "/work/api".to_string(),
+            tmux: Some(location.clone()),
+            transcript_path: Some("/home/dev/.claude/projects/-work-api/real.jsonl".to_string()),
+        };
+        let updated = handle_hook_event(&mut state, event, &[]).unwrap();
+        assert_eq!(updated.tmux_pane_id, "%7");
+        assert_eq!(
+            updated.transcript_path.as_deref(),
+            Some("/home/dev/.claude/projects/-work-api/real.jsonl")
+        );
+        assert_eq!(state.sessions.len(), 1);
+        assert!(state.get_session("real").is_some());
+    }
+
+    #[test]
+    fn test_permission_request_keeps_tool_until_resolved() {
+        let mut state = DaemonState::new();
+        let request = HookEvent::PermissionRequest {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tool_name: "MultiEdit".to_string(),
+            tool_input: Some(serde_json::json!({"file_path": "/work/api/src/lib.rs", "edits": []})),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, request, &[]).unwrap();
+        assert_eq!(
+            session.status,
+            SessionStatus::EditApproval {
+                filename: "lib.rs".to_string()
+            }
+        );
+        assert_eq!(session.pending_tool.unwrap().tool_name, "MultiEdit");
+
+        let notification = HookEvent::Notification {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            message: "Claude needs your permission".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, notification, &[]).unwrap();
+        assert!(session.pending_tool.is_some());
+
+        let done = HookEvent::PostToolUse {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tool_name: "MultiEdit".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, done, &[]).unwrap();
+        assert_eq!(session.pending_tool, None);
+        assert_eq!(session.risk, None);
+    }
+
+    #[test]
+    fn test_permission_request_applies_configured_risk_rules() {
+        let mut state = DaemonState::new();
+        let rules = [RiskRule {
+            pattern: "kubectl delete *".to_string(),
+            level: RiskLevel::High,
+            label: "cluster".to_string(),
+        }];
+        let request = HookEvent::PermissionRequest {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tool_name: "Bash".to_string(),
+            tool_input: Some(serde_json::json!({"command": "kubectl delete pod web-1"})),
+            tmux: None,
+            transcript_path: None,
+        };
+        let risk = handle_hook_event(&mut state, request, &rules)
+            .unwrap()
+            .risk
+            .unwrap();
+        assert_eq!(risk.level, RiskLevel::High);
+        assert!(risk.tags.iter().any(|tag| tag.label == "cluster"));
+    }
+
+    #[test]
+    fn test_other_tools_show_what_they_touch() {
+        let mut state = DaemonState::new();
+        let request = HookEvent::PermissionRequest {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tool_name: "Grep".to_string(),
+            tool_input: Some(serde_json::json!({"pattern": "TODO", "path": "src"})),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, request, &[]).unwrap();
+        assert_eq!(
+            session.status,
+            SessionStatus::NeedsPermission {
+                tool_name: "Grep: TODO".to_string(),
+                description: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_long_multibyte_path_truncates_on_char_boundary() {
+        let mut state = DaemonState::new();
+        let path = format!("/work/{}/файл.txt", "данные/".repeat(10));
+        let request = HookEvent::PermissionRequest {
+            session_id: "s1".to_string(),
+            cwd: "/work".to_string(),
+            tool_name: "Read".to_string(),
+            tool_input: Some(serde_json::json!({ "file_path": path })),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, request, &[]).unwrap();
+        let SessionStatus::NeedsPermission { tool_name, .. } = session.status else {
+            panic!("expected NeedsPermission");
+        };
+        assert!(tool_name.starts_with("Read: /work/данные/"));
+        assert!(tool_name.ends_with("..."));
+        assert_eq!(tool_name.chars().count(), "Read: ".len() + 60);
+    }
+
+    #[test]
+    fn test_limit_notification_survives_stop() {
+        let mut state = DaemonState::new();
+        let notification = HookEvent::Notification {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            message: "Claude usage limit reached. Your limit will reset at 3pm".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, notification, &[]).unwrap();
+        assert!(matches!(
+            session.status,
+            SessionStatus::RateLimited { resets_at: Some(_) }
+        ));
+        assert!(session.needs_attention);
+
+        let stop = HookEvent::Stop {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, stop, &[]).unwrap();
+        assert!(matches!(session.status, SessionStatus::RateLimited { .. }));
+
+        let prompt = HookEvent::UserPromptSubmit {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, prompt, &[]).unwrap();
+        assert_eq!(session.status, SessionStatus::Working);
+    }
+
+    #[test]
+    fn test_todo_write_keeps_latest_task_list() {
+        let mut state = DaemonState::new();
+        let todo_write = |statuses: [&str; 2]| HookEvent::PreToolUse {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tool_name: "TodoWrite".to_string(),
+            tool_input: Some(serde_json::json!({"todos": [
+                {"content": "Add parser", "status": statuses[0], "activeForm": "Adding parser"},
+                {"content": "Run tests", "status": statuses[1], "activeForm": "Running tests"},
+            ]})),
+            tmux: None,
+            transcript_path: None,
+        };
+        handle_hook_event(&mut state, todo_write(["in_progress", "pending"]), &[]);
+        let session =
+            handle_hook_event(&mut state, todo_write(["completed", "in_progress"]), &[]).unwrap();
+        assert_eq!(todo_progress(&session.agent_todos), (1, 2));
+
+        // Other events leave the list alone
+        let done = HookEvent::PostToolUse {
+            session_id: "s1".to_string(),
+            cwd: "/work/api".to_string(),
+            tool_name: "TodoWrite".to_string(),
+            tmux: None,
+            transcript_path: None,
+        };
+        let session = handle_hook_event(&mut state, done, &[]).unwrap();
+        assert_eq!(session.agent_todos.len(), 2);
+    }
+}
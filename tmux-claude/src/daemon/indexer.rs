@@ -0,0 +1,138 @@
+//! Background indexing of Claude transcripts for full-text search.
+//!
+//! A `JsonlTailer` wakes up when transcripts change (or every poll interval
+//! without file notifications) and reads what was appended to at most a
+//! handful of files (most recently modified first, so live sessions stay
+//! current while a first full index is built). A snapshot of the index is
+//! persisted at most every `PERSIST_INTERVAL`, serialized off the state lock.
+//! Session usage totals are refreshed from the index every round, and sessions
+//! whose transcript just ended on an API error or usage limit are marked so.
+
+use crate::common::api_error::{trailing_problem, ApiProblem};
+use crate::common::config::cached_config;
+use crate::common::jsonl::claude_projects_dir;
+use crate::common::jsonl_tail::JsonlTailer;
+use crate::common::transcript_index::{get_transcript_index_path, TranscriptIndex};
+use crate::daemon::notifier::notify_needs_attention;
+use crate::daemon::state::DaemonState;
+use crate::ipc::messages::SessionStatus;
+use chrono::Local;
+use std::fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Longest wait between rounds (every round without file notifications)
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Most files read per round
+const MAX_FILES_PER_ROUND: usize = 32;
+
+/// Shortest time between two writes of the index file
+const PERSIST_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Keep `state.transcripts` current and persisted (runs forever)
+pub async fn run(state: Arc<RwLock<DaemonState>>) {
+    let index = tokio::task::spawn_blocking(TranscriptIndex::load)
+        .await
+        .unwrap_or_default();
+    let mut tailer = JsonlTailer::new(claude_projects_dir(), index.cursors(), true);
+    state.write().await.transcripts = index;
+
+    let mut idle = false;
+    let mut unsaved = false;
+    let mut saved_at = Instant::now();
+    loop {
+        // Waiting and reading block, so both run off the async runtime
+        let Ok((returned, round)) = tokio::task::spawn_blocking(move || {
+            if idle {
+                tailer.wait(POLL_INTERVAL);
+            }
+            let round = tailer.read_changes(MAX_FILES_PER_ROUND);
+            (tailer, round)
+        })
+        .await
+        else {
+            return;
+        };
+        tailer = returned;
+        idle = round.is_empty() && !tailer.has_pending();
+        if round.is_empty() {
+            update_session_usage(&state).await;
+            if unsaved && saved_at.elapsed() >= PERSIST_INTERVAL {
+                persist(&state).await;
+                (unsaved, saved_at) = (false, Instant::now());
+            }
+            continue;
+        }
+
+        let now = Local::now();
+        let problems: Vec<(String, Option<ApiProblem>)> = round
+            .updates
+            .iter()
+            .filter_map(|update| {
+                let problem = trailing_problem(&update.entries, now)?;
+                Some((update.path.to_string_lossy().to_string(), problem))
+            })
+            .collect();
+        let hit = {
+            let mut state = state.write().await;
+            state.transcripts.apply_round(round);
+            let hit = state.update_api_problems(&problems);
+            if !hit.is_empty() {
+                state.write_status_snapshot();
+            }
+            hit
+        };
+        unsaved = true;
+        for session in hit {
+            let message = match session.status {
+                SessionStatus::RateLimited {
+                    resets_at: Some(ref resets_at),
+                } => format!("rate limited until {}", short_time(resets_at)),
+                SessionStatus::RateLimited { resets_at: None } => "rate limited".to_string(),
+                SessionStatus::ApiError { ref message } => format!("API error: {}", message),
+                _ => continue,
+            };
+            notify_needs_attention(&session.tmux_server, &session.tmux_session, &message);
+        }
+        update_session_usage(&state).await;
+        if saved_at.elapsed() >= PERSIST_INTERVAL {
+            persist(&state).await;
+            (unsaved, saved_at) = (false, Instant::now());
+        }
+    }
+}
+
+/// Write a snapshot of the index to disk; the state is only locked to copy it
+async fn persist(state: &Arc<RwLock<DaemonState>>) {
+    let index = state.read().await.transcripts.clone();
+    let path = get_transcript_index_path();
+    let _ = tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+        let content = serde_json::to_string(&index)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write then rename so readers never see a half-written index
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, &path)
+    })
+    .await;
+}
+
+/// Refresh session usage and notify sessions over budget or nearly out of context
+async fn update_session_usage(state: &Arc<RwLock<DaemonState>>) {
+    let config = cached_config();
+    let notices = state.write().await.update_session_usage(&config.usage);
+    for (server, session, message) in notices {
+        notify_needs_attention(&server, &session, &message);
+    }
+}
+
+/// Local wall-clock time of an ISO 8601 timestamp, e.g. "15:30"
+fn short_time(timestamp: &str) -> String {
+    chrono::DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+        .unwrap_or_else(|_| timestamp.to_string())
+}
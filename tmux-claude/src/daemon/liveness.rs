@@ -0,0 +1,223 @@
+//! Stall and crash detection for Claude sessions.
+//!
+//! Hooks only report what Claude does, so a Claude that hangs or dies mid-turn
+//! would stay `Working` forever. Each metrics sample checks whether the pane
+//! still runs Claude and when the session last showed signs of life.
+
+use crate::common::jsonl::cwd_to_claude_projects_path;
+use crate::common::process::pane_runs_claude;
+use crate::common::tmux::find_pane_pid;
+use crate::common::types::TmuxSession;
+use crate::ipc::messages::{SessionState, SessionStatus};
+use chrono::{DateTime, Utc};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+use sysinfo::System;
+
+/// What one metrics sample saw of a session
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Liveness {
+    /// Whether a Claude process runs in the session's pane (None = pane unknown)
+    pub claude_running: Option<bool>,
+    /// Last write to the session's transcript
+    pub last_write: Option<DateTime<Utc>>,
+}
+
+impl Liveness {
+    /// Look at a local session's pane and transcript
+    pub fn sample(sys: &System, session: &SessionState, tmux_sessions: &[TmuxSession]) -> Self {
+        let claude_running = if session.host.is_none() && !session.tmux_pane_id.is_empty() {
+            find_pane_pid(tmux_sessions, &session.tmux_server, &session.tmux_pane_id)
+                .map(|pid| pane_runs_claude(sys, pid))
+        } else {
+            None
+        };
+        let transcript = match session.transcript_path {
+            Some(ref path) => PathBuf::from(path),
+            None => cwd_to_claude_projects_path(&session.cwd)
+                .join(format!("{}.jsonl", session.session_id)),
+        };
+        let last_write = fs::metadata(transcript)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .map(DateTime::<Utc>::from);
+        Self {
+            claude_running,
+            last_write,
+        }
+    }
+}
+
+/// Tracks which sessions had a live Claude process, so a placeholder registered
+/// before Claude started isn't mistaken for a crash
+#[derive(Debug, Default)]
+pub struct LivenessTracker {
+    seen_running: HashSet<String>,
+}
+
+impl LivenessTracker {
+    /// Mark sessions stalled, crashed or (after a stall) working again.
+    /// Returns the sessions whose status changed.
+    pub fn evaluate(
+        &mut self,
+        sessions: &mut HashMap<String, SessionState>,
+        samples: &HashMap<String, Liveness>,
+        stall_after: Option<Duration>,
+        now: DateTime<Utc>,
+    ) -> Vec<SessionState> {
+        self.seen_running.retain(|id| sessions.contains_key(id));
+
+        let mut changed = Vec::new();
+        for session in sessions.values_mut() {
+            let Some(sample) = samples.get(&session.session_id) else {
+                continue;
+            };
+            let status = match sample.claude_running {
+                Some(true) => {
+                    self.seen_running.insert(session.session_id.clone());
+                    let last_active = session
+                        .last_activity
+                        .as_deref()
+                        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                        .map(|t| t.with_timezone(&Utc))
+                        .max(sample.last_write);
+                    let silent = match (stall_after, last_active) {
+                        (Some(limit), Some(last)) => {
+                            (now - last).to_std().is_ok_and(|idle| idle >= limit)
+                        }
+                        _ => false,
+                    };
+                    match session.status {
+                        SessionStatus::Working if silent => SessionStatus::Stalled,
+                        SessionStatus::Stalled if !silent => SessionStatus::Working,
+                        _ => continue,
+                    }
+                }
+                // A Claude that exits after its turn (Stop) or after a crash was reported is fine
+                Some(false)
+                    if self.seen_running.contains(&session.session_id)
+                        && !matches!(
+                            session.status,
+                            SessionStatus::Waiting | SessionStatus::Crashed
+                        ) =>
+                {
+                    SessionStatus::Crashed
+                }
+                _ => continue,
+            };
+
+            let needs_attention = status != SessionStatus::Working;
+            session.attention_since = needs_attention.then(|| now.to_rfc3339());
+            session.needs_attention = needs_attention;
+            session.status = status;
+            changed.push(session.clone());
+        }
+        changed
+    }
+}
+
+/// Notification text for a session that just stalled or crashed
+pub fn describe(session: &SessionState, stall_after: Option<Duration>) -> Option<String> {
+    match session.status {
+        SessionStatus::Stalled => Some(format!(
+            "stalled: no activity for {}m",
+            stall_after.unwrap_or_default().as_secs() / 60
+        )),
+        SessionStatus::Crashed => Some("crashed: Claude exited mid-turn".to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_session(
+        status: SessionStatus,
+        last_activity: DateTime<Utc>,
+    ) -> HashMap<String, SessionState> {
+        let mut session = SessionState::new(
+            "s1".to_string(),
+            "api".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            "/work/api".to_string(),
+        );
+        session.status = status;
+        session.last_activity = Some(last_activity.to_rfc3339());
+        HashMap::from([("s1".to_string(), session)])
+    }
+
+    fn sample(
+        claude_running: bool,
+        last_write: Option<DateTime<Utc>>,
+    ) -> HashMap<String, Liveness> {
+        let liveness = Liveness {
+            claude_running: Some(claude_running),
+            last_write,
+        };
+        HashMap::from([("s1".to_string(), liveness)])
+    }
+
+    #[test]
+    fn test_stall_uses_latest_hook_or_transcript_write() {
+        let now = Utc::now();
+        let minutes = |m: i64| now - chrono::Duration::minutes(m);
+        let stall_after = Some(Duration::from_secs(600));
+        let mut tracker = LivenessTracker::default();
+        let mut sessions = one_session(SessionStatus::Working, minutes(30));
+
+        // Transcript still being written: not stalled
+        let recent = sample(true, Some(minutes(2)));
+        assert!(tracker
+            .evaluate(&mut sessions, &recent, stall_after, now)
+            .is_empty());
+
+        let silent = sample(true, Some(minutes(11)));
+        let changed = tracker.evaluate(&mut sessions, &silent, stall_after, now);
+        assert_eq!(changed[0].status, SessionStatus::Stalled);
+        assert!(changed[0].needs_attention);
+        assert_eq!(
+            describe(&changed[0], stall_after).as_deref(),
+            Some("stalled: no activity for 10m")
+        );
+        assert!(tracker
+            .evaluate(&mut sessions, &silent, stall_after, now)
+            .is_empty());
+
+        let changed = tracker.evaluate(&mut sessions, &recent, stall_after, now);
+        assert_eq!(changed[0].status, SessionStatus::Working);
+        assert!(!changed[0].needs_attention);
+        assert!(tracker
+            .evaluate(&mut sessions, &silent, None, now)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_crash_only_after_claude_was_seen_running() {
+        let now = Utc::now();
+        let mut tracker = LivenessTracker::default();
+        let mut sessions = one_session(SessionStatus::Working, now);
+
+        // Placeholder whose Claude hasn't started yet
+        assert!(tracker
+            .evaluate(&mut sessions, &sample(false, None), None, now)
+            .is_empty());
+
+        tracker.evaluate(&mut sessions, &sample(true, None), None, now);
+        let changed = tracker.evaluate(&mut sessions, &sample(false, None), None, now);
+        assert_eq!(changed[0].status, SessionStatus::Crashed);
+        assert!(tracker
+            .evaluate(&mut sessions, &sample(false, None), None, now)
+            .is_empty());
+
+        // Exiting after the turn ended is not a crash
+        let mut sessions = one_session(SessionStatus::Waiting, now);
+        tracker.evaluate(&mut sessions, &sample(true, None), None, now);
+        assert!(tracker
+            .evaluate(&mut sessions, &sample(false, None), None, now)
+            .is_empty());
+    }
+}
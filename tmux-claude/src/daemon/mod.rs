@@ -1,6 +1,13 @@
 //! Background daemon for persistent Claude session monitoring.
 
-pub mod server;
-pub mod state;
+pub mod alerts;
+pub mod git_cache;
 pub mod hooks;
+pub mod indexer;
+pub mod liveness;
 pub mod notifier;
+pub mod remote;
+pub mod resume;
+pub mod server;
+pub mod state;
+pub mod tmux_control;
@@ -1,29 +1,64 @@
 //! Platform-native notifications for the daemon.
 
+use crate::common::risk::{Risk, RiskLevel};
+use crate::common::tmux::TmuxServer;
 use std::process::Command;
 
 /// Send a notification when a session needs attention
-pub fn notify_needs_attention(session_name: &str, status: &str) {
+pub fn notify_needs_attention(server: &TmuxServer, session_name: &str, status: &str) {
+    notify(server, &format!("{}: {}", session_name, status), "");
+}
+
+/// Notify about a permission request, tagged with its risk (red/yellow in the tmux fallback)
+pub fn notify_permission_request(
+    server: &TmuxServer,
+    session_name: &str,
+    status: &str,
+    risk: Option<&Risk>,
+) {
+    match risk.filter(|r| r.level > RiskLevel::Low) {
+        Some(risk) => {
+            let style = match risk.level {
+                RiskLevel::High => "#[fg=red,bold]",
+                _ => "#[fg=yellow]",
+            };
+            let message = format!(
+                "{}: [{} risk: {}] {}",
+                session_name,
+                risk.level.label(),
+                risk.summary(),
+                status
+            );
+            notify(server, &message, style);
+        }
+        None => notify_needs_attention(server, session_name, status),
+    }
+}
+
+/// Deliver a notification; `tmux_style` only applies to the tmux fallback
+fn notify(server: &TmuxServer, message: &str, tmux_style: &str) {
     let title = "tmux-claude";
-    let message = format!("{}: {}", session_name, status);
 
     // Try platform-specific notification
     #[cfg(target_os = "macos")]
     {
-        if notify_macos(title, &message) {
+        if notify_macos(title, message) {
             return;
         }
     }
 
     #[cfg(target_os = "linux")]
     {
-        if notify_linux(title, &message) {
+        if notify_linux(title, message) {
             return;
         }
     }
 
-    // Fallback: tmux display-message
-    notify_tmux(&message);
+    // Fallback: tmux display-message on the session's server ('#' is a format character)
+    notify_tmux(
+        server,
+        &format!("{}{}", tmux_style, message.replace('#', "##")),
+    );
 }
 
 /// macOS notification using osascript
@@ -63,8 +98,9 @@ fn notify_linux(title: &str, message: &str) -> bool {
 }
 
 /// Fallback notification via tmux display-message
-fn notify_tmux(message: &str) {
-    let _ = Command::new("tmux")
+fn notify_tmux(server: &TmuxServer, message: &str) {
+    let _ = server
+        .command()
         .args(["display-message", "-d", "3000", message])
         .output();
 }
@@ -0,0 +1,215 @@
+//! Aggregation of sessions from remote daemons over forwarded Unix sockets.
+//!
+//! A remote daemon is reached through a local socket (usually an SSH `-L`
+//! forward of its `daemon.sock`) and spoken to with the same newline-JSON
+//! `DaemonCommand` protocol the TUI uses.
+
+use crate::common::config::{load_config, RemoteConfig};
+use crate::daemon::state::DaemonState;
+use crate::ipc::messages::{DaemonCommand, DaemonResponse, SessionState};
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+use tokio::sync::RwLock;
+
+/// How often remote daemons are polled
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Upper bound for a single request to a remote daemon
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Last known sessions of a reachable remote daemon
+#[derive(Debug, Clone, Default)]
+pub struct RemoteSnapshot {
+    /// Socket the remote daemon is reached through
+    pub socket: PathBuf,
+    /// The remote's own sessions, tagged with its host name
+    pub sessions: Vec<SessionState>,
+}
+
+/// Send one command to the daemon listening on `socket` and read its response
+pub async fn send_command(socket: &Path, command: &DaemonCommand) -> Result<DaemonResponse> {
+    let request = async {
+        let stream = UnixStream::connect(socket)
+            .await
+            .with_context(|| format!("Failed to connect to {:?}", socket))?;
+        let (reader, mut writer) = stream.into_split();
+
+        let json = serde_json::to_string(command)?;
+        writer.write_all(json.as_bytes()).await?;
+        writer.write_all(b"\n").await?;
+        writer.flush().await?;
+
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await?;
+        if line.is_empty() {
+            bail!("Connection closed by {:?}", socket);
+        }
+        Ok(serde_json::from_str(&line)?)
+    };
+
+    tokio::time::timeout(REQUEST_TIMEOUT, request)
+        .await
+        .context("Remote daemon timed out")?
+}
+
+/// Fetch the sessions a remote daemon tracks itself, tagged with the remote's name
+pub async fn fetch_sessions(remote: &RemoteConfig) -> Result<Vec<SessionState>> {
+    match send_command(&remote.socket_path(), &DaemonCommand::GetState).await? {
+        DaemonResponse::State { sessions, .. } => Ok(sessions
+            .into_iter()
+            // Skip sessions the remote pulled from its own remotes, so chains can't loop
+            .filter(|session| session.host.is_none())
+            .map(|mut session| {
+                session.host = Some(remote.name.clone());
+                session
+            })
+            .collect()),
+        other => bail!("Unexpected response from {}: {:?}", remote.name, other),
+    }
+}
+
+/// Poll remote daemons and keep `state.remotes` current.
+/// `remotes` overrides the config file, which is re-read every poll otherwise.
+pub async fn run(state: Arc<RwLock<DaemonState>>, remotes: Option<Vec<RemoteConfig>>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        let remotes = remotes.clone().unwrap_or_else(|| load_config().remotes);
+
+        let mut snapshots = HashMap::new();
+        for remote in &remotes {
+            // An unreachable remote (e.g. tunnel down) drops out until it answers again
+            if let Ok(sessions) = fetch_sessions(remote).await {
+                snapshots.insert(
+                    remote.name.clone(),
+                    RemoteSnapshot {
+                        socket: remote.socket_path(),
+                        sessions,
+                    },
+                );
+            }
+        }
+
+        let mut state = state.write().await;
+        state.remotes = snapshots;
+        state.write_status_snapshot();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::daemon::server::DaemonServer;
+    use crate::ipc::messages::{HookEvent, TmuxLocation};
+    use std::time::Instant;
+
+    /// Wait until a daemon answers on `socket`
+    async fn wait_for_daemon(socket: &Path) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !matches!(
+            send_command(socket, &DaemonCommand::Ping).await,
+            Ok(DaemonResponse::Pong)
+        ) {
+            assert!(
+                Instant::now() < deadline,
+                "daemon on {:?} never came up",
+                socket
+            );
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    async fn get_sessions(socket: &Path) -> Vec<SessionState> {
+        match send_command(socket, &DaemonCommand::GetState)
+            .await
+            .unwrap()
+        {
+            DaemonResponse::State { sessions, .. } => sessions,
+            other => panic!("unexpected response: {:?}", other),
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_sessions_are_aggregated_and_approvals_forwarded() {
+        let dir = std::env::temp_dir().join(format!("tmux-claude-remote-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let remote_socket = dir.join("remote.sock");
+        let local_socket = dir.join("local.sock");
+
+        let remote = DaemonServer::with_socket_path(remote_socket.clone()).with_remotes(Vec::new());
+        tokio::spawn(async move { remote.run().await });
+        let local =
+            DaemonServer::with_socket_path(local_socket.clone()).with_remotes(vec![RemoteConfig {
+                name: "devbox".to_string(),
+                socket: remote_socket.clone(),
+            }]);
+        tokio::spawn(async move { local.run().await });
+        wait_for_daemon(&remote_socket).await;
+        wait_for_daemon(&local_socket).await;
+
+        // Register a session on the remote; its pane lives on a tmux server that doesn't exist
+        let event = HookEvent::Stop {
+            session_id: "remote-1".to_string(),
+            cwd: "/work/api".to_string(),
+            tmux: Some(TmuxLocation {
+                socket: dir.join("no-such-tmux").to_string_lossy().to_string(),
+                pane_id: "%0".to_string(),
+            }),
+            transcript_path: None,
+        };
+        send_command(&remote_socket, &DaemonCommand::HookEvent(event))
+            .await
+            .unwrap();
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        let session = loop {
+            let sessions = get_sessions(&local_socket).await;
+            if let Some(session) = sessions.into_iter().find(|s| s.session_id == "remote-1") {
+                break session;
+            }
+            assert!(Instant::now() < deadline, "remote session never aggregated");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        };
+        assert_eq!(session.host.as_deref(), Some("devbox"));
+        assert_eq!(session.cwd, "/work/api");
+
+        // The remote itself reports the session as local
+        let on_remote = get_sessions(&remote_socket).await;
+        assert_eq!(on_remote.len(), 1);
+        assert_eq!(on_remote[0].host, None);
+
+        // Approvals for the remote session are routed to the remote daemon,
+        // which reports that its (nonexistent) tmux server took no keys
+        let approve = |session_id: &str| DaemonCommand::ApprovePermission {
+            session_id: session_id.to_string(),
+            always: false,
+        };
+        let error = |response| match response {
+            DaemonResponse::Error { message } => message,
+            other => panic!("unexpected response: {:?}", other),
+        };
+        let forwarded = error(
+            send_command(&local_socket, &approve("remote-1"))
+                .await
+                .unwrap(),
+        );
+        assert!(
+            forwarded.starts_with("Failed to send keys"),
+            "{}",
+            forwarded
+        );
+        let missing = error(
+            send_command(&local_socket, &approve("missing"))
+                .await
+                .unwrap(),
+        );
+        assert!(missing.starts_with("Session not found"), "{}", missing);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
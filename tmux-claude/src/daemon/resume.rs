@@ -0,0 +1,101 @@
+//! Auto-resume of rate-limited sessions (`usage.auto_resume`).
+//!
+//! Once a session's usage limit resets, "continue" is typed into its pane so
+//! long-running agents pick up where they stopped without someone watching.
+//! The session only counts as working again once tmux took the keys; otherwise
+//! the user is notified and the session is left alone.
+
+use crate::common::config::cached_config;
+use crate::common::tmux::TmuxServer;
+use crate::daemon::notifier::notify_needs_attention;
+use crate::daemon::state::DaemonState;
+use crate::ipc::messages::{DaemonResponse, SessionState, SessionStatus};
+use chrono::Utc;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+
+/// How often reset times are checked
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Limits that reset longer ago than this (e.g. while the daemon was down)
+/// are left for the user; typing into a pane that long after is a surprise
+const RESUME_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Grace period after the reset time, so the first request isn't rejected again
+const RESET_GRACE: Duration = Duration::from_secs(30);
+
+/// Prompt sent to resume a session
+const RESUME_PROMPT: &str = "continue";
+
+/// Type the resume prompt into a session's pane; false if tmux failed
+fn type_resume_prompt(server: &TmuxServer, target: &str) -> bool {
+    let send = |args: &[&str]| {
+        server
+            .command()
+            .args(args)
+            .output()
+            .is_ok_and(|output| output.status.success())
+    };
+    send(&["send-keys", "-t", target, "-l", RESUME_PROMPT])
+        && send(&["send-keys", "-t", target, "Enter"])
+}
+
+/// Which limit a due session hit: its id and reset time
+fn limit_key(session: &SessionState) -> (String, Option<String>) {
+    let resets_at = match &session.status {
+        SessionStatus::RateLimited { resets_at } => resets_at.clone(),
+        _ => None,
+    };
+    (session.session_id.clone(), resets_at)
+}
+
+/// Resume rate-limited sessions when their limit resets (runs forever)
+pub async fn run(state: Arc<RwLock<DaemonState>>, broadcast_tx: broadcast::Sender<DaemonResponse>) {
+    let mut interval = tokio::time::interval(POLL_INTERVAL);
+    // Limits whose resume failed; the user was told and is left to it
+    let mut failed: HashSet<(String, Option<String>)> = HashSet::new();
+    loop {
+        interval.tick().await;
+        if !cached_config().usage.auto_resume {
+            continue;
+        }
+        let now = Utc::now() - RESET_GRACE;
+        let due = state.read().await.due_resumes(now, RESUME_WINDOW);
+        failed.retain(|key| due.iter().any(|session| limit_key(session) == *key));
+
+        for session in due {
+            let key = limit_key(&session);
+            if failed.contains(&key) {
+                continue;
+            }
+            let (server, target) = (session.tmux_server.clone(), session.tmux_target());
+            let typed = tokio::task::spawn_blocking(move || type_resume_prompt(&server, &target))
+                .await
+                .unwrap_or(false);
+            if !typed {
+                notify_needs_attention(
+                    &session.tmux_server,
+                    &session.tmux_session,
+                    "limit reset, but auto-resume couldn't type into the pane",
+                );
+                failed.insert(key);
+                continue;
+            }
+
+            // Only now is it working again (unless something else changed it meanwhile)
+            let resumed = {
+                let mut state = state.write().await;
+                let resumed = state.mark_resumed(&session.session_id);
+                if resumed.is_some() {
+                    state.write_status_snapshot();
+                }
+                resumed
+            };
+            if let Some(session) = resumed {
+                let _ = broadcast_tx.send(DaemonResponse::StateUpdate { session });
+            }
+        }
+    }
+}
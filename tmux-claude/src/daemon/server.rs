@@ -1,15 +1,26 @@
 //! Unix socket server for the daemon.
 
-use crate::daemon::hooks::handle_hook_event;
-use crate::daemon::notifier::notify_needs_attention;
+use crate::common::config::{cached_config, RemoteConfig};
+use crate::common::process::{get_process_info, get_session_pids};
+use crate::daemon::git_cache;
+use crate::daemon::hooks::{handle_hook_event, handle_register_session};
+use crate::daemon::indexer;
+use crate::daemon::liveness::{self, Liveness};
+use crate::daemon::notifier::{notify_needs_attention, notify_permission_request};
+use crate::daemon::remote;
+use crate::daemon::resume;
 use crate::daemon::state::DaemonState;
+use crate::daemon::tmux_control;
 use crate::ipc::messages::{
-    get_pid_file_path, get_socket_path, DaemonCommand, DaemonResponse, SessionStatus,
+    get_pid_file_path, get_socket_path, get_status_snapshot_path, DaemonCommand, DaemonResponse,
+    SessionStatus,
 };
 use anyhow::{Context, Result};
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use sysinfo::{Networks, System};
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{UnixListener, UnixStream};
@@ -21,22 +32,56 @@ pub struct DaemonServer {
     start_time: Instant,
     /// Channel for broadcasting state updates to subscribers
     broadcast_tx: broadcast::Sender<DaemonResponse>,
+    /// Socket to listen on
+    socket_path: PathBuf,
+    /// Whether this is the main daemon (owns the PID/state files and monitors tmux)
+    primary: bool,
+    /// Remote daemons to aggregate (None = read from config)
+    remotes: Option<Vec<RemoteConfig>>,
 }
 
 impl DaemonServer {
-    /// Create a new daemon server
+    /// Create the main daemon server on the default socket
     pub fn new() -> Self {
         let (broadcast_tx, _) = broadcast::channel(100);
+        let mut state = DaemonState::load();
+        state.status_snapshot_path = Some(get_status_snapshot_path());
         Self {
-            state: Arc::new(RwLock::new(DaemonState::load())),
+            state: Arc::new(RwLock::new(state)),
             start_time: Instant::now(),
             broadcast_tx,
+            socket_path: get_socket_path(),
+            primary: true,
+            remotes: None,
         }
     }
 
+    /// Create a secondary server on another socket, with fresh in-memory state.
+    /// It answers commands and polls remotes, but doesn't persist or monitor tmux.
+    /// Only tests run one; the daemon always uses `new` and the configured remotes.
+    #[cfg(test)]
+    pub fn with_socket_path(socket_path: PathBuf) -> Self {
+        let (broadcast_tx, _) = broadcast::channel(100);
+        Self {
+            state: Arc::new(RwLock::new(DaemonState::new())),
+            start_time: Instant::now(),
+            broadcast_tx,
+            socket_path,
+            primary: false,
+            remotes: None,
+        }
+    }
+
+    /// Use a fixed list of remote daemons instead of the config file
+    #[cfg(test)]
+    pub fn with_remotes(mut self, remotes: Vec<RemoteConfig>) -> Self {
+        self.remotes = Some(remotes);
+        self
+    }
+
     /// Run the daemon server
     pub async fn run(&self) -> Result<()> {
-        let socket_path = get_socket_path();
+        let socket_path = self.socket_path.clone();
 
         // Ensure socket directory exists
         if let Some(parent) = socket_path.parent() {
@@ -48,41 +93,92 @@ impl DaemonServer {
             fs::remove_file(&socket_path).context("Failed to remove existing socket")?;
         }
 
-        // Write PID file
-        let pid_path = get_pid_file_path();
-        fs::write(&pid_path, std::process::id().to_string())
-            .context("Failed to write PID file")?;
-
         // Bind to socket
         let listener = UnixListener::bind(&socket_path).context("Failed to bind to socket")?;
         eprintln!("Daemon listening on {:?}", socket_path);
 
-        // Spawn periodic state save task
+        // Spawn cleanup task for old pending approvals
         let state_clone = self.state.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
             loop {
                 interval.tick().await;
-                let state = state_clone.read().await;
-                if let Err(e) = state.save() {
-                    eprintln!("Failed to save state: {}", e);
-                }
+                let mut state = state_clone.write().await;
+                state.cleanup_old_approvals();
             }
         });
 
-        // Spawn cleanup task for old pending approvals
+        // Spawn remote daemon poller (aggregates sessions from other hosts)
+        tokio::spawn(remote::run(self.state.clone(), self.remotes.clone()));
+
+        if self.primary {
+            self.spawn_primary_tasks()?;
+            self.state.write().await.write_status_snapshot();
+        }
+
+        // Accept connections
+        loop {
+            let (stream, _) = listener
+                .accept()
+                .await
+                .context("Failed to accept connection")?;
+            let state = self.state.clone();
+            let broadcast_tx = self.broadcast_tx.clone();
+            let start_time = self.start_time;
+            let socket_path = socket_path.clone();
+            let primary = self.primary;
+
+            tokio::spawn(async move {
+                let result = handle_connection(
+                    stream,
+                    state,
+                    broadcast_tx,
+                    start_time,
+                    &socket_path,
+                    primary,
+                )
+                .await;
+                if let Err(e) = result {
+                    eprintln!("Connection error: {}", e);
+                }
+            });
+        }
+    }
+
+    /// Write the PID file and start persistence, tmux and metrics tasks
+    fn spawn_primary_tasks(&self) -> Result<()> {
+        // Write PID file
+        let pid_path = get_pid_file_path();
+        fs::write(&pid_path, std::process::id().to_string()).context("Failed to write PID file")?;
+
+        // Spawn periodic state save task
         let state_clone = self.state.clone();
         tokio::spawn(async move {
-            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
+            let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(60));
             loop {
                 interval.tick().await;
-                let mut state = state_clone.write().await;
-                state.cleanup_old_approvals();
+                let state = state_clone.read().await;
+                if let Err(e) = state.save() {
+                    eprintln!("Failed to save state: {}", e);
+                }
             }
         });
 
+        // Spawn tmux control-mode client (keeps the session/window/pane model live)
+        tokio::spawn(tmux_control::run(self.state.clone()));
+
+        // Spawn git status refresher (branch/dirty/ahead-behind per session cwd)
+        tokio::spawn(git_cache::run(self.state.clone()));
+
+        // Spawn transcript indexer (full-text search over ~/.claude/projects)
+        tokio::spawn(indexer::run(self.state.clone()));
+
+        // Spawn auto-resume of rate-limited sessions (type "continue" once limits reset)
+        tokio::spawn(resume::run(self.state.clone(), self.broadcast_tx.clone()));
+
         // Spawn metrics collection task (every 5 seconds)
         let state_clone = self.state.clone();
+        let broadcast_tx = self.broadcast_tx.clone();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(5));
             let mut sys = System::new_all();
@@ -93,24 +189,70 @@ impl DaemonServer {
                 sys.refresh_all();
                 networks.refresh();
 
+                // Group processes by tmux session for resource alert rules
+                let (tmux_sessions, samples) = {
+                    let state = state_clone.read().await;
+                    let tmux_sessions = state.tmux_sessions.clone().unwrap_or_default();
+                    // Without a synced topology every pane would look gone
+                    let samples: HashMap<String, Liveness> = if state.tmux_sessions.is_some() {
+                        state
+                            .sessions
+                            .values()
+                            .map(|s| {
+                                (
+                                    s.session_id.clone(),
+                                    Liveness::sample(&sys, s, &tmux_sessions),
+                                )
+                            })
+                            .collect()
+                    } else {
+                        HashMap::new()
+                    };
+                    (tmux_sessions, samples)
+                };
+                let session_processes: Vec<_> = tmux_sessions
+                    .iter()
+                    .map(|session| {
+                        let processes = get_session_pids(&sys, session)
+                            .into_iter()
+                            .filter_map(|pid| get_process_info(&sys, pid))
+                            .collect();
+                        (session.server.clone(), session.name.clone(), processes)
+                    })
+                    .collect();
+                let config = cached_config();
+
                 let mut state = state_clone.write().await;
                 state.metrics.collect_sample(&sys, &networks);
+                let raised = state.alerts.evaluate(
+                    &config.alerts,
+                    &session_processes,
+                    std::time::Instant::now(),
+                );
+                let stall_after = config.alerts.stall_after_secs.map(Duration::from_secs);
+                let changed = state.update_liveness(&samples, stall_after);
+                if !changed.is_empty() {
+                    state.write_status_snapshot();
+                }
+                drop(state);
+
+                for alert in raised {
+                    notify_needs_attention(&alert.server, &alert.session, &alert.describe());
+                }
+                for session in changed {
+                    if let Some(message) = liveness::describe(&session, stall_after) {
+                        notify_needs_attention(
+                            &session.tmux_server,
+                            &session.tmux_session,
+                            &message,
+                        );
+                    }
+                    let _ = broadcast_tx.send(DaemonResponse::StateUpdate { session });
+                }
             }
         });
 
-        // Accept connections
-        loop {
-            let (stream, _) = listener.accept().await.context("Failed to accept connection")?;
-            let state = self.state.clone();
-            let broadcast_tx = self.broadcast_tx.clone();
-            let start_time = self.start_time;
-
-            tokio::spawn(async move {
-                if let Err(e) = handle_connection(stream, state, broadcast_tx, start_time).await {
-                    eprintln!("Connection error: {}", e);
-                }
-            });
-        }
+        Ok(())
     }
 
     /// Get current subscriber count
@@ -125,18 +267,29 @@ async fn handle_connection(
     state: Arc<RwLock<DaemonState>>,
     broadcast_tx: broadcast::Sender<DaemonResponse>,
     start_time: Instant,
+    socket_path: &Path,
+    primary: bool,
 ) -> Result<()> {
     let (reader, mut writer) = stream.into_split();
-    let mut reader = BufReader::new(reader);
-    let mut line = String::new();
+    let mut lines = BufReader::new(reader).lines();
+    // Set while the client is subscribed to state updates
+    let mut updates: Option<broadcast::Receiver<DaemonResponse>> = None;
 
-    // Read commands line by line (newline-delimited JSON)
+    // Read commands line by line (newline-delimited JSON), pushing updates in between
     loop {
-        line.clear();
-        let bytes_read = reader.read_line(&mut line).await?;
-        if bytes_read == 0 {
+        let line = tokio::select! {
+            line = lines.next_line() => line?,
+            update = next_update(&mut updates) => {
+                match update {
+                    Some(update) => send_response(&mut writer, &update).await?,
+                    None => updates = None,
+                }
+                continue;
+            }
+        };
+        let Some(line) = line else {
             break; // Connection closed
-        }
+        };
 
         let line = line.trim();
         if line.is_empty() {
@@ -155,12 +308,21 @@ async fn handle_connection(
             }
         };
 
+        // Subscription is per connection: later updates go to this writer
+        match command {
+            DaemonCommand::Subscribe => updates = Some(broadcast_tx.subscribe()),
+            DaemonCommand::Unsubscribe => updates = None,
+            _ => {}
+        }
+
         // Handle command
         let response = handle_command(
             command,
             &state,
             &broadcast_tx,
             start_time,
+            socket_path,
+            primary,
         )
         .await;
 
@@ -177,72 +339,93 @@ async fn handle_connection(
     Ok(())
 }
 
+/// Next update for a subscribed connection (pending forever when not subscribed,
+/// None once the broadcast channel is closed)
+async fn next_update(
+    updates: &mut Option<broadcast::Receiver<DaemonResponse>>,
+) -> Option<DaemonResponse> {
+    let Some(rx) = updates else {
+        return std::future::pending().await;
+    };
+    loop {
+        match rx.recv().await {
+            Ok(update) => return Some(update),
+            // A slow subscriber just misses intermediate updates
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return None,
+        }
+    }
+}
+
 /// Handle a single command and return a response
 async fn handle_command(
     command: DaemonCommand,
     state: &Arc<RwLock<DaemonState>>,
     broadcast_tx: &broadcast::Sender<DaemonResponse>,
     start_time: Instant,
+    socket_path: &Path,
+    primary: bool,
 ) -> DaemonResponse {
     match command {
         DaemonCommand::GetState => {
             let state = state.read().await;
             DaemonResponse::State {
-                sessions: state.all_sessions(),
+                sessions: state.all_sessions_with_remotes(),
                 daemon_uptime_secs: start_time.elapsed().as_secs(),
                 metrics: Some(state.metrics.get_history()),
+                alerts: state.alerts.active_alerts(),
+                tmux_sessions: state.tmux_sessions.clone(),
+                git: state.git.statuses(),
             }
         }
 
         DaemonCommand::Subscribe => {
             // Subscription is handled at the connection level
-            // For now, just return OK
             DaemonResponse::Ok
         }
 
-        DaemonCommand::Unsubscribe => {
-            DaemonResponse::Ok
-        }
+        DaemonCommand::Unsubscribe => DaemonResponse::Ok,
 
         DaemonCommand::ApprovePermission { session_id, always } => {
-            let mut state_guard = state.write().await;
-
-            // Mark as pending approval so we can detect external vs daemon input
-            state_guard.mark_pending_approval(&session_id);
+            // Option 2 = approve always, option 1 = approve once
+            let key = if always { "2" } else { "1" };
+            let forward = DaemonCommand::ApprovePermission {
+                session_id: session_id.clone(),
+                always,
+            };
+            send_session_keys(state, &session_id, &[key, "Enter"], forward, true).await
+        }
 
-            // Get the session info for sending keys
-            if let Some(session) = state_guard.get_session(&session_id) {
-                // Send tmux keys to approve
-                let target = format!(
-                    "{}:{}.{}",
-                    session.tmux_session, session.tmux_window, session.tmux_pane
-                );
+        DaemonCommand::DenyPermission { session_id } => {
+            let forward = DaemonCommand::DenyPermission {
+                session_id: session_id.clone(),
+            };
+            send_session_keys(state, &session_id, &["Escape"], forward, false).await
+        }
 
-                // Drop the lock before running external commands
-                let target_clone = target.clone();
-                drop(state_guard);
-
-                // Send approval keys via tmux
-                let key = if always { "2" } else { "1" };
-                let _ = std::process::Command::new("tmux")
-                    .args(["send-keys", "-t", &target_clone, key])
-                    .output();
-                let _ = std::process::Command::new("tmux")
-                    .args(["send-keys", "-t", &target_clone, "Enter"])
-                    .output();
-
-                DaemonResponse::Ok
-            } else {
-                DaemonResponse::Error {
-                    message: format!("Session not found: {}", session_id),
-                }
-            }
+        DaemonCommand::RegisterSession {
+            cwd,
+            tmux_session,
+            tmux,
+            prompted,
+        } => {
+            let mut state_guard = state.write().await;
+            let session =
+                handle_register_session(&mut state_guard, cwd, tmux_session, &tmux, prompted);
+            state_guard.write_status_snapshot();
+            let _ = broadcast_tx.send(DaemonResponse::StateUpdate { session });
+            DaemonResponse::Ok
         }
 
         DaemonCommand::HookEvent(event) => {
+            // Read the config before locking; risk rules only matter for permission requests
+            let config = cached_config();
             let mut state_guard = state.write().await;
 
-            if let Some(updated_session) = handle_hook_event(&mut state_guard, event) {
+            let updated = handle_hook_event(&mut state_guard, event, &config.risk.rules);
+            state_guard.write_status_snapshot();
+
+            if let Some(updated_session) = updated {
                 // Check if session needs attention and send notification
                 if updated_session.needs_attention {
                     let status_text = match &updated_session.status {
@@ -254,9 +437,16 @@ async fn handle_command(
                         }
                         SessionStatus::PlanReview => "plan ready".to_string(),
                         SessionStatus::QuestionAsked => "question asked".to_string(),
+                        SessionStatus::RateLimited { .. } => "rate limited".to_string(),
+                        SessionStatus::ApiError { message } => format!("API error: {}", message),
                         _ => "needs attention".to_string(),
                     };
-                    notify_needs_attention(&updated_session.tmux_session, &status_text);
+                    notify_permission_request(
+                        &updated_session.tmux_server,
+                        &updated_session.tmux_session,
+                        &status_text,
+                        updated_session.risk.as_ref(),
+                    );
                 }
 
                 // Broadcast update to subscribers
@@ -268,6 +458,20 @@ async fn handle_command(
             DaemonResponse::Ok
         }
 
+        DaemonCommand::SearchTranscripts { query, limit } => {
+            let state = state.read().await;
+            DaemonResponse::TranscriptHits {
+                hits: state.transcripts.search(&query, limit),
+            }
+        }
+
+        DaemonCommand::ListTranscripts { cwd } => {
+            let state = state.read().await;
+            DaemonResponse::Transcripts {
+                transcripts: state.transcripts.project_transcripts(&cwd),
+            }
+        }
+
         DaemonCommand::Status => {
             let state = state.read().await;
             DaemonResponse::Status {
@@ -280,21 +484,88 @@ async fn handle_command(
 
         DaemonCommand::Shutdown => {
             eprintln!("Shutdown requested, exiting...");
-            // Save state before shutdown
-            let state = state.read().await;
-            let _ = state.save();
-
-            // Clean up socket and pid file
-            let _ = fs::remove_file(get_socket_path());
-            let _ = fs::remove_file(get_pid_file_path());
+            // Clean up socket (and for the main daemon, save state and remove the pid file)
+            let _ = fs::remove_file(socket_path);
+            if primary {
+                let state = state.read().await;
+                let _ = state.save();
+                let _ = fs::remove_file(get_pid_file_path());
+                let _ = fs::remove_file(get_status_snapshot_path());
+            }
 
             std::process::exit(0);
         }
 
-        DaemonCommand::Ping => {
-            DaemonResponse::Pong
+        DaemonCommand::Ping => DaemonResponse::Pong,
+    }
+}
+
+/// Send keys to a session's pane, or forward `forward` to the remote daemon owning it
+async fn send_session_keys(
+    state: &Arc<RwLock<DaemonState>>,
+    session_id: &str,
+    keys: &[&str],
+    forward: DaemonCommand,
+    mark_pending: bool,
+) -> DaemonResponse {
+    let mut state_guard = state.write().await;
+
+    // Sessions owned by a remote daemon are handled there
+    if state_guard.get_session(session_id).is_none() {
+        if let Some(remote) = state_guard.remote_for_session(session_id) {
+            let socket = remote.socket.clone();
+            drop(state_guard);
+            return remote::send_command(&socket, &forward)
+                .await
+                .unwrap_or_else(|e| DaemonResponse::Error {
+                    message: format!("Remote daemon failed: {}", e),
+                });
         }
     }
+
+    // Mark as pending approval so we can detect external vs daemon input
+    if mark_pending {
+        state_guard.mark_pending_approval(session_id);
+    }
+
+    let Some(session) = state_guard.get_session(session_id) else {
+        return DaemonResponse::Error {
+            message: format!("Session not found: {}", session_id),
+        };
+    };
+
+    let target = session.tmux_target();
+    let server = session.tmux_server.clone();
+
+    // Drop the lock before running external commands
+    drop(state_guard);
+
+    // Send keys via tmux on the session's server, off the async runtime
+    let keys: Vec<String> = keys.iter().map(|k| k.to_string()).collect();
+    let sent = {
+        let target = target.clone();
+        tokio::task::spawn_blocking(move || {
+            keys.iter().all(|key| {
+                server
+                    .command()
+                    .args(["send-keys", "-t", &target, key])
+                    .output()
+                    .is_ok_and(|output| output.status.success())
+            })
+        })
+        .await
+        .unwrap_or(false)
+    };
+    if !sent {
+        if mark_pending {
+            state.write().await.clear_pending_approval(session_id);
+        }
+        return DaemonResponse::Error {
+            message: format!("Failed to send keys to {}", target),
+        };
+    }
+
+    DaemonResponse::Ok
 }
 
 /// Send a response to a client
@@ -1,12 +1,27 @@
 //! Daemon state management.
 
+use crate::common::api_error::ApiProblem;
+use crate::common::config::UsageConfig;
+use crate::common::status_line::StatusEntry;
+use crate::common::tmux::{find_pane_by_id, TmuxServer};
+use crate::common::transcript_index::TranscriptIndex;
+use crate::common::types::TmuxSession;
+use crate::common::usage::{
+    context_window, cost_of, format_tokens, SessionUsage, Usage, UsageLedger,
+};
+use crate::daemon::alerts::AlertTracker;
+use crate::daemon::git_cache::GitCache;
+use crate::daemon::liveness::{Liveness, LivenessTracker};
+use crate::daemon::remote::RemoteSnapshot;
 use crate::ipc::messages::{
     get_state_file_path, InputSource, MetricsHistory, SessionState, SessionStatus,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::fs;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use sysinfo::{Networks, System};
 
 /// Maximum number of metrics samples to store (30 min at 5 sec intervals = 360)
@@ -64,11 +79,9 @@ impl SystemMetrics {
         }
 
         // Network (calculate rate from total)
-        let (total_rx, total_tx) = networks
-            .iter()
-            .fold((0u64, 0u64), |(r, t), (_, d)| {
-                (r + d.total_received(), t + d.total_transmitted())
-            });
+        let (total_rx, total_tx) = networks.iter().fold((0u64, 0u64), |(r, t), (_, d)| {
+            (r + d.total_received(), t + d.total_transmitted())
+        });
 
         // Calculate bytes/sec based on time since last collection
         let (rx_rate, tx_rate) = if let Some(last) = self.last_collection {
@@ -129,15 +142,22 @@ fn get_cpu_temperature() -> Option<f32> {
     {
         // Use IOKit via command for temperature
         let output = std::process::Command::new("sudo")
-            .args(["-n", "powermetrics", "--samplers", "smc", "-n", "1", "-i", "1"])
+            .args([
+                "-n",
+                "powermetrics",
+                "--samplers",
+                "smc",
+                "-n",
+                "1",
+                "-i",
+                "1",
+            ])
             .output()
             .ok()?;
 
         if !output.status.success() {
             // Fallback: try osx-cpu-temp if installed
-            let output = std::process::Command::new("osx-cpu-temp")
-                .output()
-                .ok()?;
+            let output = std::process::Command::new("osx-cpu-temp").output().ok()?;
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 // Parse "62.5°C" format
@@ -186,6 +206,30 @@ pub struct DaemonState {
     /// System metrics history (not serialized)
     #[serde(skip)]
     pub metrics: SystemMetrics,
+    /// Resource alert tracking (not serialized)
+    #[serde(skip)]
+    pub alerts: AlertTracker,
+    /// Stall and crash detection (not serialized)
+    #[serde(skip)]
+    pub liveness: LivenessTracker,
+    /// Live tmux topology from the control-mode client (None until first sync)
+    #[serde(skip)]
+    pub tmux_sessions: Option<Vec<TmuxSession>>,
+    /// Sessions pulled from remote daemons, by remote name (not serialized)
+    #[serde(skip)]
+    pub remotes: HashMap<String, RemoteSnapshot>,
+    /// Git status per session cwd (not serialized)
+    #[serde(skip)]
+    pub git: GitCache,
+    /// Full-text index of all transcripts (persisted separately by the indexer)
+    #[serde(skip)]
+    pub transcripts: TranscriptIndex,
+    /// Where to write the status-line snapshot (None = don't write one)
+    #[serde(skip)]
+    pub status_snapshot_path: Option<PathBuf>,
+    /// Last snapshot written, to skip rewriting identical content
+    #[serde(skip)]
+    last_status_snapshot: String,
 }
 
 impl DaemonState {
@@ -242,6 +286,52 @@ impl DaemonState {
         self.sessions.values().cloned().collect()
     }
 
+    /// Local sessions followed by sessions of all reachable remotes
+    pub fn all_sessions_with_remotes(&self) -> Vec<SessionState> {
+        let mut sessions = self.all_sessions();
+        for remote in self.remotes.values() {
+            sessions.extend(remote.sessions.iter().cloned());
+        }
+        sessions
+    }
+
+    /// Write the status-line snapshot if it changed.
+    /// Local sessions whose tmux pane is gone are left out.
+    pub fn write_status_snapshot(&mut self) {
+        let Some(path) = self.status_snapshot_path.clone() else {
+            return;
+        };
+        let entries: Vec<StatusEntry> = self
+            .all_sessions_with_remotes()
+            .into_iter()
+            .filter(|s| s.pane_alive(self.tmux_sessions.as_deref()))
+            .map(|s| StatusEntry {
+                tmux_session: s.tmux_session,
+                host: s.host,
+                status: s.status,
+            })
+            .collect();
+        let Ok(content) = serde_json::to_string(&entries) else {
+            return;
+        };
+        if content == self.last_status_snapshot {
+            return;
+        }
+
+        // Write-then-rename so readers never see a partial file
+        let tmp = path.with_extension("json.tmp");
+        if fs::write(&tmp, &content).is_ok() && fs::rename(&tmp, &path).is_ok() {
+            self.last_status_snapshot = content;
+        }
+    }
+
+    /// Find the remote daemon owning a session that isn't tracked locally
+    pub fn remote_for_session(&self, session_id: &str) -> Option<&RemoteSnapshot> {
+        self.remotes
+            .values()
+            .find(|remote| remote.sessions.iter().any(|s| s.session_id == session_id))
+    }
+
     /// Check if a session has a pending approval
     pub fn has_pending_approval(&self, session_id: &str) -> bool {
         self.pending_approvals.contains_key(session_id)
@@ -280,12 +370,199 @@ impl DaemonState {
         }
     }
 
+    /// Mark sessions stalled or crashed from one liveness sample; returns the
+    /// sessions whose status changed
+    pub fn update_liveness(
+        &mut self,
+        samples: &HashMap<String, Liveness>,
+        stall_after: Option<Duration>,
+    ) -> Vec<SessionState> {
+        self.liveness
+            .evaluate(&mut self.sessions, samples, stall_after, Utc::now())
+    }
+
     /// Update last input source for a session
     pub fn update_input_source(&mut self, session_id: &str, source: InputSource) {
         if let Some(session) = self.sessions.get_mut(session_id) {
             session.last_input_source = source;
         }
     }
+
+    /// Show API errors and limits that transcripts (by path) just ended on, and
+    /// clear them once a normal reply follows. Returns sessions that just ran
+    /// into a problem.
+    pub fn update_api_problems(
+        &mut self,
+        problems: &[(String, Option<ApiProblem>)],
+    ) -> Vec<SessionState> {
+        let mut changed = Vec::new();
+        for (path, problem) in problems {
+            let stem = path
+                .rsplit('/')
+                .next()
+                .and_then(|name| name.strip_suffix(".jsonl"));
+            let Some(session) = self
+                .sessions
+                .values_mut()
+                .find(|s| match s.transcript_path {
+                    Some(ref transcript) => transcript == path,
+                    None => stem == Some(s.session_id.as_str()),
+                })
+            else {
+                continue;
+            };
+            let has_problem = matches!(
+                session.status,
+                SessionStatus::RateLimited { .. } | SessionStatus::ApiError { .. }
+            );
+            match problem {
+                Some(problem) => {
+                    let status = problem.clone().into_status();
+                    if session.status == status {
+                        continue;
+                    }
+                    if !session.needs_attention {
+                        session.attention_since = Some(Utc::now().to_rfc3339());
+                    }
+                    session.status = status;
+                    session.needs_attention = true;
+                    changed.push(session.clone());
+                }
+                None if has_problem => {
+                    session.status = SessionStatus::Working;
+                    session.needs_attention = false;
+                    session.attention_since = None;
+                }
+                None => {}
+            }
+        }
+        changed
+    }
+
+    /// Rate-limited sessions whose limit reset within `window`
+    pub fn due_resumes(&self, now: DateTime<Utc>, window: Duration) -> Vec<SessionState> {
+        let mut due = Vec::new();
+        for session in self.sessions.values() {
+            let SessionStatus::RateLimited {
+                resets_at: Some(ref resets_at),
+            } = session.status
+            else {
+                continue;
+            };
+            let Ok(resets_at) = DateTime::parse_from_rfc3339(resets_at) else {
+                continue;
+            };
+            let since_reset = now.signed_duration_since(resets_at);
+            if session.host.is_some()
+                || since_reset < chrono::Duration::zero()
+                || since_reset.to_std().is_ok_and(|since| since > window)
+            {
+                continue;
+            }
+            due.push(session.clone());
+        }
+        due
+    }
+
+    /// Mark a session working again once it was resumed, so it's resumed only once.
+    /// None if it isn't rate-limited any more.
+    pub fn mark_resumed(&mut self, session_id: &str) -> Option<SessionState> {
+        let session = self.sessions.get_mut(session_id)?;
+        if !matches!(session.status, SessionStatus::RateLimited { .. }) {
+            return None;
+        }
+        session.status = SessionStatus::Working;
+        session.needs_attention = false;
+        session.attention_since = None;
+        Some(session.clone())
+    }
+
+    /// Recompute each session's usage from its indexed transcript (the hook's
+    /// transcript path, else `<session_id>.jsonl`). Returns sessions that just went over budget or
+    /// past the context warning, as (server, tmux session, message) to notify about.
+    pub fn update_session_usage(
+        &mut self,
+        config: &UsageConfig,
+    ) -> Vec<(TmuxServer, String, String)> {
+        let by_session_id: HashMap<&str, &UsageLedger> = self
+            .transcripts
+            .files
+            .iter()
+            .filter_map(|(path, file)| {
+                let stem = path.rsplit('/').next()?.strip_suffix(".jsonl")?;
+                Some((stem, &file.usage))
+            })
+            .collect();
+
+        let mut notices = Vec::new();
+        for session in self.sessions.values_mut() {
+            let ledger = session
+                .transcript_path
+                .as_ref()
+                .and_then(|path| self.transcripts.files.get(path))
+                .map(|file| &file.usage)
+                .or_else(|| by_session_id.get(session.session_id.as_str()).copied());
+            let Some(ledger) = ledger else {
+                continue;
+            };
+            let by_model = ledger.by_model(None);
+            let mut tokens = Usage::default();
+            for usage in by_model.values() {
+                tokens.add(usage);
+            }
+            let (context_tokens, context_window) = match ledger.context {
+                Some(ref context) => (
+                    context.tokens,
+                    context_window(&context.model, context.tokens, &config.context_windows),
+                ),
+                None => (0, 0),
+            };
+            let usage = SessionUsage {
+                tokens,
+                cost_usd: cost_of(&by_model, &config.prices),
+                budget_usd: config.budget_for(&session.tmux_session),
+                context_tokens,
+                context_window,
+            };
+            if usage.over_budget() && !session.budget_notified {
+                session.budget_notified = true;
+                notices.push((
+                    session.tmux_server.clone(),
+                    session.tmux_session.clone(),
+                    format!(
+                        "over budget: ${:.2} of ${:.2}",
+                        usage.cost_usd,
+                        usage.budget_usd.unwrap_or_default()
+                    ),
+                ));
+            } else if !usage.over_budget() {
+                // Budget raised in the config: notify again if it's crossed later
+                session.budget_notified = false;
+            }
+            let percent = usage.context_percent().unwrap_or(0.0);
+            let context_full = config
+                .context_warn_percent
+                .is_some_and(|warn| percent >= warn);
+            if context_full && !session.context_notified {
+                session.context_notified = true;
+                notices.push((
+                    session.tmux_server.clone(),
+                    session.tmux_session.clone(),
+                    format!(
+                        "context {:.0}% full ({} of {})",
+                        percent,
+                        format_tokens(usage.context_tokens),
+                        format_tokens(usage.context_window)
+                    ),
+                ));
+            } else if !context_full {
+                // Compacted or cleared: warn again when it fills up
+                session.context_notified = false;
+            }
+            session.usage = Some(Box::new(usage));
+        }
+        notices
+    }
 }
 
 impl SessionState {
@@ -302,13 +579,154 @@ impl SessionState {
             tmux_session,
             tmux_window,
             tmux_pane,
+            tmux_server: TmuxServer::Current,
+            tmux_pane_id: String::new(),
+            host: None,
+            attention_since: None,
             cwd,
+            transcript_path: None,
             status: SessionStatus::Unknown,
             needs_attention: false,
             last_input_source: InputSource::Unknown,
             last_activity: None,
             cpu_percent: 0.0,
             memory_kb: 0,
+            pending_tool: None,
+            risk: None,
+            agent_todos: Box::default(),
+            usage: None,
+            budget_notified: false,
+            context_notified: false,
+        }
+    }
+
+    /// Whether the session's tmux pane still exists. Remote sessions, sessions
+    /// without a known pane, and an unsynced topology count as alive.
+    pub fn pane_alive(&self, tmux_sessions: Option<&[TmuxSession]>) -> bool {
+        match tmux_sessions {
+            Some(tmux) if self.host.is_none() && !self.tmux_pane_id.is_empty() => {
+                find_pane_by_id(tmux, &self.tmux_server, &self.tmux_pane_id).is_some()
+            }
+            _ => true,
+        }
+    }
+
+    /// tmux target for the Claude pane (pane id is stable, fall back to session:window.pane)
+    pub fn tmux_target(&self) -> String {
+        if self.tmux_pane_id.is_empty() {
+            format!(
+                "{}:{}.{}",
+                self.tmux_session, self.tmux_window, self.tmux_pane
+            )
+        } else {
+            self.tmux_pane_id.clone()
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::usage::UsageRecord;
+
+    #[test]
+    fn test_session_usage_notifies_once_per_crossing() {
+        let mut state = DaemonState::new();
+        state.upsert_session(SessionState::new(
+            "abc".to_string(),
+            "api".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            "/work/api".to_string(),
+        ));
+        let path = "/p/-work-api/abc.jsonl".to_string();
+        let respond = |state: &mut DaemonState, id: &str, output: u64, cache_read: u64| {
+            let file = state.transcripts.files.entry(path.clone()).or_default();
+            file.usage.record(UsageRecord {
+                message_id: id.to_string(),
+                model: "claude-sonnet-4-5".to_string(),
+                day: "2026-03-02".to_string(),
+                usage: Usage {
+                    output,
+                    cache_read,
+                    ..Default::default()
+                },
+                sidechain: false,
+            });
+        };
+        let config = UsageConfig {
+            session_budget_usd: Some(1.0),
+            ..Default::default()
+        };
+
+        respond(&mut state, "msg_1", 100_000, 80_000);
+        let notices: Vec<String> = state
+            .update_session_usage(&config)
+            .into_iter()
+            .map(|(_, _, message)| message)
+            .collect();
+        assert_eq!(
+            notices,
+            vec![
+                "over budget: $1.52 of $1.00",
+                "context 90% full (180k of 200k)"
+            ]
+        );
+        let usage = state.get_session("abc").unwrap().usage.clone().unwrap();
+        assert_eq!(usage.tokens.output, 100_000);
+        assert!(state.update_session_usage(&config).is_empty());
+
+        // Compacted, then filled up again: warned again, budget only once
+        respond(&mut state, "msg_2", 1_000, 10_000);
+        assert!(state.update_session_usage(&config).is_empty());
+        respond(&mut state, "msg_3", 1_000, 190_000);
+        let notices = state.update_session_usage(&config);
+        assert_eq!(notices.len(), 1);
+        assert!(notices[0].2.starts_with("context 96% full"));
+    }
+
+    #[test]
+    fn test_limit_from_transcript_resumes_once_after_reset() {
+        let mut state = DaemonState::new();
+        state.upsert_session(SessionState::new(
+            "abc".to_string(),
+            "api".to_string(),
+            "0".to_string(),
+            "0".to_string(),
+            "/work/api".to_string(),
+        ));
+        let now = Utc::now();
+        let path = "/home/dev/.claude/projects/-work-api/abc.jsonl".to_string();
+        let limited = ApiProblem::RateLimited {
+            resets_at: Some(now + chrono::Duration::minutes(5)),
+        };
+
+        let hit = state.update_api_problems(&[(path.clone(), Some(limited.clone()))]);
+        assert_eq!(hit.len(), 1);
+        assert!(hit[0].needs_attention);
+        assert!(state
+            .update_api_problems(&[(path.clone(), Some(limited))])
+            .is_empty());
+
+        let window = Duration::from_secs(900);
+        assert!(state.due_resumes(now, window).is_empty());
+        let later = now + chrono::Duration::minutes(6);
+        assert_eq!(state.due_resumes(later, window).len(), 1);
+        // Still due until the prompt was typed
+        assert_eq!(state.due_resumes(later, window).len(), 1);
+        let resumed = state.mark_resumed("abc").unwrap();
+        assert_eq!(resumed.status, SessionStatus::Working);
+        assert!(state.due_resumes(later, window).is_empty());
+        assert!(state.mark_resumed("abc").is_none());
+
+        // An error cleared by a later normal reply
+        let overloaded = ApiProblem::ApiError {
+            message: "overloaded".to_string(),
+        };
+        state.update_api_problems(&[(path.clone(), Some(overloaded))]);
+        state.update_api_problems(&[(path, None)]);
+        let session = state.get_session("abc").unwrap();
+        assert_eq!(session.status, SessionStatus::Working);
+        assert!(!session.needs_attention);
+    }
+}
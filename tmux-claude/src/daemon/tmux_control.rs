@@ -0,0 +1,750 @@
+//! tmux control-mode (`tmux -C`) client keeping a live model of sessions, windows and panes.
+//!
+//! One client runs per discovered tmux server. Its model is loaded with a single
+//! `list-panes -a` when the client connects and then kept current from notifications:
+//! renames and window closes are applied directly, and added or re-laid-out windows are
+//! re-read one at a time over the control connection. A format subscription listing
+//! every pane with its working directory covers what has no notification (pane cwds,
+//! panes of sessions the client isn't attached to) and reveals drift, which is repaired
+//! window by window. The full reparse only runs again when a notification names an id
+//! the model doesn't know.
+
+use crate::common::tmux::{discover_tmux_servers, TmuxServer};
+use crate::common::types::{TmuxPane, TmuxSession, TmuxWindow};
+use crate::daemon::state::DaemonState;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{ChildStdin, Stdio};
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio::time::{Duration, Instant, MissedTickBehavior};
+
+/// Pane listing format of topology queries, one line per pane
+const PANE_FORMAT: &str = "#{session_id}\t#{session_name}\t#{window_id}\t#{window_index}\t#{window_name}\t#{pane_index}\t#{pane_id}\t#{pane_pid}\t#{pane_current_path}";
+
+/// Name of the subscription listing every pane
+const SUBSCRIPTION: &str = "panes";
+
+/// Subscription format: "session window pane cwd" per pane, tab separated.
+/// tmux re-evaluates it about once a second and notifies only when it changes.
+const SUBSCRIPTION_FORMAT: &str =
+    "#{S:#{W:#{P:#{session_id} #{window_id} #{pane_id} #{pane_current_path}\t}}}";
+
+/// Wait for a burst of model updates to settle before publishing
+const DEBOUNCE: Duration = Duration::from_millis(150);
+/// How often the socket directory is checked for new servers
+const DISCOVERY_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay before restarting a server's control client after it exits
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Event from a control-mode reader thread
+#[derive(Debug)]
+enum ControlEvent {
+    /// Current sessions of a server
+    Topology(TmuxServer, Vec<TmuxSession>),
+    Exited(TmuxServer),
+}
+
+/// Command sent over the control connection to read part of the topology
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    /// Start the pane subscription
+    Subscribe,
+    /// Every pane of the server
+    All,
+    /// Panes of one session (by `$id`)
+    Session(String),
+    /// Panes of one window (by `@id`)
+    Window(String),
+    /// Ids of all sessions
+    SessionIds,
+}
+
+impl Query {
+    /// Control-mode command line for this query
+    pub fn command(&self) -> String {
+        match self {
+            Query::Subscribe => format!(
+                "refresh-client -B '{}::{}'",
+                SUBSCRIPTION, SUBSCRIPTION_FORMAT
+            ),
+            Query::All => format!("list-panes -a -F '{}'", PANE_FORMAT),
+            Query::Session(id) => format!("list-panes -s -t '{}' -F '{}'", id, PANE_FORMAT),
+            Query::Window(id) => format!("list-panes -t '{}' -F '{}'", id, PANE_FORMAT),
+            Query::SessionIds => "list-sessions -F '#{session_id}'".to_string(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SessionNode {
+    id: String,
+    name: String,
+    windows: Vec<WindowNode>,
+}
+
+#[derive(Debug)]
+struct WindowNode {
+    id: String,
+    index: String,
+    name: String,
+    panes: Vec<TmuxPane>,
+}
+
+/// Output block of a command being read
+#[derive(Debug)]
+struct Block {
+    /// Whether the command came from this client (the attach command's block doesn't)
+    ours: bool,
+    lines: Vec<String>,
+}
+
+/// Model of one server's topology, fed with control-mode output lines
+#[derive(Debug, Default)]
+pub struct ControlClient {
+    sessions: Vec<SessionNode>,
+    /// Queries sent and awaiting their output block, oldest first
+    pending: VecDeque<Query>,
+    block: Option<Block>,
+    changed: bool,
+}
+
+impl ControlClient {
+    /// Queries to send once connected
+    pub fn connect(&mut self) -> Vec<Query> {
+        self.request(vec![Query::Subscribe, Query::All])
+    }
+
+    /// Feed one output line; returns the queries to send in response
+    pub fn feed(&mut self, line: &str) -> Vec<Query> {
+        if let Some(block) = &mut self.block {
+            let ok = line.starts_with("%end ");
+            if !ok && !line.starts_with("%error ") {
+                block.lines.push(line.to_string());
+                return Vec::new();
+            }
+            let block = self.block.take().expect("block being read");
+            if !block.ours {
+                return Vec::new();
+            }
+            return match self.pending.pop_front() {
+                Some(query) => self.apply_response(query, ok.then_some(block.lines)),
+                None => Vec::new(),
+            };
+        }
+
+        let (name, args) = line.split_once(' ').unwrap_or((line, ""));
+        match name {
+            "%begin" => {
+                self.block = Some(Block {
+                    ours: args.split(' ').nth(2) == Some("1"),
+                    lines: Vec::new(),
+                });
+                Vec::new()
+            }
+            "%session-renamed" => {
+                let (id, new_name) = args.split_once(' ').unwrap_or((args, ""));
+                match self.sessions.iter_mut().find(|s| s.id == id) {
+                    Some(session) => {
+                        session.name = new_name.to_string();
+                        self.changed = true;
+                        Vec::new()
+                    }
+                    None => self.request(vec![Query::All]),
+                }
+            }
+            "%window-renamed" | "%unlinked-window-renamed" => {
+                let (id, new_name) = args.split_once(' ').unwrap_or((args, ""));
+                let window = self
+                    .sessions
+                    .iter_mut()
+                    .flat_map(|s| s.windows.iter_mut())
+                    .find(|w| w.id == id);
+                match window {
+                    Some(window) => {
+                        window.name = new_name.to_string();
+                        self.changed = true;
+                        Vec::new()
+                    }
+                    None => self.request(vec![Query::All]),
+                }
+            }
+            "%window-close" | "%unlinked-window-close" => {
+                self.remove_window(args.trim());
+                Vec::new()
+            }
+            "%window-add" | "%unlinked-window-add" | "%layout-change" => {
+                let id = args.split(' ').next().unwrap_or_default();
+                self.request(vec![Query::Window(id.to_string())])
+            }
+            "%sessions-changed" => self.request(vec![Query::SessionIds]),
+            "%subscription-changed" => match args.split_once(" : ") {
+                Some((header, value)) if header.split(' ').next() == Some(SUBSCRIPTION) => {
+                    self.apply_pane_list(value)
+                }
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether the model changed since the last call and no queries are outstanding
+    pub fn take_changed(&mut self) -> bool {
+        let changed = self.changed && self.pending.is_empty();
+        if changed {
+            self.changed = false;
+        }
+        changed
+    }
+
+    /// Sessions of the model in tmux's order (by name, then window and pane index)
+    pub fn sessions(&self, server: &TmuxServer) -> Vec<TmuxSession> {
+        let by_index = |index: &str| (index.parse::<u32>().unwrap_or(u32::MAX), index.to_string());
+        let mut sessions: Vec<TmuxSession> = self
+            .sessions
+            .iter()
+            .map(|s| {
+                let mut windows: Vec<TmuxWindow> = s
+                    .windows
+                    .iter()
+                    .map(|w| {
+                        let mut panes = w.panes.clone();
+                        panes.sort_by_key(|p| by_index(&p.index));
+                        TmuxWindow {
+                            index: w.index.clone(),
+                            name: w.name.clone(),
+                            panes,
+                        }
+                    })
+                    .collect();
+                windows.sort_by_key(|w| by_index(&w.index));
+                TmuxSession {
+                    name: s.name.clone(),
+                    windows,
+                    server: server.clone(),
+                }
+            })
+            .collect();
+        sessions.sort_by(|a, b| a.name.cmp(&b.name));
+        sessions
+    }
+
+    /// Record queries as sent (dropping repeats within the batch) and return them
+    fn request(&mut self, queries: Vec<Query>) -> Vec<Query> {
+        let mut sent: Vec<Query> = Vec::new();
+        for query in queries {
+            if !sent.contains(&query) {
+                self.pending.push_back(query.clone());
+                sent.push(query);
+            }
+        }
+        sent
+    }
+
+    /// Apply a query's output (None if tmux answered with an error)
+    fn apply_response(&mut self, query: Query, lines: Option<Vec<String>>) -> Vec<Query> {
+        match query {
+            Query::Subscribe => {}
+            Query::All => {
+                if let Some(lines) = lines {
+                    self.sessions.clear();
+                    self.insert_rows(&lines);
+                    self.changed = true;
+                }
+            }
+            Query::Session(id) => {
+                self.sessions.retain(|s| s.id != id);
+                self.insert_rows(&lines.unwrap_or_default());
+                self.changed = true;
+            }
+            Query::Window(id) => {
+                self.remove_window(&id);
+                self.insert_rows(&lines.unwrap_or_default());
+                self.changed = true;
+            }
+            Query::SessionIds => {
+                let Some(ids) = lines else {
+                    return Vec::new();
+                };
+                let before = self.sessions.len();
+                self.sessions.retain(|s| ids.contains(&s.id));
+                self.changed |= self.sessions.len() != before;
+                let added = ids
+                    .into_iter()
+                    .filter(|id| !self.sessions.iter().any(|s| s.id == *id))
+                    .map(Query::Session)
+                    .collect();
+                return self.request(added);
+            }
+        }
+        Vec::new()
+    }
+
+    /// Update pane cwds from the subscription and re-read windows that drifted
+    fn apply_pane_list(&mut self, value: &str) -> Vec<Query> {
+        // Window id -> (session id, pane ids)
+        let mut listed: HashMap<&str, (&str, HashSet<&str>)> = HashMap::new();
+        let mut cwds: HashMap<&str, &str> = HashMap::new();
+        for entry in value.split('\t') {
+            let parts: Vec<&str> = entry.splitn(4, ' ').collect();
+            if let [session_id, window_id, pane_id, cwd] = parts[..] {
+                listed
+                    .entry(window_id)
+                    .or_insert_with(|| (session_id, HashSet::new()))
+                    .1
+                    .insert(pane_id);
+                cwds.insert(pane_id, cwd);
+            }
+        }
+        let listed_sessions: HashSet<&str> = listed.values().map(|(s, _)| *s).collect();
+
+        // Sessions and windows that are gone
+        let before: usize = self.sessions.iter().map(|s| s.windows.len()).sum();
+        self.sessions
+            .retain(|s| listed_sessions.contains(s.id.as_str()));
+        for session in &mut self.sessions {
+            session
+                .windows
+                .retain(|w| listed.contains_key(w.id.as_str()));
+        }
+        self.sessions.retain(|s| !s.windows.is_empty());
+        let after: usize = self.sessions.iter().map(|s| s.windows.len()).sum();
+        self.changed |= after != before;
+
+        for pane in self
+            .sessions
+            .iter_mut()
+            .flat_map(|s| s.windows.iter_mut())
+            .flat_map(|w| w.panes.iter_mut())
+        {
+            if let Some(cwd) = cwds.get(pane.id.as_str()) {
+                if pane.cwd != *cwd {
+                    pane.cwd = cwd.to_string();
+                    self.changed = true;
+                }
+            }
+        }
+
+        // New sessions, and windows whose panes differ
+        let mut queries = Vec::new();
+        for session_id in &listed_sessions {
+            if !self.sessions.iter().any(|s| s.id == *session_id) {
+                queries.push(Query::Session(session_id.to_string()));
+            }
+        }
+        for (window_id, (session_id, pane_ids)) in &listed {
+            let Some(session) = self.sessions.iter().find(|s| s.id == *session_id) else {
+                continue;
+            };
+            let in_sync = session.windows.iter().any(|w| {
+                w.id == *window_id
+                    && w.panes.len() == pane_ids.len()
+                    && w.panes.iter().all(|p| pane_ids.contains(p.id.as_str()))
+            });
+            if !in_sync {
+                queries.push(Query::Window(window_id.to_string()));
+            }
+        }
+        queries.sort_by_key(|q| format!("{:?}", q));
+        self.request(queries)
+    }
+
+    /// Drop a window, and its session if that was the last window
+    fn remove_window(&mut self, id: &str) {
+        for session in &mut self.sessions {
+            let before = session.windows.len();
+            session.windows.retain(|w| w.id != id);
+            self.changed |= session.windows.len() != before;
+        }
+        self.sessions.retain(|s| !s.windows.is_empty());
+    }
+
+    /// Add `PANE_FORMAT` rows to the model
+    fn insert_rows(&mut self, lines: &[String]) {
+        for line in lines {
+            let parts: Vec<&str> = line.splitn(9, '\t').collect();
+            let [session_id, session_name, window_id, window_index, window_name, pane_index, pane_id, pane_pid, cwd] =
+                parts[..]
+            else {
+                continue;
+            };
+            let Ok(pid) = pane_pid.parse::<u32>() else {
+                continue;
+            };
+
+            let session = match self.sessions.iter().position(|s| s.id == session_id) {
+                Some(i) => &mut self.sessions[i],
+                None => {
+                    self.sessions.push(SessionNode {
+                        id: session_id.to_string(),
+                        name: String::new(),
+                        windows: Vec::new(),
+                    });
+                    self.sessions.last_mut().expect("session just pushed")
+                }
+            };
+            session.name = session_name.to_string();
+            let window = match session.windows.iter().position(|w| w.id == window_id) {
+                Some(i) => &mut session.windows[i],
+                None => {
+                    session.windows.push(WindowNode {
+                        id: window_id.to_string(),
+                        index: String::new(),
+                        name: String::new(),
+                        panes: Vec::new(),
+                    });
+                    session.windows.last_mut().expect("window just pushed")
+                }
+            };
+            window.index = window_index.to_string();
+            window.name = window_name.to_string();
+            window.panes.retain(|p| p.id != pane_id);
+            window.panes.push(TmuxPane {
+                index: pane_index.to_string(),
+                id: pane_id.to_string(),
+                pid,
+                cwd: cwd.to_string(),
+            });
+        }
+    }
+}
+
+/// Write queries to the control client; false once the connection is gone
+fn send_queries(stdin: &mut ChildStdin, queries: &[Query]) -> bool {
+    queries
+        .iter()
+        .all(|q| writeln!(stdin, "{}", q.command()).is_ok())
+}
+
+/// Run a control-mode client on one server until it exits, forwarding its topology
+fn run_control_client(server: TmuxServer, tx: mpsc::UnboundedSender<ControlEvent>) {
+    let child = server
+        .command()
+        .args([
+            "-C",
+            "attach-session",
+            "-f",
+            "ignore-size,no-output,read-only",
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+
+    if let Ok(mut child) = child {
+        // Keep stdin open: the control client detaches when it closes
+        if let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) {
+            let mut client = ControlClient::default();
+            let mut connected = send_queries(&mut stdin, &client.connect());
+            let mut lines = BufReader::new(stdout).lines().map_while(Result::ok);
+            while connected {
+                let Some(line) = lines.next() else {
+                    break;
+                };
+                let queries = client.feed(&line);
+                connected = send_queries(&mut stdin, &queries);
+                if client.take_changed() {
+                    let sessions = client.sessions(&server);
+                    connected &= tx
+                        .send(ControlEvent::Topology(server.clone(), sessions))
+                        .is_ok();
+                }
+            }
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+
+    let _ = tx.send(ControlEvent::Exited(server));
+}
+
+/// A server's control client
+struct ClientSlot {
+    running: bool,
+    /// When the client started (while running) or exited
+    since: Instant,
+    /// Latest topology reported (None until the first one)
+    sessions: Option<Vec<TmuxSession>>,
+}
+
+/// Start clients for new servers, and restart exited ones after a delay
+async fn start_clients(
+    clients: &mut HashMap<TmuxServer, ClientSlot>,
+    order: &mut Vec<TmuxServer>,
+    tx: &mpsc::UnboundedSender<ControlEvent>,
+) {
+    *order = tokio::task::spawn_blocking(discover_tmux_servers)
+        .await
+        .unwrap_or_default();
+    for server in order.iter() {
+        let can_start = match clients.get(server) {
+            Some(slot) => !slot.running && slot.since.elapsed() >= RECONNECT_DELAY,
+            None => true,
+        };
+        if can_start {
+            let sessions = clients.remove(server).and_then(|slot| slot.sessions);
+            clients.insert(
+                server.clone(),
+                ClientSlot {
+                    running: true,
+                    since: Instant::now(),
+                    sessions,
+                },
+            );
+            let server = server.clone();
+            let tx = tx.clone();
+            std::thread::spawn(move || run_control_client(server, tx));
+        }
+    }
+}
+
+/// Update the client table from a reader event; true if the topology changed
+fn handle_event(clients: &mut HashMap<TmuxServer, ClientSlot>, event: ControlEvent) -> bool {
+    match event {
+        ControlEvent::Topology(server, sessions) => {
+            if let Some(slot) = clients.get_mut(&server) {
+                slot.sessions = Some(sessions);
+            }
+            true
+        }
+        ControlEvent::Exited(server) => {
+            let Some(slot) = clients.get_mut(&server) else {
+                return false;
+            };
+            // A client that ran for a while (its session ended, or the server went
+            // away) is restarted right away and keeps its topology until it reports
+            // again; one that failed quickly waits and drops its sessions.
+            let healthy = slot.since.elapsed() >= RECONNECT_DELAY;
+            slot.running = false;
+            if healthy {
+                slot.since = Instant::now() - RECONNECT_DELAY;
+                false
+            } else {
+                slot.since = Instant::now();
+                slot.sessions.take().is_some()
+            }
+        }
+    }
+}
+
+/// Publish the merged topology once every running client has reported
+async fn publish(
+    state: &Arc<RwLock<DaemonState>>,
+    clients: &HashMap<TmuxServer, ClientSlot>,
+    order: &[TmuxServer],
+) {
+    if clients.values().any(|c| c.running && c.sessions.is_none()) {
+        return;
+    }
+    let sessions: Vec<TmuxSession> = order
+        .iter()
+        .filter_map(|server| clients.get(server)?.sessions.clone())
+        .flatten()
+        .collect();
+    let mut state = state.write().await;
+    state.tmux_sessions = Some(sessions);
+    // Sessions whose panes closed drop out of the status line
+    state.write_status_snapshot();
+}
+
+/// Keep `DaemonState::tmux_sessions` up to date (runs forever)
+pub async fn run(state: Arc<RwLock<DaemonState>>) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut clients: HashMap<TmuxServer, ClientSlot> = HashMap::new();
+    let mut order: Vec<TmuxServer> = Vec::new();
+    let mut published = false;
+    let mut interval = tokio::time::interval(DISCOVERY_INTERVAL);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let mut changed = false;
+        tokio::select! {
+            event = rx.recv() => {
+                let Some(event) = event else {
+                    return;
+                };
+                let exited = matches!(event, ControlEvent::Exited(_));
+                changed |= handle_event(&mut clients, event);
+                // Coalesce the burst (e.g. several servers reporting on connect)
+                tokio::time::sleep(DEBOUNCE).await;
+                while let Ok(event) = rx.try_recv() {
+                    changed |= handle_event(&mut clients, event);
+                }
+                if exited {
+                    start_clients(&mut clients, &mut order, &tx).await;
+                }
+            }
+            _ = interval.tick() => {
+                let before = order.clone();
+                start_clients(&mut clients, &mut order, &tx).await;
+                changed |= order != before;
+            }
+        }
+
+        if changed || !published {
+            publish(&state, &clients, &order).await;
+            published = state.read().await.tmux_sessions.is_some();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feed lines, answering queries with the given output blocks in order
+    fn feed_all(client: &mut ControlClient, lines: &[&str]) -> Vec<Query> {
+        lines.iter().flat_map(|line| client.feed(line)).collect()
+    }
+
+    fn row(session: (&str, &str), window: (&str, &str), pane: (&str, &str), cwd: &str) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\tzsh\t{}\t{}\t100\t{}",
+            session.0, session.1, window.0, window.1, pane.0, pane.1, cwd
+        )
+    }
+
+    fn respond(client: &mut ControlClient, rows: &[String]) -> Vec<Query> {
+        let mut queries = client.feed("%begin 1700000000 10 1");
+        for row in rows {
+            queries.extend(client.feed(row));
+        }
+        queries.extend(client.feed("%end 1700000000 10 1"));
+        queries
+    }
+
+    /// Client loaded with sessions "api" (windows @0, @1) and "web" (window @2)
+    fn loaded() -> ControlClient {
+        let mut client = ControlClient::default();
+        assert_eq!(client.connect(), vec![Query::Subscribe, Query::All]);
+        // The attach command's own block is not a reply to our queries
+        feed_all(
+            &mut client,
+            &["%begin 1700000000 1 0", "%end 1700000000 1 0"],
+        );
+        respond(&mut client, &[]);
+        respond(
+            &mut client,
+            &[
+                row(("$0", "api"), ("@0", "0"), ("0", "%0"), "/work/api"),
+                row(("$0", "api"), ("@1", "1"), ("0", "%1"), "/work/api/src"),
+                row(("$1", "web"), ("@2", "0"), ("0", "%2"), "/work/web"),
+            ],
+        );
+        assert!(client.take_changed());
+        client
+    }
+
+    fn pane_ids(client: &ControlClient) -> Vec<(String, String, String)> {
+        client
+            .sessions(&TmuxServer::Current)
+            .into_iter()
+            .flat_map(|s| {
+                s.windows.into_iter().flat_map(move |w| {
+                    let name = s.name.clone();
+                    w.panes
+                        .into_iter()
+                        .map(move |p| (name.clone(), w.index.clone(), p.id))
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_renames_and_closes_apply_without_queries() {
+        let mut client = loaded();
+        let queries = feed_all(
+            &mut client,
+            &[
+                "%session-renamed $1 frontend",
+                "%window-renamed @0 edit",
+                "%unlinked-window-close @1",
+                "%output %0 hello",
+                "%session-window-changed $0 @0",
+            ],
+        );
+        assert!(queries.is_empty());
+        assert!(client.take_changed());
+        let sessions = client.sessions(&TmuxServer::Current);
+        assert_eq!(sessions[0].name, "api");
+        assert_eq!(sessions[0].windows.len(), 1);
+        assert_eq!(sessions[0].windows[0].name, "edit");
+        assert_eq!(sessions[1].name, "frontend");
+
+        // Closing a session's last window drops the session
+        client.feed("%window-close @2");
+        assert_eq!(client.sessions(&TmuxServer::Current).len(), 1);
+    }
+
+    #[test]
+    fn test_new_windows_are_read_one_at_a_time() {
+        let mut client = loaded();
+        let queries = client.feed("%layout-change @2 b25d,80x24,0,0,2 b25d,80x24,0,0,2 *");
+        assert_eq!(queries, vec![Query::Window("@2".to_string())]);
+        assert!(!client.take_changed());
+        respond(
+            &mut client,
+            &[
+                row(("$1", "web"), ("@2", "0"), ("0", "%2"), "/work/web"),
+                row(("$1", "web"), ("@2", "0"), ("1", "%5"), "/work/web/src"),
+            ],
+        );
+        assert!(client.take_changed());
+        assert_eq!(pane_ids(&client).len(), 4);
+
+        // A window that is gone by the time it's read answers with an error
+        assert_eq!(
+            client.feed("%window-add @7"),
+            vec![Query::Window("@7".to_string())]
+        );
+        feed_all(
+            &mut client,
+            &[
+                "%begin 1700000000 11 1",
+                "can't find window: @7",
+                "%error 1700000000 11 1",
+            ],
+        );
+        assert_eq!(pane_ids(&client).len(), 4);
+    }
+
+    #[test]
+    fn test_subscription_updates_cwds_and_repairs_drift() {
+        let mut client = loaded();
+        // %1 moved to another directory; %2 was killed and %6 split off in web's window
+        let queries = client.feed(
+            "%subscription-changed panes $0 - - - : $0 @0 %0 /work/api\t$0 @1 %1 /tmp/other dir\t$1 @2 %6 /work/web\t",
+        );
+        assert_eq!(queries, vec![Query::Window("@2".to_string())]);
+        let sessions = client.sessions(&TmuxServer::Current);
+        assert_eq!(sessions[0].windows[1].panes[0].cwd, "/tmp/other dir");
+
+        // A session that only shows up in the subscription is read on its own
+        let queries = client.feed(
+            "%subscription-changed panes $0 - - - : $0 @0 %0 /work/api\t$0 @1 %1 /tmp\t$3 @9 %9 /srv\t",
+        );
+        assert_eq!(queries, vec![Query::Session("$3".to_string())]);
+        // ... and "web", no longer listed, is gone
+        assert!(pane_ids(&client).iter().all(|(s, _, _)| s != "web"));
+    }
+
+    #[test]
+    fn test_unknown_ids_and_session_changes() {
+        let mut client = loaded();
+        // A rename of a session the model doesn't know means it is out of sync
+        assert_eq!(client.feed("%session-renamed $8 ghost"), vec![Query::All]);
+        respond(
+            &mut client,
+            &[row(("$8", "ghost"), ("@8", "0"), ("0", "%8"), "/")],
+        );
+        assert_eq!(
+            pane_ids(&client),
+            vec![("ghost".to_string(), "0".to_string(), "%8".to_string())]
+        );
+
+        assert_eq!(client.feed("%sessions-changed"), vec![Query::SessionIds]);
+        let queries = respond(&mut client, &["$9".to_string()]);
+        assert_eq!(queries, vec![Query::Session("$9".to_string())]);
+        assert!(pane_ids(&client).is_empty());
+    }
+}
@@ -1,6 +1,24 @@
 //! IPC message types for daemon-TUI communication.
 
+use crate::common::agent_todos::AgentTodo;
+use crate::common::git::GitStatus;
+use crate::common::risk::Risk;
+use crate::common::tmux::TmuxServer;
+use crate::common::transcript::TranscriptSummary;
+use crate::common::transcript_index::TranscriptHit;
+use crate::common::types::TmuxSession;
+use crate::common::usage::SessionUsage;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Where a hook ran, from the $TMUX and $TMUX_PANE environment variables
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TmuxLocation {
+    /// Server socket path (first field of $TMUX)
+    pub socket: String,
+    /// Pane id, e.g. "%3"
+    pub pane_id: String,
+}
 
 /// Hook events sent from Claude Code hooks to the daemon
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -9,6 +27,12 @@ pub enum HookEvent {
     Stop {
         session_id: String,
         cwd: String,
+        /// tmux pane the hook ran in (absent for hooks outside tmux)
+        #[serde(default)]
+        tmux: Option<TmuxLocation>,
+        /// Transcript JSONL of the session, as reported by Claude
+        #[serde(default)]
+        transcript_path: Option<String>,
     },
     /// Tool is about to be executed (may or may not need permission)
     PreToolUse {
@@ -16,12 +40,24 @@ pub enum HookEvent {
         cwd: String,
         tool_name: String,
         tool_input: Option<serde_json::Value>,
+        /// tmux pane the hook ran in (absent for hooks outside tmux)
+        #[serde(default)]
+        tmux: Option<TmuxLocation>,
+        /// Transcript JSONL of the session, as reported by Claude
+        #[serde(default)]
+        transcript_path: Option<String>,
     },
     /// Tool execution completed
     PostToolUse {
         session_id: String,
         cwd: String,
         tool_name: String,
+        /// tmux pane the hook ran in (absent for hooks outside tmux)
+        #[serde(default)]
+        tmux: Option<TmuxLocation>,
+        /// Transcript JSONL of the session, as reported by Claude
+        #[serde(default)]
+        transcript_path: Option<String>,
     },
     /// Permission is being requested (user must approve)
     PermissionRequest {
@@ -29,17 +65,35 @@ pub enum HookEvent {
         cwd: String,
         tool_name: String,
         tool_input: Option<serde_json::Value>,
+        /// tmux pane the hook ran in (absent for hooks outside tmux)
+        #[serde(default)]
+        tmux: Option<TmuxLocation>,
+        /// Transcript JSONL of the session, as reported by Claude
+        #[serde(default)]
+        transcript_path: Option<String>,
     },
     /// User submitted a prompt (used for external input detection)
     UserPromptSubmit {
         session_id: String,
         cwd: String,
+        /// tmux pane the hook ran in (absent for hooks outside tmux)
+        #[serde(default)]
+        tmux: Option<TmuxLocation>,
+        /// Transcript JSONL of the session, as reported by Claude
+        #[serde(default)]
+        transcript_path: Option<String>,
     },
     /// Notification event from Claude
     Notification {
         session_id: String,
         cwd: String,
         message: String,
+        /// tmux pane the hook ran in (absent for hooks outside tmux)
+        #[serde(default)]
+        tmux: Option<TmuxLocation>,
+        /// Transcript JSONL of the session, as reported by Claude
+        #[serde(default)]
+        transcript_path: Option<String>,
     },
 }
 
@@ -56,6 +110,42 @@ impl HookEvent {
         }
     }
 
+    /// Get the tmux location from any hook event
+    pub fn tmux_location(&self) -> Option<&TmuxLocation> {
+        match self {
+            HookEvent::Stop { tmux, .. } => tmux.as_ref(),
+            HookEvent::PreToolUse { tmux, .. } => tmux.as_ref(),
+            HookEvent::PostToolUse { tmux, .. } => tmux.as_ref(),
+            HookEvent::PermissionRequest { tmux, .. } => tmux.as_ref(),
+            HookEvent::UserPromptSubmit { tmux, .. } => tmux.as_ref(),
+            HookEvent::Notification { tmux, .. } => tmux.as_ref(),
+        }
+    }
+
+    /// Get the transcript path from any hook event
+    pub fn transcript_path(&self) -> Option<&str> {
+        match self {
+            HookEvent::Stop {
+                transcript_path, ..
+            } => transcript_path.as_deref(),
+            HookEvent::PreToolUse {
+                transcript_path, ..
+            } => transcript_path.as_deref(),
+            HookEvent::PostToolUse {
+                transcript_path, ..
+            } => transcript_path.as_deref(),
+            HookEvent::PermissionRequest {
+                transcript_path, ..
+            } => transcript_path.as_deref(),
+            HookEvent::UserPromptSubmit {
+                transcript_path, ..
+            } => transcript_path.as_deref(),
+            HookEvent::Notification {
+                transcript_path, ..
+            } => transcript_path.as_deref(),
+        }
+    }
+
     /// Get the cwd from any hook event
     pub fn cwd(&self) -> &str {
         match self {
@@ -84,8 +174,22 @@ pub enum DaemonCommand {
         /// true = approve always (option 2), false = approve once (option 1)
         always: bool,
     },
+    /// Deny a permission request for a session (dismisses the prompt with Escape)
+    DenyPermission { session_id: String },
+    /// Track a just-started Claude pane before its first hook fires
+    RegisterSession {
+        cwd: String,
+        tmux_session: String,
+        tmux: TmuxLocation,
+        /// Whether Claude was started with an initial prompt (and is already working)
+        prompted: bool,
+    },
     /// Send a hook event (from the hook script)
     HookEvent(HookEvent),
+    /// Full-text search over all indexed transcripts
+    SearchTranscripts { query: String, limit: usize },
+    /// Past conversations of the project in `cwd`, from the transcript index
+    ListTranscripts { cwd: String },
     /// Request daemon status
     Status,
     /// Graceful shutdown
@@ -116,12 +220,27 @@ pub struct SessionState {
     pub tmux_window: String,
     /// tmux pane index
     pub tmux_pane: String,
+    /// tmux server the session runs on
+    #[serde(default)]
+    pub tmux_server: TmuxServer,
+    /// tmux pane id (e.g. "%3"), stable across window/pane renumbering
+    #[serde(default)]
+    pub tmux_pane_id: String,
+    /// Remote host the session runs on (None for sessions of this daemon)
+    #[serde(default)]
+    pub host: Option<String>,
     /// Working directory
     pub cwd: String,
+    /// Transcript JSONL from the session's hooks (None until a hook reports it)
+    #[serde(default)]
+    pub transcript_path: Option<String>,
     /// Current Claude status
     pub status: SessionStatus,
     /// Whether this session needs user attention
     pub needs_attention: bool,
+    /// When the current attention request started (ISO 8601)
+    #[serde(default)]
+    pub attention_since: Option<String>,
     /// Source of the last input
     pub last_input_source: InputSource,
     /// Timestamp of last activity (ISO 8601)
@@ -130,6 +249,37 @@ pub struct SessionState {
     pub cpu_percent: f32,
     /// Memory in KB (from tmux process tree)
     pub memory_kb: u64,
+    /// Tool call awaiting permission (kept so edits can be previewed before approval;
+    /// boxed to keep `StateUpdate` small)
+    #[serde(default)]
+    pub pending_tool: Option<Box<PendingTool>>,
+    /// Risk classification of the pending tool call
+    #[serde(default)]
+    pub risk: Option<Risk>,
+    /// Claude's own task list, from its latest `TodoWrite` call (a boxed slice
+    /// to keep `StateUpdate` small)
+    #[serde(default)]
+    pub agent_todos: Box<[AgentTodo]>,
+    /// Token usage and cost of the session's transcript (None until indexed; boxed
+    /// to keep `StateUpdate` small)
+    #[serde(default)]
+    pub usage: Option<Box<SessionUsage>>,
+    /// Whether the over-budget notification was already sent
+    #[serde(default)]
+    pub budget_notified: bool,
+    /// Whether the context window warning was already sent (reset after compaction)
+    #[serde(default)]
+    pub context_notified: bool,
+}
+
+/// Tool name and input of an open permission request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PendingTool {
+    pub tool_name: String,
+    pub tool_input: serde_json::Value,
+    /// When the request arrived (RFC 3339); tells repeated identical calls apart
+    #[serde(default)]
+    pub requested_at: Option<String>,
 }
 
 /// Claude status as tracked by the daemon
@@ -150,6 +300,14 @@ pub enum SessionStatus {
     QuestionAsked,
     /// Working/processing
     Working,
+    /// Working, but no hook events or transcript writes for a while
+    Stalled,
+    /// Claude process exited without finishing its turn
+    Crashed,
+    /// Rate or usage limit hit; when it resets (ISO 8601), if known
+    RateLimited { resets_at: Option<String> },
+    /// API error (overloaded, server error, timeout)
+    ApiError { message: String },
     /// Unknown state
     Unknown,
 }
@@ -169,6 +327,53 @@ pub struct MetricsHistory {
     pub temp: Vec<f32>,
 }
 
+/// Which resource threshold a session crossed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum AlertKind {
+    /// A process stayed above the CPU threshold for the sustained period
+    Cpu { percent: f32, sustained_secs: u64 },
+    /// A process exceeded the resident memory threshold
+    Rss { kb: u64 },
+    /// The session has more processes than allowed
+    ProcessCount { count: usize },
+}
+
+/// An active resource alert for a tmux session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceAlert {
+    /// tmux session name
+    pub session: String,
+    /// tmux server the session lives on
+    #[serde(default)]
+    pub server: TmuxServer,
+    /// Offending process (highest CPU process for ProcessCount alerts)
+    pub pid: u32,
+    pub process_name: String,
+    pub kind: AlertKind,
+}
+
+impl ResourceAlert {
+    /// Short human-readable description for notifications and the detail view
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            AlertKind::Cpu {
+                percent,
+                sustained_secs,
+            } => format!(
+                "{} (PID {}) at {:.0}% CPU for {}s",
+                self.process_name, self.pid, percent, sustained_secs
+            ),
+            AlertKind::Rss { kb } => format!(
+                "{} (PID {}) using {}MB RSS",
+                self.process_name,
+                self.pid,
+                kb / 1024
+            ),
+            AlertKind::ProcessCount { count } => format!("{} processes running", count),
+        }
+    }
+}
+
 /// Response from daemon to TUI/CLI
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DaemonResponse {
@@ -179,21 +384,30 @@ pub enum DaemonResponse {
         /// System metrics history for sparkline display
         #[serde(default)]
         metrics: Option<MetricsHistory>,
+        /// Active resource alerts (one per tmux session)
+        #[serde(default)]
+        alerts: Vec<ResourceAlert>,
+        /// Live tmux topology (None if the daemon hasn't synced yet)
+        #[serde(default)]
+        tmux_sessions: Option<Vec<TmuxSession>>,
+        /// Git status by cwd, for cwds inside a repository
+        #[serde(default)]
+        git: HashMap<String, GitStatus>,
     },
     /// Real-time state update (sent to subscribers)
-    StateUpdate {
-        session: SessionState,
-    },
+    StateUpdate { session: SessionState },
     /// Session was removed (tmux session ended)
-    SessionRemoved {
-        session_id: String,
-    },
+    SessionRemoved { session_id: String },
     /// Operation completed successfully
     Ok,
     /// Error response
     Error { message: String },
     /// Pong response for health check
     Pong,
+    /// Sessions matching a transcript search, grouped by project
+    TranscriptHits { hits: Vec<TranscriptHit> },
+    /// Past conversations of a project, most recent first
+    Transcripts { transcripts: Vec<TranscriptSummary> },
     /// Daemon status info
     Status {
         running: bool,
@@ -219,6 +433,14 @@ pub fn get_state_file_path() -> std::path::PathBuf {
         .join("daemon-state.json")
 }
 
+/// Status snapshot written by the daemon for `status-line`
+pub fn get_status_snapshot_path() -> std::path::PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| std::path::PathBuf::from("/tmp"))
+        .join("tmux-claude")
+        .join("status.json")
+}
+
 /// PID file path for daemon
 pub fn get_pid_file_path() -> std::path::PathBuf {
     dirs::cache_dir()
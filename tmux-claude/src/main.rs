@@ -1,5 +1,6 @@
 //! tmux-claude: Interactive Claude Code session dashboard for tmux.
 
+mod cli;
 mod common;
 mod daemon;
 mod ipc;
@@ -12,16 +13,20 @@ use std::io::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
+use sysinfo::Signal;
 
 use crate::common::debug::{debug_log, init_debug};
+use crate::common::launch::LaunchRequest;
 use crate::common::persistence::{
     load_restorable_sessions, load_skipped_sessions, save_parked_sessions, sesh_connect,
 };
+use crate::common::process::RENICE_NICENESS;
 use crate::common::tmux::{
-    get_current_tmux_session, get_current_tmux_session_names, switch_to_session,
+    current_server, get_current_tmux_session, get_current_tmux_session_names, session_key,
+    switch_to_session, TmuxServer,
 };
 use crate::common::types::PERMISSION_KEYS;
-use crate::tui::app::{find_session_by_permission_key, App, InputMode, SearchResult};
+use crate::tui::app::{find_session_by_permission_key, App, InputMode, SearchResult, SearchScope};
 use crate::tui::ui::ui;
 
 #[derive(Parser, Debug)]
@@ -69,9 +74,104 @@ enum Command {
     /// Stop the running daemon (shortcut for `daemon stop`)
     Stop,
     /// Cycle to next tmux session (skipping skipped sessions)
-    CycleNext,
+    CycleNext {
+        /// Only visit sessions needing attention, oldest request first
+        #[arg(long)]
+        attention: bool,
+    },
     /// Cycle to previous tmux session (skipping skipped sessions)
-    CyclePrev,
+    CyclePrev {
+        /// Only visit sessions needing attention, oldest request first
+        #[arg(long)]
+        attention: bool,
+    },
+    /// Jump to the Claude pane of the oldest session needing attention
+    JumpAttention,
+    /// Block until a session reaches a status (exit code 124 on timeout)
+    Wait {
+        /// tmux session name (or daemon session id)
+        session: String,
+        /// Condition to wait for
+        #[arg(long, value_enum)]
+        until: cli::wait::WaitCondition,
+        /// Give up after this long (e.g. 90, 30s, 10m, 1h)
+        #[arg(long, value_parser = cli::wait::parse_duration)]
+        timeout: Option<Duration>,
+    },
+    /// List sessions (tmux sessions merged with daemon state)
+    List {
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Only sessions where Claude is waiting on you
+        #[arg(long)]
+        attention: bool,
+    },
+    /// Approve a session's pending permission prompt
+    Approve {
+        /// Session name (or daemon session id)
+        session: String,
+        /// Approve always (Bash prompts only)
+        #[arg(long)]
+        always: bool,
+    },
+    /// Deny a session's pending permission prompt
+    Deny {
+        /// Session name (or daemon session id)
+        session: String,
+    },
+    /// Switch the tmux client to a session
+    #[command(alias = "switch")]
+    Focus {
+        /// Session name (or daemon session id)
+        session: String,
+    },
+    /// Type a message into a session's Claude pane and submit it
+    Send {
+        /// Session name (or daemon session id)
+        session: String,
+        /// Text to send
+        message: String,
+    },
+    /// Start Claude in a new tmux session
+    New {
+        /// Directory or sesh project name
+        target: String,
+        /// tmux session name (default: directory name)
+        #[arg(long)]
+        name: Option<String>,
+        /// Initial prompt for Claude
+        #[arg(long)]
+        prompt: Option<String>,
+        /// Run in a git worktree for this branch (created under `worktree_root` if missing)
+        #[arg(long)]
+        worktree: Option<String>,
+    },
+    /// Print a Claude status summary for the tmux status bar
+    StatusLine {
+        /// Template with #{attention} #{permission} #{plan} #{question} #{waiting} #{working}
+        /// #{total}, or #{status} #{icon} with --session
+        #[arg(long)]
+        format: Option<String>,
+        /// Show one tmux session's status instead of the summary
+        #[arg(long)]
+        session: Option<String>,
+        /// Wrap values in tmux color codes
+        #[arg(long)]
+        color: bool,
+    },
+    /// Token usage and cost from Claude transcripts
+    Stats {
+        /// Group by project, session, model or day
+        #[arg(long, value_enum, default_value = "project")]
+        by: cli::stats::StatsGroup,
+        /// Only count usage since a date or age (e.g. 2026-03-01, 7d, 2w, 12h)
+        #[arg(long, value_parser = cli::stats::parse_since)]
+        since: Option<chrono::NaiveDate>,
+        /// Print JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -144,8 +244,16 @@ fn run_tui(
                 return Ok(());
             }
 
+            // Transcript search results arrive from a background thread
+            if app.poll_transcript_search() {
+                terminal.draw(|frame| ui(frame, &mut app))?;
+            }
+
             if poll(Duration::from_millis(sleep_ms))? {
-                if let Event::Key(KeyEvent { code, modifiers, .. }) = read()? {
+                if let Event::Key(KeyEvent {
+                    code, modifiers, ..
+                }) = read()?
+                {
                     debug_log(&format!(
                         "KEY: {:?} (mode={:?}, showing_parked={}, showing_detail={:?})",
                         code,
@@ -205,9 +313,7 @@ fn run_tui(
                                 app.cancel_park_input();
                                 needs_redraw = true;
                             }
-                            KeyCode::Enter
-                                if modifiers.contains(KeyModifiers::ALT) =>
-                            {
+                            KeyCode::Enter if modifiers.contains(KeyModifiers::ALT) => {
                                 app.input_buffer.push('\n');
                                 needs_redraw = true;
                             }
@@ -233,9 +339,7 @@ fn run_tui(
                                 app.cancel_add_todo();
                                 needs_redraw = true;
                             }
-                            KeyCode::Enter
-                                if modifiers.contains(KeyModifiers::ALT) =>
-                            {
+                            KeyCode::Enter if modifiers.contains(KeyModifiers::ALT) => {
                                 app.input_buffer.push('\n');
                                 needs_redraw = true;
                             }
@@ -253,6 +357,32 @@ fn run_tui(
                             }
                             _ => {}
                         }
+                    } else if app.input_mode == InputMode::NewSession {
+                        // Handle "<dir|project> [prompt]" input
+                        match code {
+                            KeyCode::Esc => {
+                                app.cancel_new_session();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Enter if modifiers.contains(KeyModifiers::ALT) => {
+                                app.input_buffer.push('\n');
+                                needs_redraw = true;
+                            }
+                            KeyCode::Enter => {
+                                app.complete_new_session();
+                                should_refresh = true;
+                                break;
+                            }
+                            KeyCode::Backspace => {
+                                app.input_buffer.pop();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Char(c) => {
+                                app.input_buffer.push(c);
+                                needs_redraw = true;
+                            }
+                            _ => {}
+                        }
                     } else if app.input_mode == InputMode::Search {
                         // Handle search input
                         match code {
@@ -283,6 +413,14 @@ fn run_tui(
                                             app.search_results.clear();
                                             needs_redraw = true;
                                         }
+                                        SearchResult::Transcript(hit_idx) => {
+                                            // Read the matching conversation
+                                            app.open_transcript_hit(hit_idx);
+                                            app.input_mode = InputMode::Normal;
+                                            app.search_query.clear();
+                                            app.search_results.clear();
+                                            needs_redraw = true;
+                                        }
                                         SearchResult::SeshProject(name) => {
                                             // Connect to sesh project and exit
                                             app.input_mode = InputMode::Normal;
@@ -316,6 +454,11 @@ fn run_tui(
                                 app.update_search_results();
                                 needs_redraw = true;
                             }
+                            KeyCode::Tab => {
+                                // Switch between session names and transcript contents
+                                app.toggle_search_scope();
+                                needs_redraw = true;
+                            }
                             KeyCode::Up => {
                                 if app.selected > 0 {
                                     app.selected -= 1;
@@ -335,6 +478,171 @@ fn run_tui(
                             }
                             _ => {}
                         }
+                    } else if app.input_mode == InputMode::ProcessAction {
+                        // Handle process action modal
+                        match code {
+                            KeyCode::Char('i') | KeyCode::Char('I') => {
+                                app.request_signal(Signal::Interrupt);
+                            }
+                            KeyCode::Char('t') | KeyCode::Char('T') => {
+                                app.request_signal(Signal::Term);
+                            }
+                            KeyCode::Char('k') | KeyCode::Char('K') => {
+                                app.request_signal(Signal::Kill);
+                            }
+                            KeyCode::Char('n') | KeyCode::Char('N') => {
+                                app.renice_target(RENICE_NICENESS);
+                            }
+                            KeyCode::Char('c') => app.copy_target(false),
+                            KeyCode::Char('C') => app.copy_target(true),
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                                app.cancel_process_action();
+                            }
+                            _ => {}
+                        }
+                        needs_redraw = true;
+                    } else if app.input_mode == InputMode::ConfirmKill {
+                        // Handle kill confirmation
+                        match code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                app.complete_kill_session();
+                                should_refresh = true;
+                                break;
+                            }
+                            _ => {
+                                app.cancel_kill_session();
+                                needs_redraw = true;
+                            }
+                        }
+                    } else if app.input_mode == InputMode::ConfirmRemoveWorktree {
+                        // Handle worktree removal offer after park/kill
+                        match code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                app.confirm_worktree_removal();
+                            }
+                            _ => app.cancel_worktree_removal(),
+                        }
+                        needs_redraw = true;
+                    } else if app.input_mode == InputMode::ConfirmBulkApprove {
+                        // Handle bulk approval confirmation
+                        match code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                app.confirm_bulk_approve();
+                                app.hide_selection();
+                                should_refresh = true;
+                                break;
+                            }
+                            _ => {
+                                app.cancel_bulk_approve();
+                                needs_redraw = true;
+                            }
+                        }
+                    } else if app.input_mode == InputMode::ConfirmSignal {
+                        // Handle signal confirmation
+                        match code {
+                            KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                                app.confirm_pending_signal();
+                                should_refresh = true;
+                                break;
+                            }
+                            _ => {
+                                app.cancel_process_action();
+                                needs_redraw = true;
+                            }
+                        }
+                    } else if app.input_mode == InputMode::TranscriptSearch {
+                        // Handle transcript search input
+                        match code {
+                            KeyCode::Esc => {
+                                app.cancel_transcript_search();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Enter => {
+                                app.complete_transcript_search();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Backspace => {
+                                app.input_buffer.pop();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Char(c) => {
+                                app.input_buffer.push(c);
+                                needs_redraw = true;
+                            }
+                            _ => {}
+                        }
+                    } else if let Some(view) = app.transcript.as_mut() {
+                        // Handle transcript view input
+                        match code {
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                                app.close_transcript();
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => view.move_by(-1),
+                            KeyCode::Down | KeyCode::Char('j') => view.move_by(1),
+                            KeyCode::PageUp | KeyCode::Char('K') => view.move_by(-10),
+                            KeyCode::PageDown | KeyCode::Char('J') => view.move_by(10),
+                            KeyCode::Home | KeyCode::Char('g') => view.selected = 0,
+                            KeyCode::End => view.jump_to_end(),
+                            KeyCode::Char('G') => {
+                                // Pick up new turns, then jump to the latest one
+                                app.reload_transcript();
+                                if let Some(view) = app.transcript.as_mut() {
+                                    view.jump_to_latest_turn();
+                                }
+                            }
+                            KeyCode::Char(' ') | KeyCode::Enter => view.toggle_expanded(),
+                            KeyCode::Char('/') => app.start_transcript_search(),
+                            KeyCode::Char('n') => view.next_match(true),
+                            KeyCode::Char('N') => view.next_match(false),
+                            KeyCode::Char('r') | KeyCode::Char('R') => app.reload_transcript(),
+                            KeyCode::Char('c') | KeyCode::Char('C') => {
+                                // Continue the conversation in a new window
+                                let resumed = app.resume_transcript().is_some();
+                                if resumed && app.popup_mode {
+                                    app.save_restorable();
+                                    return Ok(());
+                                }
+                                if resumed {
+                                    app.close_transcript();
+                                    app.close_detail();
+                                    should_refresh = true;
+                                    break;
+                                }
+                            }
+                            _ => {}
+                        }
+                        needs_redraw = true;
+                    } else if app.history.is_some() {
+                        // Handle history view input
+                        match code {
+                            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+                                app.history = None;
+                            }
+                            KeyCode::Up | KeyCode::Char('k') => app.move_history_selection(-1),
+                            KeyCode::Down | KeyCode::Char('j') => app.move_history_selection(1),
+                            KeyCode::PageUp | KeyCode::Char('K') => app.move_history_selection(-10),
+                            KeyCode::PageDown | KeyCode::Char('J') => {
+                                app.move_history_selection(10)
+                            }
+                            KeyCode::Char('t') | KeyCode::Char('T') => {
+                                app.open_history_transcript()
+                            }
+                            KeyCode::Enter => {
+                                // Resume the conversation in a new window
+                                let resumed = app.resume_history_selected().is_some();
+                                if resumed && app.popup_mode {
+                                    app.save_restorable();
+                                    return Ok(());
+                                }
+                                if resumed {
+                                    app.close_detail();
+                                    should_refresh = true;
+                                    break;
+                                }
+                            }
+                            _ => {}
+                        }
+                        needs_redraw = true;
                     } else if app.showing_detail.is_some() {
                         // Handle detail view input
                         match code {
@@ -363,12 +671,8 @@ fn run_tui(
                                 needs_redraw = true;
                             }
                             KeyCode::Down | KeyCode::Char('j') => {
-                                let todo_count = app.detail_todos().len();
-                                let port_count = app.showing_detail
-                                    .and_then(|idx| app.session_infos.get(idx))
-                                    .map(|s| s.listening_ports.len())
-                                    .unwrap_or(0);
-                                let total = todo_count + port_count;
+                                let total =
+                                    app.detail_process_offset() + app.detail_process_rows().len();
                                 if total > 0 && app.detail_selected < total - 1 {
                                     app.detail_selected += 1;
                                 } else {
@@ -377,27 +681,73 @@ fn run_tui(
                                 }
                                 needs_redraw = true;
                             }
+                            KeyCode::PageDown | KeyCode::Char('J') => {
+                                app.diff_scroll += 5;
+                                needs_redraw = true;
+                            }
+                            KeyCode::PageUp | KeyCode::Char('K') => {
+                                app.diff_scroll = app.diff_scroll.saturating_sub(5);
+                                needs_redraw = true;
+                            }
+                            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                                // Approve the pending request (uppercase = always, Bash only)
+                                if let Some(idx) = app.showing_detail {
+                                    let always = code == KeyCode::Char('Y');
+                                    if app.awaits_approval(idx)
+                                        && app.approve_session_at(idx, always)
+                                    {
+                                        app.diff_scroll = 0;
+                                        should_refresh = true;
+                                        break;
+                                    }
+                                    needs_redraw = true;
+                                }
+                            }
+                            KeyCode::Char(' ') => {
+                                app.toggle_selected_process_collapsed();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Left | KeyCode::Char('h') => {
+                                app.set_selected_process_collapsed(true);
+                                needs_redraw = true;
+                            }
+                            KeyCode::Right | KeyCode::Char('l') => {
+                                app.set_selected_process_collapsed(false);
+                                needs_redraw = true;
+                            }
+                            KeyCode::Enter if app.selected_process().is_some() => {
+                                // Process selected — open action modal
+                                app.open_process_action();
+                                needs_redraw = true;
+                            }
                             KeyCode::Enter => {
                                 let todo_count = app.detail_todos().len();
-                                let port_count = app.showing_detail
+                                let port_count = app
+                                    .showing_detail
                                     .and_then(|idx| app.session_infos.get(idx))
                                     .map(|s| s.listening_ports.len())
                                     .unwrap_or(0);
                                 if app.detail_selected >= todo_count && port_count > 0 {
                                     // Port selected — focus existing tab or open new one
                                     let port_idx = app.detail_selected - todo_count;
-                                    if let Some(session) = app.showing_detail
+                                    if let Some(session) = app
+                                        .showing_detail
                                         .and_then(|idx| app.session_infos.get(idx))
                                     {
-                                        if let Some(port_info) = session.listening_ports.get(port_idx) {
+                                        if let Some(port_info) =
+                                            session.listening_ports.get(port_idx)
+                                        {
                                             // Try to focus an existing matched Chrome tab
-                                            let matched_tab = app.detail_chrome_tabs.iter()
+                                            let matched_tab = app
+                                                .detail_chrome_tabs
+                                                .iter()
                                                 .find(|(_, p)| *p == port_info.port);
                                             if let Some((tab, _)) = matched_tab {
                                                 crate::common::chrome::focus_chrome_tab(tab);
                                             } else {
                                                 // No existing tab — open new one
-                                                let url = format!("http://localhost:{}", port_info.port);
+                                                let url =
+                                                    format!("http://localhost:{}", port_info.port);
                                                 crate::common::chrome::open_chrome_tab(&url);
                                             }
                                         }
@@ -405,13 +755,14 @@ fn run_tui(
                                     needs_redraw = true;
                                 } else {
                                     // Todo selected or no selectable items — switch to session
-                                    if let Some(name) = app.detail_session_name() {
-                                        switch_to_session(&name);
-                                        if app.popup_mode {
-                                            app.save_restorable();
-                                            return Ok(());
+                                    if let Some(idx) = app.showing_detail {
+                                        if app.switch_to_session_at(idx) {
+                                            if app.popup_mode {
+                                                app.save_restorable();
+                                                return Ok(());
+                                            }
+                                            app.close_detail();
                                         }
-                                        app.close_detail();
                                         needs_redraw = true;
                                     }
                                 }
@@ -423,6 +774,13 @@ fn run_tui(
                                     needs_redraw = true;
                                 }
                             }
+                            KeyCode::Char('x') | KeyCode::Char('X') => {
+                                // Kill this tmux session (after confirmation)
+                                if let Some(idx) = app.showing_detail {
+                                    app.start_kill_session(idx);
+                                    needs_redraw = true;
+                                }
+                            }
                             KeyCode::Char('!') => {
                                 // Toggle auto-approve for this session
                                 if let Some(idx) = app.showing_detail {
@@ -444,6 +802,16 @@ fn run_tui(
                                     needs_redraw = true;
                                 }
                             }
+                            KeyCode::Char('t') | KeyCode::Char('T') => {
+                                // Read the conversation without attaching to the pane
+                                app.open_transcript();
+                                needs_redraw = true;
+                            }
+                            KeyCode::Char('r') | KeyCode::Char('R') => {
+                                // Past conversations of this project, to resume one
+                                app.open_history();
+                                needs_redraw = true;
+                            }
                             _ => {}
                         }
                     } else if app.showing_parked_detail.is_some() {
@@ -460,7 +828,7 @@ fn run_tui(
                             KeyCode::Enter => {
                                 // Unpark this session
                                 if let Some(name) = app.showing_parked_detail.take() {
-                                    if sesh_connect(&name) {
+                                    if app.reopen_parked(&name) {
                                         app.parked_sessions.remove(&name);
                                         save_parked_sessions(&app.parked_sessions);
                                         should_refresh = true;
@@ -521,11 +889,32 @@ fn run_tui(
                                 app.save_restorable();
                                 return Ok(());
                             }
+                            // Space: mark the selected session for bulk approval
+                            KeyCode::Char(' ') => {
+                                app.toggle_mark_selected();
+                                needs_redraw = true;
+                            }
+                            // A: approve the requests of all marked sessions
+                            KeyCode::Char('a') | KeyCode::Char('A') => {
+                                app.start_approve_marked();
+                                needs_redraw = true;
+                            }
+                            // O: approve every open Read/Grep/Glob request
+                            KeyCode::Char('o') | KeyCode::Char('O') => {
+                                app.start_approve_read_only();
+                                needs_redraw = true;
+                            }
+                            // N: start Claude in a new tmux session
+                            KeyCode::Char('n') | KeyCode::Char('N') => {
+                                app.start_new_session();
+                                needs_redraw = true;
+                            }
                             // / : enter search mode
                             KeyCode::Char('/') => {
                                 app.input_mode = InputMode::Search;
                                 app.search_query.clear();
                                 app.search_scroll_offset = 0;
+                                app.search_scope = SearchScope::Sessions;
                                 app.load_sesh_projects(); // Load sesh projects on search mode entry
                                 app.update_search_results();
                                 app.selected = 0;
@@ -534,13 +923,14 @@ fn run_tui(
                             // Number keys (1-9): switch to session
                             KeyCode::Char(c) if c.is_ascii_digit() && c != '0' => {
                                 let idx = c.to_digit(10).unwrap() as usize - 1;
-                                if let Some(session_info) = app.session_infos.get(idx) {
-                                    switch_to_session(&session_info.name);
-                                    if app.popup_mode {
-                                        app.save_restorable();
-                                        return Ok(());
+                                if idx < app.session_infos.len() {
+                                    if app.switch_to_session_at(idx) {
+                                        if app.popup_mode {
+                                            app.save_restorable();
+                                            return Ok(());
+                                        }
+                                        app.hide_selection();
                                     }
-                                    app.hide_selection();
                                     needs_redraw = true;
                                 }
                             }
@@ -548,36 +938,16 @@ fn run_tui(
                             KeyCode::Char(c)
                                 if PERMISSION_KEYS.contains(&c.to_ascii_lowercase()) =>
                             {
-                                let is_uppercase = c.is_ascii_uppercase();
-                                if let Some(session_info) =
+                                // Uppercase = approve always (option 2) - only for Bash
+                                if let Some(idx) =
                                     find_session_by_permission_key(&app.session_infos, c)
                                 {
-                                    if let Some((ref sess, ref win, ref pane)) =
-                                        session_info.claude_pane
-                                    {
-                                        use crate::common::tmux::send_key_to_pane;
-                                        use crate::common::types::ClaudeStatus;
-                                        // Only NeedsPermission (Bash) has "approve always" option
-                                        // EditApproval only has Yes/No, so uppercase should also send "1"
-                                        let has_approve_always = matches!(
-                                            session_info.claude_status,
-                                            Some(ClaudeStatus::NeedsPermission(_, _))
-                                        );
-                                        if is_uppercase && has_approve_always {
-                                            // Uppercase = approve always (option 2) - only for Bash
-                                            send_key_to_pane(sess, win, pane, "2");
-                                            send_key_to_pane(sess, win, pane, "Enter");
-                                        } else {
-                                            // Lowercase or no approve-always = approve once (option 1)
-                                            send_key_to_pane(sess, win, pane, "1");
-                                            send_key_to_pane(sess, win, pane, "Enter");
-                                        }
-                                        // Mark as pending so key disappears immediately
-                                        app.pending_approvals.insert(session_info.name.clone());
+                                    if app.approve_session_at(idx, c.is_ascii_uppercase()) {
                                         app.hide_selection();
                                         should_refresh = true;
                                         break;
                                     }
+                                    needs_redraw = true;
                                 }
                             }
                             _ => {}
@@ -603,12 +973,16 @@ const LAUNCHD_LABEL: &str = "com.tmux-claude.daemon";
 
 /// Get the launchd plist path
 fn get_launchd_plist_path() -> Option<std::path::PathBuf> {
-    dirs::home_dir().map(|h| h.join("Library/LaunchAgents").join(format!("{}.plist", LAUNCHD_LABEL)))
+    dirs::home_dir().map(|h| {
+        h.join("Library/LaunchAgents")
+            .join(format!("{}.plist", LAUNCHD_LABEL))
+    })
 }
 
 /// Load (start) daemon via launchctl
 fn launchctl_load() -> Result<()> {
-    let plist = get_launchd_plist_path().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+    let plist =
+        get_launchd_plist_path().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
 
     let output = std::process::Command::new("launchctl")
         .args(["load", plist.to_str().unwrap()])
@@ -626,7 +1000,8 @@ fn launchctl_load() -> Result<()> {
 
 /// Unload (stop) daemon via launchctl
 fn launchctl_unload() -> Result<()> {
-    let plist = get_launchd_plist_path().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
+    let plist =
+        get_launchd_plist_path().ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?;
 
     let output = std::process::Command::new("launchctl")
         .args(["unload", plist.to_str().unwrap()])
@@ -664,7 +1039,10 @@ fn run_daemon() -> Result<()> {
     }
 
     // If launchd plist exists, use launchctl load
-    if get_launchd_plist_path().map(|p| p.exists()).unwrap_or(false) {
+    if get_launchd_plist_path()
+        .map(|p| p.exists())
+        .unwrap_or(false)
+    {
         println!("Starting daemon via launchctl...");
         launchctl_load()?;
         std::thread::sleep(std::time::Duration::from_millis(500));
@@ -715,7 +1093,9 @@ fn run_stop() -> Result<()> {
     }
 
     // If launchd plist exists, unload it first to prevent auto-restart
-    let has_launchd = get_launchd_plist_path().map(|p| p.exists()).unwrap_or(false);
+    let has_launchd = get_launchd_plist_path()
+        .map(|p| p.exists())
+        .unwrap_or(false);
     if has_launchd {
         println!("Unloading launchd service...");
         launchctl_unload()?;
@@ -726,9 +1106,7 @@ fn run_stop() -> Result<()> {
     if is_daemon_running() {
         println!("Stopping daemon...");
         let runtime = tokio::runtime::Runtime::new()?;
-        let _ = runtime.block_on(async {
-            crate::daemon::server::stop_daemon().await
-        });
+        let _ = runtime.block_on(async { crate::daemon::server::stop_daemon().await });
         std::thread::sleep(std::time::Duration::from_millis(500));
     }
 
@@ -744,7 +1122,9 @@ fn run_stop() -> Result<()> {
 fn run_restart() -> Result<()> {
     use crate::daemon::server::is_daemon_running;
 
-    let has_launchd = get_launchd_plist_path().map(|p| p.exists()).unwrap_or(false);
+    let has_launchd = get_launchd_plist_path()
+        .map(|p| p.exists())
+        .unwrap_or(false);
 
     // Stop the running daemon
     if is_daemon_running() {
@@ -759,9 +1139,7 @@ fn run_restart() -> Result<()> {
         if is_daemon_running() {
             println!("Stopping daemon...");
             let runtime = tokio::runtime::Runtime::new()?;
-            let _ = runtime.block_on(async {
-                crate::daemon::server::stop_daemon().await
-            });
+            let _ = runtime.block_on(async { crate::daemon::server::stop_daemon().await });
             std::thread::sleep(std::time::Duration::from_millis(500));
         }
     }
@@ -794,7 +1172,13 @@ fn run_setup() -> Result<()> {
     // Find the binary path
     let binary_path = std::env::current_exe()
         .ok()
-        .or_else(|| home.join(".local").join("bin").join("tmux-claude").exists().then(|| home.join(".local").join("bin").join("tmux-claude")))
+        .or_else(|| {
+            home.join(".local")
+                .join("bin")
+                .join("tmux-claude")
+                .exists()
+                .then(|| home.join(".local").join("bin").join("tmux-claude"))
+        })
         .unwrap_or_else(|| home.join(".local").join("bin").join("tmux-claude"));
 
     // Get the hook script path
@@ -867,6 +1251,13 @@ fn run_setup() -> Result<()> {
         "command": "{} PreToolUse"
       }}]
     }}],
+    "PermissionRequest": [{{
+      "matcher": "*",
+      "hooks": [{{
+        "type": "command",
+        "command": "{} PermissionRequest"
+      }}]
+    }}],
     "PostToolUse": [{{
       "matcher": "*",
       "hooks": [{{
@@ -883,7 +1274,7 @@ fn run_setup() -> Result<()> {
   }}
 }}
 "#,
-        hook_path, hook_path, hook_path, hook_path
+        hook_path, hook_path, hook_path, hook_path, hook_path
     );
 
     Ok(())
@@ -951,11 +1342,12 @@ fn install_system_service(home: &std::path::Path, binary_path: &std::path::Path)
 fn run_cycle(forward: bool) -> Result<()> {
     let skipped = load_skipped_sessions();
     let all_sessions = get_current_tmux_session_names();
+    let server = current_server();
 
     // Filter out skipped sessions
     let filtered: Vec<&String> = all_sessions
         .iter()
-        .filter(|name| !skipped.contains(*name))
+        .filter(|name| !skipped.contains(&session_key(&server, name)))
         .collect();
 
     if filtered.is_empty() {
@@ -986,7 +1378,7 @@ fn run_cycle(forward: bool) -> Result<()> {
         }
     };
 
-    switch_to_session(target);
+    switch_to_session(&TmuxServer::Current, target);
     Ok(())
 }
 
@@ -1005,8 +1397,44 @@ fn main() -> Result<()> {
         Some(Command::Status) => run_status(),
         Some(Command::Stop) => run_stop(),
         Some(Command::Setup) => run_setup(),
-        Some(Command::CycleNext) => run_cycle(true),
-        Some(Command::CyclePrev) => run_cycle(false),
+        Some(Command::CycleNext { attention: false }) => run_cycle(true),
+        Some(Command::CyclePrev { attention: false }) => run_cycle(false),
+        Some(Command::CycleNext { attention: true }) => cli::attention::run_attention_cycle(true),
+        Some(Command::CyclePrev { attention: true }) => cli::attention::run_attention_cycle(false),
+        Some(Command::JumpAttention) => cli::attention::run_jump_attention(),
+        Some(Command::Wait {
+            session,
+            until,
+            timeout,
+        }) => cli::wait::run_wait(&session, until, timeout),
+        Some(Command::List { json, attention }) => {
+            cli::sessions::run_list(args.filter, json, attention)
+        }
+        Some(Command::Approve { session, always }) => {
+            cli::sessions::run_approve(args.filter, &session, always)
+        }
+        Some(Command::Deny { session }) => cli::sessions::run_deny(args.filter, &session),
+        Some(Command::Focus { session }) => cli::sessions::run_focus(args.filter, &session),
+        Some(Command::Send { session, message }) => {
+            cli::sessions::run_send(args.filter, &session, &message)
+        }
+        Some(Command::New {
+            target,
+            name,
+            prompt,
+            worktree,
+        }) => cli::new::run_new(LaunchRequest {
+            target,
+            name,
+            prompt,
+            worktree,
+        }),
+        Some(Command::StatusLine {
+            format,
+            session,
+            color,
+        }) => cli::status_line::run_status_line(format, session, color),
+        Some(Command::Stats { by, since, json }) => cli::stats::run_stats(by, since, json),
         Some(Command::Tui) | None => {
             // Check for sessions to restore BEFORE starting TUI (skip in popup mode)
             if !args.popup {
@@ -1043,7 +1471,7 @@ fn main() -> Result<()> {
 
                             // Switch back to original session
                             if let Some(ref original) = original_session {
-                                switch_to_session(original);
+                                switch_to_session(&TmuxServer::Current, original);
                             }
 
                             // Brief pause to let sessions stabilize
@@ -1,42 +1,265 @@
 //! TUI application state and logic.
 
 use crate::common::debug::debug_log;
+use crate::common::diff::{edit_preview, EditPreview};
+use crate::common::git::GitStatus;
+use crate::common::jsonl_tail::{read_appended, TailCursor};
+use crate::common::launch::{launch_session, resume_session, LaunchRequest};
 use crate::common::persistence::{
     has_sesh_config, is_globally_muted, list_sesh_projects, load_auto_approve_sessions,
     load_muted_sessions, load_parked_sessions, load_session_todos, load_skipped_sessions,
-    save_auto_approve_sessions, save_muted_sessions, save_parked_sessions,
-    save_restorable_sessions, save_session_todos, save_skipped_sessions, set_global_mute,
-    sesh_connect,
+    load_worktrees, save_auto_approve_sessions, save_muted_sessions, save_parked_sessions,
+    save_restorable_sessions, save_session_todos, save_skipped_sessions, save_worktrees,
+    sesh_connect, set_global_mute, worktrees_modified,
 };
 use crate::common::ports::get_listening_ports_for_pids;
-use crate::common::process::{get_all_descendants, get_process_info, is_claude_process};
-use crate::common::tmux::{get_tmux_sessions, kill_tmux_session};
+use crate::common::process::{
+    flatten_process_tree, get_process_info, get_session_pids, pane_runs_claude, renice_process,
+    retain_with_ancestors, send_signal, signal_name, ProcessTreeRow,
+};
+use crate::common::tmux::{
+    copy_to_tmux_buffer, discover_tmux_servers, focus_pane, get_tmux_sessions, kill_tmux_session,
+    send_key_to_pane, send_text_to_pane, switch_to_session, TmuxServer,
+};
+use crate::common::transcript::{
+    find_transcript, list_transcripts, parse_transcript_entry, TranscriptItem, TranscriptKind,
+    TranscriptSummary,
+};
+use crate::common::transcript_index::{TranscriptHit, TranscriptIndex};
 use crate::common::types::{
-    lines_for_session, matches_filter, ClaudeStatus, ProcessInfo, SessionInfo, PERMISSION_KEYS,
+    lines_for_session, matches_filter, ClaudeStatus, ProcessInfo, SessionInfo, TmuxSession,
+    PERMISSION_KEYS, READ_ONLY_TOOLS,
+};
+use crate::common::worktree::{
+    removal_blocker, remove_worktree, reopen_worktree_session, SessionWorktree,
+};
+use crate::ipc::messages::{
+    MetricsHistory, PendingTool, ResourceAlert, SessionState, SessionStatus,
 };
-use crate::ipc::messages::{MetricsHistory, SessionStatus};
 use crate::tui::client::DaemonClient;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use chrono::{DateTime, Utc};
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, HashSet};
-use std::time::{Duration, Instant};
-use sysinfo::System;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime};
+use sysinfo::{Signal, System};
 
 /// Text input mode for the TUI
 #[derive(Debug, PartialEq)]
 pub enum InputMode {
     Normal,
-    ParkNote, // Entering note for parking
-    AddTodo,  // Adding a todo in detail view
-    Search,   // Interactive session search
+    ParkNote,              // Entering note for parking
+    AddTodo,               // Adding a todo in detail view
+    Search,                // Interactive session search
+    ProcessAction,         // Choosing an action for the selected process
+    ConfirmSignal,         // Confirming a destructive signal
+    ConfirmKill,           // Confirming killing a tmux session
+    ConfirmRemoveWorktree, // Offering to remove a killed session's worktree
+    NewSession,            // Entering "<dir|project> [prompt]" for a new Claude session
+    ConfirmBulkApprove,    // Confirming approval of several sessions' requests
+    TranscriptSearch,      // Entering a search query in the transcript view
 }
 
 /// Search result item - active session, parked one, or inactive sesh project
 #[derive(Clone)]
 pub enum SearchResult {
-    Active(usize),      // Index into session_infos
-    Parked(String),     // Session name from parked_sessions
+    Active(usize),       // Index into session_infos
+    Parked(String),      // Session name from parked_sessions
     SeshProject(String), // Sesh project name (not active, not parked)
+    Transcript(usize),   // Index into transcript_hits
+}
+
+/// What `/` searches
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SearchScope {
+    Sessions,    // Session, parked and sesh project names
+    Transcripts, // Contents of every Claude transcript
+}
+
+/// How often tmux servers are rediscovered when polling tmux without a daemon
+const SERVER_DISCOVERY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Most sessions listed for a transcript search
+const TRANSCRIPT_HIT_LIMIT: usize = 50;
+
+/// Typing pause before a transcript search runs
+const TRANSCRIPT_SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Transcript search run on a background thread, so typing never waits on it
+#[derive(Default)]
+pub struct TranscriptSearch {
+    pending: Option<(String, Instant)>, // query to search, and when (after the debounce)
+    running: Option<Receiver<(String, Vec<TranscriptHit>)>>,
+}
+
+/// Diff of the detail-view session's pending edit, with what it was computed from
+pub struct EditPreviewCache {
+    key: (String, u64, Option<SystemTime>), // session key, tool call hash, file mtime
+    pub preview: Option<EditPreview>,
+}
+
+/// Scrollable conversation of a Claude session, opened from the detail view
+pub struct TranscriptView {
+    pub title: String, // session or project name for the header
+    pub cwd: String,   // project directory (where to resume it)
+    pub path: PathBuf,
+    pub items: Vec<TranscriptItem>,
+    pub selected: usize,          // selected item index
+    pub scroll: usize,            // first visible line (clamped by the renderer)
+    pub expanded: HashSet<usize>, // thinking blocks, long tool results and tool inputs shown in full
+    pub query: String,            // last search query
+    pub matches: Vec<usize>,      // item indices matching the query
+    cursor: TailCursor,           // where reading the file stopped
+    turn: usize,                  // turns read so far
+}
+
+impl TranscriptView {
+    /// Load a transcript and select its latest turn
+    pub fn load(path: PathBuf, title: String, cwd: String) -> Result<Self> {
+        let mut view = Self {
+            title,
+            cwd,
+            path,
+            items: Vec::new(),
+            selected: 0,
+            scroll: 0,
+            expanded: HashSet::new(),
+            query: String::new(),
+            matches: Vec::new(),
+            cursor: TailCursor::default(),
+            turn: 0,
+        };
+        view.read_appended()?;
+        view.jump_to_latest_turn();
+        Ok(view)
+    }
+
+    /// Parse what was appended to the file since the last read (all of it again
+    /// when it was replaced or truncated)
+    fn read_appended(&mut self) -> Result<()> {
+        let update = read_appended(&self.path, self.cursor)?;
+        if update.reset {
+            self.items.clear();
+            self.turn = 0;
+        }
+        for entry in &update.entries {
+            self.items
+                .extend(parse_transcript_entry(entry, &mut self.turn));
+        }
+        self.cursor = update.cursor;
+        Ok(())
+    }
+
+    /// Read new turns, keeping the selection and search
+    pub fn reload(&mut self) -> Result<()> {
+        let at_end = self.selected + 1 >= self.items.len();
+        self.read_appended()?;
+        if at_end {
+            self.selected = self.items.len().saturating_sub(1);
+        } else {
+            self.selected = self.selected.min(self.items.len().saturating_sub(1));
+        }
+        self.expanded.retain(|&i| i < self.items.len());
+        self.run_search();
+        Ok(())
+    }
+
+    /// Move the selection by `delta` items
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.items.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Select the last user prompt
+    pub fn jump_to_latest_turn(&mut self) {
+        self.selected = self
+            .items
+            .iter()
+            .rposition(|item| item.kind == TranscriptKind::UserPrompt)
+            .unwrap_or(0);
+    }
+
+    /// Select the last item
+    pub fn jump_to_end(&mut self) {
+        self.selected = self.items.len().saturating_sub(1);
+    }
+
+    /// Expand or collapse the selected item
+    pub fn toggle_expanded(&mut self) {
+        if !self.expanded.remove(&self.selected) {
+            self.expanded.insert(self.selected);
+        }
+    }
+
+    /// Recompute matches for the current query (case-insensitive)
+    fn run_search(&mut self) {
+        let query = self.query.to_lowercase();
+        self.matches = if query.is_empty() {
+            Vec::new()
+        } else {
+            self.items
+                .iter()
+                .enumerate()
+                .filter(|(_, item)| {
+                    let tool = match &item.kind {
+                        TranscriptKind::ToolCall(name) => name.to_lowercase(),
+                        _ => String::new(),
+                    };
+                    tool.contains(&query) || item.text.to_lowercase().contains(&query)
+                })
+                .map(|(i, _)| i)
+                .collect()
+        };
+    }
+
+    /// Search for `query` and select the first match at or after the selection
+    pub fn search(&mut self, query: &str) -> bool {
+        self.query = query.to_string();
+        self.run_search();
+        if let Some(&first) = self
+            .matches
+            .iter()
+            .find(|&&i| i >= self.selected)
+            .or(self.matches.first())
+        {
+            self.selected = first;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Select the next (or previous) match, wrapping around
+    pub fn next_match(&mut self, forward: bool) {
+        let next = if forward {
+            self.matches
+                .iter()
+                .find(|&&i| i > self.selected)
+                .or(self.matches.first())
+        } else {
+            self.matches
+                .iter()
+                .rev()
+                .find(|&&i| i < self.selected)
+                .or(self.matches.last())
+        };
+        if let Some(&i) = next {
+            self.selected = i;
+        }
+    }
+}
+
+/// Past conversations of the detail view's project
+pub struct HistoryView {
+    pub title: String, // session name for the header
+    pub cwd: String,
+    pub entries: Vec<TranscriptSummary>,
+    pub selected: usize,
+    pub scroll: usize, // first visible row (clamped by the renderer)
 }
 
 /// TUI application state
@@ -59,15 +282,27 @@ pub struct App {
     pub input_mode: InputMode,
     pub input_buffer: String,
     pub pending_park_session: Option<usize>, // session index to park after note entry
+    pub pending_kill_session: Option<usize>, // session index awaiting kill confirmation
+    // Per-session git worktrees (created by `new --worktree`)
+    pub worktrees: HashMap<String, SessionWorktree>,
+    pub worktrees_modified: Option<SystemTime>, // worktrees file mtime when last loaded
+    pub pending_worktree_removal: Option<String>, // killed session whose worktree may go
     // Session todos
-    pub session_todos: HashMap<String, Vec<String>>, // name -> list of todos
+    pub session_todos: HashMap<String, Vec<String>>, // session key -> list of todos
     // Detail view
     pub showing_detail: Option<usize>, // session index being viewed
     pub detail_selected: usize,        // selected todo index in detail view
     pub detail_scroll_offset: usize,   // scroll offset for detail view content
+    pub diff_scroll: usize,            // scroll offset of the pending edit diff
+    pub edit_preview_cache: Option<EditPreviewCache>, // see `update_edit_preview`
+    pub transcript: Option<TranscriptView>, // conversation view over the detail view
+    pub history: Option<HistoryView>,  // past conversations of the detail view's project
+    pub detail_collapsed: HashSet<u32>, // collapsed process tree nodes (by PID)
+    pub process_target: Option<ProcessInfo>, // process the action modal applies to
+    pub pending_signal: Option<Signal>, // signal awaiting confirmation
     // Session restore
     pub last_save: Instant, // Track last save time for periodic saves
-    // Stable permission key assignments (session name -> key)
+    // Stable permission key assignments (session key -> key)
     pub permission_key_map: HashMap<String, char>,
     // Sessions where we've sent permission approval but jsonl hasn't updated yet
     pub pending_approvals: HashSet<String>,
@@ -79,15 +314,27 @@ pub struct App {
     pub search_query: String,
     pub search_results: Vec<SearchResult>,
     pub search_scroll_offset: usize, // Scroll offset for search results
-    pub sesh_projects: Vec<String>,  // Cached list of all sesh projects
+    pub search_scope: SearchScope,
+    pub transcript_hits: Vec<TranscriptHit>,
+    pub transcript_search: TranscriptSearch,
+    // Read from disk when there's no daemon (shared with the search thread)
+    pub transcript_index: Arc<OnceLock<TranscriptIndex>>,
+    pub sesh_projects: Vec<String>, // Cached list of all sesh projects
     // Parked session detail view
     pub showing_parked_detail: Option<String>, // parked session name being viewed
+    // tmux servers polled without a daemon (rediscovered every SERVER_DISCOVERY_INTERVAL)
+    pub tmux_servers: Vec<TmuxServer>,
+    pub tmux_servers_checked: Option<Instant>,
     // Daemon client (optional - falls back to JSONL polling if None)
     pub daemon_client: Option<DaemonClient>,
     // Track if we're connected to daemon (for UI indicator)
     pub daemon_connected: bool,
-    // Per-session auto-approve toggle
+    // Per-session auto-approve toggle (by session key, as are the sets below)
     pub auto_approve_sessions: HashSet<String>,
+    // Sessions marked for bulk approval (by session key)
+    pub marked_sessions: HashSet<String>,
+    // Requests listed in the bulk approval confirmation, as they were when it opened
+    pub bulk_approval: Vec<BulkRequest>,
     // Per-session notification mute
     pub muted_sessions: HashSet<String>,
     pub global_mute: bool,
@@ -126,10 +373,21 @@ impl App {
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             pending_park_session: None,
+            pending_kill_session: None,
+            worktrees: load_worktrees(),
+            worktrees_modified: worktrees_modified(),
+            pending_worktree_removal: None,
             session_todos: load_session_todos(),
             showing_detail: None,
             detail_selected: 0,
             detail_scroll_offset: 0,
+            diff_scroll: 0,
+            edit_preview_cache: None,
+            transcript: None,
+            history: None,
+            detail_collapsed: HashSet::new(),
+            process_target: None,
+            pending_signal: None,
             last_save: Instant::now(),
             permission_key_map: HashMap::new(),
             pending_approvals: HashSet::new(),
@@ -138,8 +396,14 @@ impl App {
             search_query: String::new(),
             search_results: Vec::new(),
             search_scroll_offset: 0,
+            search_scope: SearchScope::Sessions,
+            transcript_hits: Vec::new(),
+            transcript_search: TranscriptSearch::default(),
+            transcript_index: Arc::default(),
             sesh_projects: Vec::new(), // Loaded on demand when entering search mode
             showing_parked_detail: None,
+            tmux_servers: Vec::new(),
+            tmux_servers_checked: None,
             daemon_client: if daemon_connected {
                 Some(daemon_client)
             } else {
@@ -147,6 +411,8 @@ impl App {
             },
             daemon_connected,
             auto_approve_sessions: load_auto_approve_sessions(),
+            marked_sessions: HashSet::new(),
+            bulk_approval: Vec::new(),
             muted_sessions: load_muted_sessions(),
             global_mute: is_globally_muted(),
             skipped_sessions: load_skipped_sessions(),
@@ -169,14 +435,15 @@ impl App {
     /// Update search results based on current query
     pub fn update_search_results(&mut self) {
         self.search_results.clear();
+        if self.search_scope == SearchScope::Transcripts {
+            self.update_transcript_hits();
+            return;
+        }
         let query = self.search_query.to_lowercase();
 
         // Collect active session names for deduplication
-        let active_names: HashSet<String> = self
-            .session_infos
-            .iter()
-            .map(|s| s.name.clone())
-            .collect();
+        let active_names: HashSet<String> =
+            self.session_infos.iter().map(|s| s.name.clone()).collect();
 
         // Add matching active sessions
         for (i, info) in self.session_infos.iter().enumerate() {
@@ -199,7 +466,8 @@ impl App {
                 continue;
             }
             if query.is_empty() || name.to_lowercase().contains(&query) {
-                self.search_results.push(SearchResult::SeshProject(name.clone()));
+                self.search_results
+                    .push(SearchResult::SeshProject(name.clone()));
             }
         }
 
@@ -209,6 +477,126 @@ impl App {
         }
     }
 
+    /// Schedule a transcript search for the current query (see `poll_transcript_search`);
+    /// the previous hits stay listed until its results arrive
+    fn update_transcript_hits(&mut self) {
+        let query = self.search_query.trim().to_string();
+        if query.chars().count() < 2 {
+            self.transcript_search.pending = None;
+            self.transcript_hits.clear();
+        } else if self
+            .transcript_search
+            .pending
+            .as_ref()
+            .is_none_or(|(pending, _)| *pending != query)
+        {
+            let due = Instant::now() + TRANSCRIPT_SEARCH_DEBOUNCE;
+            self.transcript_search.pending = Some((query, due));
+        }
+        self.list_transcript_hits();
+    }
+
+    /// Start a due transcript search and collect finished ones (via the daemon's
+    /// index, else the index on disk). Returns whether new hits arrived.
+    pub fn poll_transcript_search(&mut self) -> bool {
+        let mut arrived = false;
+        if let Some(rx) = &self.transcript_search.running {
+            match rx.try_recv() {
+                Ok((query, hits)) => {
+                    self.transcript_search.running = None;
+                    // Results for a query that was edited since are dropped
+                    if self.search_scope == SearchScope::Transcripts
+                        && query == self.search_query.trim()
+                    {
+                        self.transcript_hits = hits;
+                        self.list_transcript_hits();
+                        arrived = true;
+                    }
+                }
+                Err(TryRecvError::Empty) => return false,
+                Err(TryRecvError::Disconnected) => self.transcript_search.running = None,
+            }
+        }
+        let query = match self.transcript_search.pending.take() {
+            Some((query, at)) if at <= Instant::now() => query,
+            pending => {
+                self.transcript_search.pending = pending;
+                return arrived;
+            }
+        };
+        let (tx, rx) = channel();
+        let index = Arc::clone(&self.transcript_index);
+        std::thread::spawn(move || {
+            let mut client = DaemonClient::new();
+            let hits = client
+                .connect()
+                .then(|| client.search_transcripts(&query, TRANSCRIPT_HIT_LIMIT))
+                .flatten()
+                .unwrap_or_else(|| {
+                    index
+                        .get_or_init(TranscriptIndex::load)
+                        .search(&query, TRANSCRIPT_HIT_LIMIT)
+                });
+            let _ = tx.send((query, hits));
+        });
+        self.transcript_search.running = Some(rx);
+        arrived
+    }
+
+    /// List the current transcript hits as search results
+    fn list_transcript_hits(&mut self) {
+        self.search_results = (0..self.transcript_hits.len())
+            .map(SearchResult::Transcript)
+            .collect();
+        if self.selected >= self.search_results.len() {
+            self.selected = 0;
+        }
+    }
+
+    /// Switch `/` between session names and transcript contents
+    pub fn toggle_search_scope(&mut self) {
+        self.search_scope = match self.search_scope {
+            SearchScope::Sessions => SearchScope::Transcripts,
+            SearchScope::Transcripts => SearchScope::Sessions,
+        };
+        self.selected = 0;
+        self.search_scroll_offset = 0;
+        self.update_search_results();
+    }
+
+    /// Whether transcript hit `idx` starts a new project group
+    pub fn starts_project_group(&self, idx: usize) -> bool {
+        idx == 0
+            || self.transcript_hits.get(idx - 1).map(|h| &h.project)
+                != self.transcript_hits.get(idx).map(|h| &h.project)
+    }
+
+    /// Open the transcript of search hit `idx`, with the query's first match selected
+    pub fn open_transcript_hit(&mut self, idx: usize) -> bool {
+        let Some(hit) = self.transcript_hits.get(idx) else {
+            return false;
+        };
+        let title = Path::new(&hit.project)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| hit.project.clone());
+        match TranscriptView::load(hit.path.clone(), title, hit.project.clone()) {
+            Ok(mut view) => {
+                if !view.search(self.search_query.trim()) {
+                    // The index matches words anywhere; fall back to the first one
+                    let first_word = self.search_query.split_whitespace().next().unwrap_or("");
+                    view.search(first_word);
+                }
+                self.transcript = Some(view);
+                true
+            }
+            Err(e) => {
+                self.error_message = Some((format!("{:#}", e), Instant::now()));
+                false
+            }
+        }
+    }
+
     /// Load sesh projects list (called when entering search mode)
     pub fn load_sesh_projects(&mut self) {
         self.sesh_projects = list_sesh_projects();
@@ -228,6 +616,8 @@ impl App {
                 1
             }
             SearchResult::SeshProject(_) => 1,
+            // Session line and best snippet, after a project header for a new project
+            SearchResult::Transcript(idx) => 2 + usize::from(self.starts_project_group(*idx)),
         }
     }
 
@@ -261,203 +651,66 @@ impl App {
     /// Refresh session data (gather from tmux + sysinfo, with daemon state overlay)
     pub fn refresh(&mut self) -> Result<()> {
         self.sys.refresh_all();
+        // `tmux-claude new --worktree` may have added sessions since the last refresh
+        let worktrees_modified = worktrees_modified();
+        if worktrees_modified != self.worktrees_modified {
+            self.worktrees = load_worktrees();
+            self.worktrees_modified = worktrees_modified;
+        }
 
         // Get daemon state if connected (for Claude status and metrics)
-        // Index by cwd since hooks don't know tmux session names
-        let (daemon_sessions, daemon_metrics): (HashMap<String, _>, Option<MetricsHistory>) =
-            if let Some(client) = &mut self.daemon_client {
-                match client.get_state_with_metrics() {
-                    Some((sessions, metrics)) => (
-                        sessions.into_iter().map(|s| (s.cwd.clone(), s)).collect(),
-                        metrics,
-                    ),
-                    None => (HashMap::new(), None),
-                }
-            } else {
-                (HashMap::new(), None)
-            };
+        let snapshot = self
+            .daemon_client
+            .as_mut()
+            .and_then(|client| client.get_snapshot())
+            .unwrap_or_default();
 
         // Store metrics from daemon
-        self.metrics_history = daemon_metrics;
-
-        let using_daemon = !daemon_sessions.is_empty();
-        if using_daemon {
-            debug_log(&format!(
-                "REFRESH: Using daemon state for {} sessions (by cwd)",
-                daemon_sessions.len()
-            ));
-        }
-
-        let sessions = get_tmux_sessions()?;
-        let mut session_infos = Vec::new();
-
-        for session in sessions {
-            if !matches_filter(&session.name, &self.filter) {
-                continue;
-            }
-
-            // Get session CWD from first pane
-            let session_cwd = session
-                .windows
-                .first()
-                .and_then(|w| w.panes.first())
-                .map(|p| p.cwd.clone());
-
-            // Calculate session totals and collect per-process info
-            let mut all_pids = Vec::new();
-            for window in &session.windows {
-                for pane in &window.panes {
-                    all_pids.push(pane.pid);
-                    get_all_descendants(&self.sys, pane.pid, &mut all_pids);
-                }
-            }
-
-            let mut total_cpu = 0.0;
-            let mut total_mem_kb = 0u64;
-            let mut processes: Vec<ProcessInfo> = Vec::new();
-
-            for &pid in &all_pids {
-                if let Some(info) = get_process_info(&self.sys, pid) {
-                    total_cpu += info.cpu_percent;
-                    total_mem_kb += info.memory_kb;
-                    // Keep processes with >0 CPU or >1MB memory
-                    if info.cpu_percent > 0.0 || info.memory_kb >= 1024 {
-                        processes.push(info);
-                    }
-                }
-            }
-
-            // Sort processes by CPU descending
-            processes.sort_by(|a, b| b.cpu_percent.partial_cmp(&a.cpu_percent).unwrap_or(std::cmp::Ordering::Equal));
-
-            // Detect listening ports for all PIDs in this session
-            let listening_ports = get_listening_ports_for_pids(&all_pids, &self.sys);
-
-            // Find Claude pane: check daemon state by cwd, or detect Claude process
-            let mut claude_status: Option<ClaudeStatus> = None;
-            let mut claude_pane: Option<(String, String, String)> = None;
-            let mut last_activity = None;
-
-            'outer: for window in &session.windows {
-                for p in &window.panes {
-                    // Check if a Claude process is actually running in this pane
-                    let mut pane_pids = vec![p.pid];
-                    get_all_descendants(&self.sys, p.pid, &mut pane_pids);
-
-                    let has_claude_process = pane_pids.iter().any(|&pid| {
-                        get_process_info(&self.sys, pid)
-                            .map(|info| is_claude_process(&info))
-                            .unwrap_or(false)
-                    });
-
-                    if has_claude_process {
-                        // Use daemon state if available (richer status info)
-                        if let Some(daemon_state) = daemon_sessions.get(&p.cwd) {
-                            claude_status = Some(convert_daemon_status(&daemon_state.status));
-                            last_activity = daemon_state
-                                .last_activity
-                                .as_ref()
-                                .and_then(|s| parse_timestamp(s));
-                        } else {
-                            // Claude running but no daemon state yet - show as working
-                            claude_status = Some(ClaudeStatus::Unknown);
-                        }
-                        claude_pane = Some((
-                            session.name.clone(),
-                            window.index.clone(),
-                            p.index.clone(),
-                        ));
-                        break 'outer;
-                    }
-                }
-            }
-
-            session_infos.push(SessionInfo {
-                name: session.name.clone(),
-                claude_status,
-                claude_pane,
-                permission_key: None, // Will be assigned after sorting
-                total_cpu,
-                total_mem_kb,
-                last_activity,
-                processes,
-                cwd: session_cwd,
-                listening_ports,
-            });
-        }
-
-        // Sort: Claude (non-skipped) → non-Claude (non-skipped) → skipped
-        // Within each group, stable sort preserves original order
-        session_infos.sort_by_key(|s| {
-            let is_skipped = self.skipped_sessions.contains(&s.name);
-            (is_skipped, s.claude_status.is_none())
-        });
-
-        // Stable permission key assignment
-        // 1. Determine which sessions need permission (excluding pending approvals)
-        let sessions_needing_permission: HashSet<String> = session_infos
-            .iter()
-            .filter(|s| {
-                !self.pending_approvals.contains(&s.name)
-                    && matches!(
-                        s.claude_status,
-                        Some(ClaudeStatus::NeedsPermission(_, _))
-                            | Some(ClaudeStatus::EditApproval(_))
-                    )
-            })
-            .map(|s| s.name.clone())
-            .collect();
-
-        // 2. Clean up pending approvals for sessions that no longer need permission
-        //    (Claude has processed the approval)
-        self.pending_approvals.retain(|name| {
-            session_infos.iter().any(|s| {
-                &s.name == name
-                    && matches!(
-                        s.claude_status,
-                        Some(ClaudeStatus::NeedsPermission(_, _))
-                            | Some(ClaudeStatus::EditApproval(_))
-                    )
-            })
-        });
-
-        // 3. Remove sessions that no longer need permission from the key map
-        self.permission_key_map
-            .retain(|name, _| sessions_needing_permission.contains(name));
-
-        // 4. Get currently used keys and find available keys
-        let used_keys: HashSet<char> = self.permission_key_map.values().copied().collect();
-        let mut available_keys: Vec<char> = PERMISSION_KEYS
-            .iter()
-            .filter(|k| !used_keys.contains(k))
-            .copied()
-            .collect();
-
-        // 5. Assign keys to sessions that need permission
-        for session in &mut session_infos {
-            if sessions_needing_permission.contains(&session.name) {
-                if let Some(&existing_key) = self.permission_key_map.get(&session.name) {
-                    // Already has a key, use it
-                    session.permission_key = Some(existing_key);
-                } else if let Some(new_key) = available_keys.pop() {
-                    // Assign first available key
-                    self.permission_key_map
-                        .insert(session.name.clone(), new_key);
-                    session.permission_key = Some(new_key);
+        self.metrics_history = snapshot.metrics;
+
+        // Prefer the daemon's live tmux model over spawning tmux ourselves
+        let sessions = match snapshot.tmux_sessions {
+            Some(sessions) => sessions,
+            None => {
+                let stale = self
+                    .tmux_servers_checked
+                    .is_none_or(|checked| checked.elapsed() >= SERVER_DISCOVERY_INTERVAL);
+                if stale {
+                    self.tmux_servers = discover_tmux_servers();
+                    self.tmux_servers_checked = Some(Instant::now());
                 }
-                // else: no more keys available, permission_key stays None
+                get_tmux_sessions(&self.tmux_servers)
             }
-        }
+        };
+        let mut session_infos = collect_sessions(
+            &self.sys,
+            sessions,
+            snapshot.sessions,
+            snapshot.alerts,
+            &snapshot.git,
+            &self.filter,
+            true,
+        );
+        sort_sessions(&mut session_infos, &self.skipped_sessions);
+        assign_permission_keys(
+            &mut session_infos,
+            &mut self.permission_key_map,
+            &mut self.pending_approvals,
+        );
 
         self.session_infos = session_infos;
+        let keys: HashSet<String> = self.session_infos.iter().map(|s| s.key()).collect();
+        self.marked_sessions.retain(|key| keys.contains(key));
 
         // Fetch Chrome tabs for detail view (only when detail is open and session has ports)
         if let Some(idx) = self.showing_detail {
             if let Some(session) = self.session_infos.get(idx) {
                 if !session.listening_ports.is_empty() {
                     let all_tabs = crate::common::chrome::get_chrome_tabs();
-                    self.detail_chrome_tabs =
-                        crate::common::chrome::match_tabs_to_ports(&all_tabs, &session.listening_ports);
+                    self.detail_chrome_tabs = crate::common::chrome::match_tabs_to_ports(
+                        &all_tabs,
+                        &session.listening_ports,
+                    );
                 } else {
                     self.detail_chrome_tabs.clear();
                 }
@@ -544,7 +797,8 @@ impl App {
     pub fn start_park_session(&mut self, idx: usize) {
         if let Some(session_info) = self.session_infos.get(idx) {
             let name = session_info.name.clone();
-            if !has_sesh_config(&name) {
+            // Worktree sessions are reopened from their worktree instead of sesh
+            if !has_sesh_config(&name) && !self.worktrees.contains_key(&name) {
                 self.error_message = Some((
                     format!("Cannot park '{}': no sesh config", name),
                     Instant::now(),
@@ -560,25 +814,132 @@ impl App {
 
     /// Complete parking a session with the given note
     pub fn complete_park_session(&mut self) {
+        let mut parked = None;
         if let Some(idx) = self.pending_park_session.take() {
             if let Some(session_info) = self.session_infos.get(idx) {
                 let name = session_info.name.clone();
                 let note = self.input_buffer.trim().to_string();
-                if kill_tmux_session(&name) {
+                if kill_tmux_session(&session_info.server, &name) {
                     self.parked_sessions.insert(name.clone(), note);
                     save_parked_sessions(&self.parked_sessions);
                     // Close detail view since the session was killed
                     self.showing_detail = None;
+                    parked = Some(name);
                 } else {
-                    self.error_message = Some((
-                        format!("Failed to kill session '{}'", name),
-                        Instant::now(),
-                    ));
+                    self.error_message =
+                        Some((format!("Failed to kill session '{}'", name), Instant::now()));
                 }
             }
         }
         self.input_mode = InputMode::Normal;
         self.input_buffer.clear();
+        if let Some(name) = parked {
+            self.offer_worktree_removal(&name);
+        }
+    }
+
+    /// Ask for confirmation before killing a session
+    pub fn start_kill_session(&mut self, idx: usize) {
+        if let Some(session_info) = self.session_infos.get(idx) {
+            if session_info.host.is_some() {
+                self.error_message = Some((
+                    format!("'{}' runs on a remote host", session_info.name),
+                    Instant::now(),
+                ));
+                return;
+            }
+            self.input_mode = InputMode::ConfirmKill;
+            self.pending_kill_session = Some(idx);
+        }
+    }
+
+    /// Kill the session awaiting confirmation
+    pub fn complete_kill_session(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(idx) = self.pending_kill_session.take() else {
+            return;
+        };
+        let Some(session_info) = self.session_infos.get(idx) else {
+            return;
+        };
+        let name = session_info.name.clone();
+        if kill_tmux_session(&session_info.server, &name) {
+            self.showing_detail = None;
+            self.offer_worktree_removal(&name);
+        } else {
+            self.error_message =
+                Some((format!("Failed to kill session '{}'", name), Instant::now()));
+        }
+    }
+
+    /// Cancel killing a session
+    pub fn cancel_kill_session(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.pending_kill_session = None;
+    }
+
+    /// After a session is gone, offer to remove its worktree if that loses nothing
+    fn offer_worktree_removal(&mut self, name: &str) {
+        let Some(worktree) = self.worktrees.get(name) else {
+            return;
+        };
+        match removal_blocker(worktree) {
+            Some(reason) => {
+                self.error_message = Some((
+                    format!("Keeping worktree {}: {}", worktree.path.display(), reason),
+                    Instant::now(),
+                ));
+            }
+            None => {
+                self.input_mode = InputMode::ConfirmRemoveWorktree;
+                self.pending_worktree_removal = Some(name.to_string());
+            }
+        }
+    }
+
+    /// Worktree awaiting removal confirmation
+    pub fn pending_worktree(&self) -> Option<&SessionWorktree> {
+        self.pending_worktree_removal
+            .as_ref()
+            .and_then(|name| self.worktrees.get(name))
+    }
+
+    /// Remove the worktree awaiting confirmation (and its branch)
+    pub fn confirm_worktree_removal(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let Some(name) = self.pending_worktree_removal.take() else {
+            return;
+        };
+        let Some(worktree) = self.worktrees.get(&name) else {
+            return;
+        };
+        match remove_worktree(worktree) {
+            Ok(()) => {
+                self.worktrees.remove(&name);
+                save_worktrees(&self.worktrees);
+                // Without its worktree a parked session can't be reopened
+                if self.parked_sessions.remove(&name).is_some() {
+                    save_parked_sessions(&self.parked_sessions);
+                }
+            }
+            Err(e) => {
+                self.error_message = Some((format!("{:#}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Keep the worktree awaiting confirmation
+    pub fn cancel_worktree_removal(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.pending_worktree_removal = None;
+    }
+
+    /// Reopen a parked session: from its worktree if it has one, otherwise via sesh
+    pub fn reopen_parked(&self, name: &str) -> bool {
+        match self.worktrees.get(name) {
+            Some(worktree) => reopen_worktree_session(name, worktree),
+            None => sesh_connect(name),
+        }
     }
 
     /// Cancel note input and return to normal mode
@@ -593,7 +954,7 @@ impl App {
         let list = self.parked_list();
         if let Some((name, _note)) = list.get(self.parked_selected) {
             let name = name.clone();
-            if sesh_connect(&name) {
+            if self.reopen_parked(&name) {
                 self.parked_sessions.remove(&name);
                 save_parked_sessions(&self.parked_sessions);
                 self.showing_parked = false;
@@ -634,6 +995,45 @@ impl App {
         }
     }
 
+    /// Switch to the session at index on its tmux server, reporting if no client can switch
+    pub fn switch_to_session_at(&mut self, idx: usize) -> bool {
+        let Some(session_info) = self.session_infos.get(idx) else {
+            return false;
+        };
+        match focus_session(session_info) {
+            Ok(()) => true,
+            Err(e) => {
+                self.error_message = Some((e.to_string(), Instant::now()));
+                false
+            }
+        }
+    }
+
+    /// Approve a session's permission prompt (see `approve_session`)
+    pub fn approve_session_at(&mut self, idx: usize, always: bool) -> bool {
+        let Some(session_info) = self.session_infos.get(idx) else {
+            return false;
+        };
+        let approved = approve_session(session_info, always, self.daemon_client.as_mut());
+
+        let (key, name) = (session_info.key(), session_info.name.clone());
+        if approved {
+            // Mark as pending so the key disappears immediately
+            self.pending_approvals.insert(key);
+        } else {
+            self.error_message = Some((format!("Approval for '{}' failed", name), Instant::now()));
+        }
+        approved
+    }
+
+    /// Whether sessions come from more than one tmux server
+    pub fn has_multiple_servers(&self) -> bool {
+        self.session_infos
+            .first()
+            .map(|first| self.session_infos.iter().any(|s| s.server != first.server))
+            .unwrap_or(false)
+    }
+
     // --- Detail view methods ---
 
     /// Open detail view for a session by index
@@ -642,6 +1042,7 @@ impl App {
             self.showing_detail = Some(idx);
             self.detail_selected = 0;
             self.detail_scroll_offset = 0;
+            self.diff_scroll = 0;
         }
     }
 
@@ -650,9 +1051,15 @@ impl App {
         self.showing_detail = None;
         self.detail_selected = 0;
         self.detail_scroll_offset = 0;
+        self.diff_scroll = 0;
+        self.transcript = None;
+        self.history = None;
         self.input_mode = InputMode::Normal;
         self.input_buffer.clear();
         self.detail_chrome_tabs.clear();
+        self.detail_collapsed.clear();
+        self.process_target = None;
+        self.pending_signal = None;
     }
 
     /// Get the session name for the current detail view
@@ -662,14 +1069,97 @@ impl App {
             .map(|s| s.name.clone())
     }
 
+    /// Key of the session in the detail view (see `session_key`)
+    pub fn detail_session_key(&self) -> Option<String> {
+        self.showing_detail
+            .and_then(|idx| self.session_infos.get(idx))
+            .map(|s| s.key())
+    }
+
+    /// Recompute the detail-view session's pending edit diff, only when its tool
+    /// call or the file on disk changed since the last call
+    pub fn update_edit_preview(&mut self) {
+        let pending = self
+            .showing_detail
+            .and_then(|idx| self.session_infos.get(idx))
+            .filter(|s| matches!(s.claude_status, Some(ClaudeStatus::EditApproval(_))))
+            .and_then(|s| Some((s, s.pending_tool.as_ref()?)));
+        let Some((session, tool)) = pending else {
+            self.edit_preview_cache = None;
+            return;
+        };
+        // Remote files can't be read, so those are diffed from the tool input alone
+        let cwd = session
+            .cwd
+            .as_deref()
+            .filter(|_| session.host.is_none())
+            .map(Path::new);
+        let mtime = cwd
+            .zip(tool.tool_input.get("file_path").and_then(|p| p.as_str()))
+            .and_then(|(cwd, file)| std::fs::metadata(cwd.join(file)).ok())
+            .and_then(|metadata| metadata.modified().ok());
+        let mut hasher = DefaultHasher::new();
+        tool.tool_name.hash(&mut hasher);
+        tool.tool_input.to_string().hash(&mut hasher);
+        let key = (session.key(), hasher.finish(), mtime);
+        if self
+            .edit_preview_cache
+            .as_ref()
+            .is_some_and(|cache| cache.key == key)
+        {
+            return;
+        }
+        let preview = edit_preview(tool, cwd);
+        self.edit_preview_cache = Some(EditPreviewCache { key, preview });
+    }
+
+    /// Diff of the detail-view session's pending edit (see `update_edit_preview`)
+    pub fn detail_edit_preview(&self) -> Option<&EditPreview> {
+        self.edit_preview_cache.as_ref()?.preview.as_ref()
+    }
+
     /// Get todos for the session in detail view
     pub fn detail_todos(&self) -> Vec<String> {
-        self.detail_session_name()
-            .and_then(|name| self.session_todos.get(&name))
+        self.detail_session_key()
+            .and_then(|key| self.session_todos.get(&key))
             .cloned()
             .unwrap_or_default()
     }
 
+    /// Start entering a new Claude session
+    pub fn start_new_session(&mut self) {
+        self.input_mode = InputMode::NewSession;
+        self.input_buffer.clear();
+    }
+
+    /// Start Claude for the entered directory/project and register it with the daemon
+    pub fn complete_new_session(&mut self) {
+        let input = std::mem::take(&mut self.input_buffer);
+        self.input_mode = InputMode::Normal;
+        let Some(request) = LaunchRequest::from_input(&input) else {
+            return;
+        };
+        match launch_session(&request) {
+            Ok(launched) => {
+                if let Some(client) = self.daemon_client.as_mut() {
+                    client.register_session(&launched, request.prompt.is_some());
+                }
+                if let Some(worktree) = launched.worktree {
+                    self.worktrees.insert(launched.session, worktree);
+                }
+            }
+            Err(e) => {
+                self.error_message = Some((format!("{:#}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Cancel new session input
+    pub fn cancel_new_session(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.input_buffer.clear();
+    }
+
     /// Start adding a todo
     pub fn start_add_todo(&mut self) {
         self.input_mode = InputMode::AddTodo;
@@ -678,10 +1168,10 @@ impl App {
 
     /// Complete adding a todo
     pub fn complete_add_todo(&mut self) {
-        if let Some(name) = self.detail_session_name() {
+        if let Some(key) = self.detail_session_key() {
             let todo = self.input_buffer.trim().to_string();
             if !todo.is_empty() {
-                self.session_todos.entry(name).or_default().push(todo);
+                self.session_todos.entry(key).or_default().push(todo);
                 save_session_todos(&self.session_todos);
             }
         }
@@ -697,11 +1187,11 @@ impl App {
 
     /// Delete the selected todo
     pub fn delete_selected_todo(&mut self) {
-        let Some(name) = self.detail_session_name() else {
+        let Some(key) = self.detail_session_key() else {
             return;
         };
 
-        let should_save = if let Some(todos) = self.session_todos.get_mut(&name) {
+        let should_save = if let Some(todos) = self.session_todos.get_mut(&key) {
             if self.detail_selected < todos.len() {
                 todos.remove(self.detail_selected);
                 // Adjust selection if needed
@@ -721,11 +1211,338 @@ impl App {
         }
     }
 
-    /// Get todo count for a session name
-    pub fn todo_count(&self, session_name: &str) -> usize {
-        self.session_todos
-            .get(session_name)
-            .map(|v| v.len())
+    // --- Transcript view methods ---
+
+    /// Open the transcript of the session in the detail view
+    pub fn open_transcript(&mut self) {
+        let Some(session) = self
+            .showing_detail
+            .and_then(|idx| self.session_infos.get(idx))
+        else {
+            return;
+        };
+        if session.host.is_some() {
+            self.error_message = Some((
+                "Transcripts of remote sessions aren't available here".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        let Some(ref cwd) = session.cwd else {
+            self.error_message = Some((
+                "Session has no working directory".to_string(),
+                Instant::now(),
+            ));
+            return;
+        };
+        let Some(path) = find_transcript(
+            cwd,
+            session.daemon_session_id.as_deref(),
+            session.transcript_path.as_deref(),
+        ) else {
+            self.error_message = Some((format!("No transcript found for {}", cwd), Instant::now()));
+            return;
+        };
+        match TranscriptView::load(path, session.name.clone(), cwd.clone()) {
+            Ok(view) => self.transcript = Some(view),
+            Err(e) => self.error_message = Some((format!("{:#}", e), Instant::now())),
+        }
+    }
+
+    /// Re-read the open transcript (picks up new turns)
+    pub fn reload_transcript(&mut self) {
+        if let Some(ref mut view) = self.transcript {
+            if let Err(e) = view.reload() {
+                self.error_message = Some((format!("{:#}", e), Instant::now()));
+            }
+        }
+    }
+
+    /// Resume the open transcript's conversation with `claude --resume` in a new window.
+    /// Returns the tmux session it was started in.
+    pub fn resume_transcript(&mut self) -> Option<String> {
+        let view = self.transcript.as_ref()?;
+        let session_id = view.path.file_stem()?.to_string_lossy().to_string();
+        let cwd = view.cwd.clone();
+        self.resume_conversation(&cwd, &session_id)
+    }
+
+    /// Run `claude --resume <session_id>` in a new window of the session working in `cwd`
+    /// (created if needed) and switch to it. Returns the tmux session name.
+    fn resume_conversation(&mut self, cwd: &str, session_id: &str) -> Option<String> {
+        // Resuming a live conversation would fork it; go to it instead
+        if let Some(running) = self
+            .session_infos
+            .iter()
+            .find(|s| s.daemon_session_id.as_deref() == Some(session_id))
+        {
+            let name = running.name.clone();
+            if !switch_to_session(&running.server, &name) {
+                self.error_message =
+                    Some((format!("Already running in '{}'", name), Instant::now()));
+                return None;
+            }
+            return Some(name);
+        }
+        match resume_session(Path::new(cwd), session_id) {
+            Ok(launched) => {
+                if let Some(client) = self.daemon_client.as_mut() {
+                    client.register_session(&launched, false);
+                }
+                focus_pane(&TmuxServer::Current, &launched.location.pane_id);
+                Some(launched.session)
+            }
+            Err(e) => {
+                self.error_message = Some((format!("{:#}", e), Instant::now()));
+                None
+            }
+        }
+    }
+
+    /// Open the list of past conversations of the detail view's project
+    pub fn open_history(&mut self) {
+        let Some(session) = self
+            .showing_detail
+            .and_then(|idx| self.session_infos.get(idx))
+        else {
+            return;
+        };
+        if session.host.is_some() {
+            self.error_message = Some((
+                "History of remote sessions isn't available here".to_string(),
+                Instant::now(),
+            ));
+            return;
+        }
+        let Some(cwd) = session.cwd.clone() else {
+            self.error_message = Some((
+                "Session has no working directory".to_string(),
+                Instant::now(),
+            ));
+            return;
+        };
+        // The daemon's index is already parsed; read the files without it (or
+        // while it hasn't reached this project yet)
+        let entries = self
+            .daemon_client
+            .as_mut()
+            .and_then(|client| client.list_transcripts(&cwd))
+            .filter(|entries| !entries.is_empty())
+            .unwrap_or_else(|| list_transcripts(&cwd));
+        if entries.is_empty() {
+            self.error_message = Some((
+                format!("No Claude conversations in {}", cwd),
+                Instant::now(),
+            ));
+            return;
+        }
+        self.history = Some(HistoryView {
+            title: session.name.clone(),
+            cwd,
+            entries,
+            selected: 0,
+            scroll: 0,
+        });
+    }
+
+    /// Move the history selection by `delta` rows
+    pub fn move_history_selection(&mut self, delta: isize) {
+        if let Some(ref mut history) = self.history {
+            let last = history.entries.len().saturating_sub(1);
+            history.selected = history.selected.saturating_add_signed(delta).min(last);
+        }
+    }
+
+    /// Read the selected past conversation
+    pub fn open_history_transcript(&mut self) {
+        let Some(history) = self.history.as_ref() else {
+            return;
+        };
+        let Some(entry) = history.entries.get(history.selected) else {
+            return;
+        };
+        match TranscriptView::load(
+            entry.path.clone(),
+            history.title.clone(),
+            history.cwd.clone(),
+        ) {
+            Ok(view) => self.transcript = Some(view),
+            Err(e) => self.error_message = Some((format!("{:#}", e), Instant::now())),
+        }
+    }
+
+    /// Resume the selected past conversation. Returns the tmux session it runs in.
+    pub fn resume_history_selected(&mut self) -> Option<String> {
+        let history = self.history.as_ref()?;
+        let entry = history.entries.get(history.selected)?;
+        let (cwd, session_id) = (history.cwd.clone(), entry.session_id.clone());
+        self.resume_conversation(&cwd, &session_id)
+    }
+
+    /// Close the transcript, back to the view it was opened from
+    pub fn close_transcript(&mut self) {
+        self.transcript = None;
+        self.input_mode = InputMode::Normal;
+        self.input_buffer.clear();
+    }
+
+    /// Start typing a transcript search query
+    pub fn start_transcript_search(&mut self) {
+        self.input_mode = InputMode::TranscriptSearch;
+        self.input_buffer.clear();
+    }
+
+    /// Run the typed search in the transcript
+    pub fn complete_transcript_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let query = std::mem::take(&mut self.input_buffer);
+        if let Some(ref mut view) = self.transcript {
+            if !view.search(query.trim()) && !query.trim().is_empty() {
+                self.error_message =
+                    Some((format!("No match for '{}'", query.trim()), Instant::now()));
+            }
+        }
+    }
+
+    /// Abandon the typed search
+    pub fn cancel_transcript_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.input_buffer.clear();
+    }
+
+    // --- Process tree methods ---
+
+    /// Get the visible process tree rows for the session in detail view
+    pub fn detail_process_rows(&self) -> Vec<ProcessTreeRow> {
+        self.showing_detail
+            .and_then(|idx| self.session_infos.get(idx))
+            .map(|s| flatten_process_tree(&s.processes, &self.detail_collapsed))
+            .unwrap_or_default()
+    }
+
+    /// Selection index of the first process row (processes come after todos and ports)
+    pub fn detail_process_offset(&self) -> usize {
+        let port_count = self
+            .showing_detail
+            .and_then(|idx| self.session_infos.get(idx))
+            .map(|s| s.listening_ports.len())
+            .unwrap_or(0);
+        self.detail_todos().len() + port_count
+    }
+
+    /// Get the process under the detail view selection, if a process row is selected
+    pub fn selected_process(&self) -> Option<&ProcessInfo> {
+        let session = self
+            .showing_detail
+            .and_then(|idx| self.session_infos.get(idx))?;
+        let row_idx = self
+            .detail_selected
+            .checked_sub(self.detail_process_offset())?;
+        let rows = flatten_process_tree(&session.processes, &self.detail_collapsed);
+        rows.get(row_idx).map(|row| &session.processes[row.index])
+    }
+
+    /// Collapse or expand the selected process node
+    pub fn set_selected_process_collapsed(&mut self, collapsed: bool) {
+        let Some(pid) = self.selected_process().map(|p| p.pid) else {
+            return;
+        };
+        if collapsed {
+            self.detail_collapsed.insert(pid);
+        } else {
+            self.detail_collapsed.remove(&pid);
+        }
+    }
+
+    /// Toggle collapse of the selected process node
+    pub fn toggle_selected_process_collapsed(&mut self) {
+        if let Some(pid) = self.selected_process().map(|p| p.pid) {
+            let collapsed = self.detail_collapsed.contains(&pid);
+            self.set_selected_process_collapsed(!collapsed);
+        }
+    }
+
+    /// Open the action modal for the selected process
+    pub fn open_process_action(&mut self) {
+        if let Some(proc) = self.selected_process().cloned() {
+            self.process_target = Some(proc);
+            self.input_mode = InputMode::ProcessAction;
+        }
+    }
+
+    /// Ask for confirmation before sending a signal to the target process
+    pub fn request_signal(&mut self, signal: Signal) {
+        self.pending_signal = Some(signal);
+        self.input_mode = InputMode::ConfirmSignal;
+    }
+
+    /// Send the signal awaiting confirmation
+    pub fn confirm_pending_signal(&mut self) {
+        if let Some(signal) = self.pending_signal.take() {
+            self.send_signal_to_target(signal);
+        }
+    }
+
+    fn send_signal_to_target(&mut self, signal: Signal) {
+        if let Some(proc) = self.process_target.take() {
+            let msg = if send_signal(&self.sys, proc.pid, signal) {
+                format!(
+                    "Sent {} to {} ({})",
+                    signal_name(signal),
+                    proc.pid,
+                    proc.name
+                )
+            } else {
+                format!("Failed to send {} to {}", signal_name(signal), proc.pid)
+            };
+            self.error_message = Some((msg, Instant::now()));
+        }
+        self.cancel_process_action();
+    }
+
+    /// Lower the target process's priority
+    pub fn renice_target(&mut self, niceness: i32) {
+        if let Some(proc) = self.process_target.take() {
+            let msg = if renice_process(proc.pid, niceness) {
+                format!("Reniced {} ({}) to {}", proc.pid, proc.name, niceness)
+            } else {
+                format!("Failed to renice {}", proc.pid)
+            };
+            self.error_message = Some((msg, Instant::now()));
+        }
+        self.cancel_process_action();
+    }
+
+    /// Copy the target process's PID or full command into the tmux buffer
+    pub fn copy_target(&mut self, command: bool) {
+        if let Some(proc) = self.process_target.take() {
+            let (text, what) = if command {
+                (proc.command.clone(), "command")
+            } else {
+                (proc.pid.to_string(), "PID")
+            };
+            let msg = if copy_to_tmux_buffer(&text) {
+                format!("Copied {} of {}", what, proc.pid)
+            } else {
+                format!("Failed to copy {}", what)
+            };
+            self.error_message = Some((msg, Instant::now()));
+        }
+        self.cancel_process_action();
+    }
+
+    /// Close the process action modal
+    pub fn cancel_process_action(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.process_target = None;
+        self.pending_signal = None;
+    }
+
+    /// Get todo count for a session
+    pub fn todo_count(&self, session: &SessionInfo) -> usize {
+        self.session_todos
+            .get(&session.key())
+            .map(|v| v.len())
             .unwrap_or(0)
     }
 
@@ -753,26 +1570,154 @@ impl App {
         let Some(session_info) = self.session_infos.get(idx) else {
             return;
         };
-        let name = session_info.name.clone();
-        if self.auto_approve_sessions.contains(&name) {
-            self.auto_approve_sessions.remove(&name);
-            self.error_message = Some((
-                format!("Auto-approve OFF for '{}'", name),
-                Instant::now(),
-            ));
+        let (key, name) = (session_info.key(), session_info.name.clone());
+        if self.auto_approve_sessions.remove(&key) {
+            self.error_message = Some((format!("Auto-approve OFF for '{}'", name), Instant::now()));
         } else {
-            self.auto_approve_sessions.insert(name.clone());
-            self.error_message = Some((
-                format!("Auto-approve ON for '{}'", name),
-                Instant::now(),
-            ));
+            self.auto_approve_sessions.insert(key);
+            self.error_message = Some((format!("Auto-approve ON for '{}'", name), Instant::now()));
         }
         save_auto_approve_sessions(&self.auto_approve_sessions);
     }
 
+    /// Whether a session has an open permission request that hasn't been answered yet
+    pub fn awaits_approval(&self, idx: usize) -> bool {
+        self.session_infos
+            .get(idx)
+            .is_some_and(|s| awaits_approval(s, &self.pending_approvals))
+    }
+
+    /// Why a session's open request must not be approved without a human (high or unknown risk)
+    pub fn approval_blocker(&self, idx: usize) -> Option<String> {
+        approval_blocker(self.session_infos.get(idx)?)
+    }
+
+    /// Mark/unmark the selected Claude session for bulk approval
+    pub fn toggle_mark_selected(&mut self) {
+        if !self.show_selection {
+            return;
+        }
+        let Some(session_info) = self.session_infos.get(self.selected) else {
+            return;
+        };
+        if session_info.claude_status.is_none() {
+            return;
+        }
+        let key = session_info.key();
+        if !self.marked_sessions.remove(&key) {
+            self.marked_sessions.insert(key);
+        }
+    }
+
+    /// Confirm approving the open requests of all marked sessions
+    pub fn start_approve_marked(&mut self) {
+        let requests: Vec<BulkRequest> = self
+            .session_infos
+            .iter()
+            .filter(|s| {
+                self.marked_sessions.contains(&s.key())
+                    && awaits_approval(s, &self.pending_approvals)
+            })
+            .filter_map(BulkRequest::of)
+            .collect();
+        self.start_bulk_approve(requests, "No marked session has an open request");
+    }
+
+    /// Open requests of read-only tools (Read, Grep, ...)
+    pub fn read_only_requests(&self) -> Vec<BulkRequest> {
+        read_only_requests(&self.session_infos, &self.pending_approvals)
+    }
+
+    /// Confirm approving every open request of a read-only tool
+    pub fn start_approve_read_only(&mut self) {
+        let requests = self.read_only_requests();
+        self.start_bulk_approve(requests, "No open Read/Grep/Glob requests");
+    }
+
+    fn start_bulk_approve(&mut self, requests: Vec<BulkRequest>, none_message: &str) {
+        if requests.is_empty() {
+            self.error_message = Some((none_message.to_string(), Instant::now()));
+            return;
+        }
+        self.bulk_approval = requests;
+        self.input_mode = InputMode::ConfirmBulkApprove;
+    }
+
+    /// Rows of the bulk confirmation: session name, request, and why it is held back
+    pub fn bulk_approval_rows(&self) -> Vec<(String, String, Option<String>)> {
+        self.bulk_approval
+            .iter()
+            .map(|request| {
+                let held = match request.current_index(&self.session_infos) {
+                    Some(idx) => self.approval_blocker(idx),
+                    None => Some("answered or changed".to_string()),
+                };
+                (request.name.clone(), request.prompt.clone(), held)
+            })
+            .collect()
+    }
+
+    /// Approve each listed request that is still open unchanged (through the daemon
+    /// when possible) and report per session
+    pub fn confirm_bulk_approve(&mut self) {
+        self.input_mode = InputMode::Normal;
+        let mut approved = Vec::new();
+        let mut failed = Vec::new();
+        let mut held = Vec::new();
+        let mut changed = Vec::new();
+        for request in std::mem::take(&mut self.bulk_approval) {
+            // The session may have answered or moved on to another request meanwhile
+            let Some(idx) = request.current_index(&self.session_infos) else {
+                changed.push(request.name);
+                continue;
+            };
+            if !self.awaits_approval(idx) {
+                continue;
+            }
+            if self.approval_blocker(idx).is_some() {
+                held.push(request.name);
+                continue;
+            }
+            let via_daemon = match (request.session_id.as_deref(), self.daemon_client.as_mut()) {
+                (Some(session_id), Some(client)) => client.approve_permission(session_id, false),
+                _ => false,
+            };
+            if via_daemon {
+                self.pending_approvals.insert(request.key.clone());
+            }
+            // Fall back to typing the answer into the local pane
+            if via_daemon || self.approve_session_at(idx, false) {
+                self.marked_sessions.remove(&request.key);
+                approved.push(request.name);
+            } else {
+                failed.push(request.name);
+            }
+        }
+
+        let mut parts = vec![format!("Approved {}", approved.len())];
+        if !approved.is_empty() {
+            parts[0].push_str(&format!(": {}", approved.join(", ")));
+        }
+        if !failed.is_empty() {
+            parts.push(format!("failed: {}", failed.join(", ")));
+        }
+        if !held.is_empty() {
+            parts.push(format!("held back: {}", held.join(", ")));
+        }
+        if !changed.is_empty() {
+            parts.push(format!("skipped (changed): {}", changed.join(", ")));
+        }
+        self.error_message = Some((parts.join(" · "), Instant::now()));
+    }
+
+    pub fn cancel_bulk_approve(&mut self) {
+        self.bulk_approval.clear();
+        self.input_mode = InputMode::Normal;
+    }
+
     /// Check if a session has auto-approve enabled
-    pub fn is_auto_approved(&self, name: &str) -> bool {
-        self.auto_approve_sessions.contains(name)
+    pub fn is_auto_approved(&self, session: &SessionInfo) -> bool {
+        self.auto_approve_sessions.contains(&session.key())
     }
 
     /// Toggle notification mute for a session by index
@@ -780,26 +1725,20 @@ impl App {
         let Some(session_info) = self.session_infos.get(idx) else {
             return;
         };
-        let name = session_info.name.clone();
-        if self.muted_sessions.contains(&name) {
-            self.muted_sessions.remove(&name);
-            self.error_message = Some((
-                format!("Notifications ON for '{}'", name),
-                Instant::now(),
-            ));
+        let (key, name) = (session_info.key(), session_info.name.clone());
+        if self.muted_sessions.remove(&key) {
+            self.error_message = Some((format!("Notifications ON for '{}'", name), Instant::now()));
         } else {
-            self.muted_sessions.insert(name.clone());
-            self.error_message = Some((
-                format!("Notifications OFF for '{}'", name),
-                Instant::now(),
-            ));
+            self.muted_sessions.insert(key);
+            self.error_message =
+                Some((format!("Notifications OFF for '{}'", name), Instant::now()));
         }
         save_muted_sessions(&self.muted_sessions);
     }
 
     /// Check if a session is muted
-    pub fn is_muted(&self, name: &str) -> bool {
-        self.muted_sessions.contains(name)
+    pub fn is_muted(&self, session: &SessionInfo) -> bool {
+        self.muted_sessions.contains(&session.key())
     }
 
     /// Toggle global mute
@@ -818,34 +1757,455 @@ impl App {
         let Some(session_info) = self.session_infos.get(idx) else {
             return;
         };
-        let name = session_info.name.clone();
-        if self.skipped_sessions.contains(&name) {
-            self.skipped_sessions.remove(&name);
-            self.error_message = Some((
-                format!("Cycling ON for '{}'", name),
-                Instant::now(),
-            ));
+        let (key, name) = (session_info.key(), session_info.name.clone());
+        if self.skipped_sessions.remove(&key) {
+            self.error_message = Some((format!("Cycling ON for '{}'", name), Instant::now()));
         } else {
-            self.skipped_sessions.insert(name.clone());
-            self.error_message = Some((
-                format!("Cycling OFF for '{}'", name),
-                Instant::now(),
-            ));
+            self.skipped_sessions.insert(key);
+            self.error_message = Some((format!("Cycling OFF for '{}'", name), Instant::now()));
         }
         save_skipped_sessions(&self.skipped_sessions);
     }
 
     /// Check if a session is skipped from cycling
-    pub fn is_skipped(&self, name: &str) -> bool {
-        self.skipped_sessions.contains(name)
+    pub fn is_skipped(&self, session: &SessionInfo) -> bool {
+        self.skipped_sessions.contains(&session.key())
+    }
+}
+
+/// Merge tmux sessions with the daemon's Claude sessions (matched by pane cwd, since
+/// hooks don't know tmux session names) into the list rows, remote sessions last.
+/// Unsorted and without permission keys; ports are only looked up `with_ports`.
+pub fn collect_sessions(
+    sys: &System,
+    tmux_sessions: Vec<TmuxSession>,
+    daemon_sessions: Vec<SessionState>,
+    alerts: Vec<ResourceAlert>,
+    git_statuses: &HashMap<String, GitStatus>,
+    filter: &Option<String>,
+    with_ports: bool,
+) -> Vec<SessionInfo> {
+    let (remote_sessions, local_sessions): (Vec<_>, Vec<_>) =
+        daemon_sessions.into_iter().partition(|s| s.host.is_some());
+    let daemon_sessions: HashMap<String, _> = local_sessions
+        .into_iter()
+        .map(|s| (s.cwd.clone(), s))
+        .collect();
+    let mut daemon_alerts: HashMap<(TmuxServer, String), ResourceAlert> = alerts
+        .into_iter()
+        .map(|a| ((a.server.clone(), a.session.clone()), a))
+        .collect();
+
+    let using_daemon = !daemon_sessions.is_empty();
+    if using_daemon {
+        debug_log(&format!(
+            "REFRESH: Using daemon state for {} sessions (by cwd)",
+            daemon_sessions.len()
+        ));
+    }
+    let mut session_infos = Vec::new();
+
+    for session in tmux_sessions {
+        if !matches_filter(&session.name, filter) {
+            continue;
+        }
+
+        // Get session CWD from first pane
+        let session_cwd = session
+            .windows
+            .first()
+            .and_then(|w| w.panes.first())
+            .map(|p| p.cwd.clone());
+
+        // Calculate session totals and collect per-process info
+        let all_pids = get_session_pids(sys, &session);
+
+        let mut total_cpu = 0.0;
+        let mut total_mem_kb = 0u64;
+        let mut processes: Vec<ProcessInfo> = Vec::new();
+
+        for &pid in &all_pids {
+            if let Some(info) = get_process_info(sys, pid) {
+                total_cpu += info.cpu_percent;
+                total_mem_kb += info.memory_kb;
+                processes.push(info);
+            }
+        }
+        // Keep processes with >0 CPU or >1MB memory, and their parents
+        retain_with_ancestors(&mut processes, |p| {
+            p.cpu_percent > 0.0 || p.memory_kb >= 1024
+        });
+
+        // Sort processes by CPU descending
+        processes.sort_by(|a, b| {
+            b.cpu_percent
+                .partial_cmp(&a.cpu_percent)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        // Detect listening ports for all PIDs in this session
+        let listening_ports = if with_ports {
+            get_listening_ports_for_pids(&all_pids, sys)
+        } else {
+            Vec::new()
+        };
+
+        // Find Claude pane: check daemon state by cwd, or detect Claude process
+        let mut claude_status: Option<ClaudeStatus> = None;
+        let mut claude_pane: Option<(String, String, String)> = None;
+        let mut last_activity = None;
+        let mut daemon_session_id = None;
+        let mut transcript_path = None;
+        let mut agent_todos = Vec::new();
+        let mut risk = None;
+        let mut pending_tool = None;
+        let mut usage = None;
+
+        'outer: for window in &session.windows {
+            for p in &window.panes {
+                // Check if a Claude process is actually running in this pane (a
+                // crashed one is still shown, from the daemon's state)
+                let has_claude_process = pane_runs_claude(sys, p.pid)
+                    || daemon_sessions.get(&p.cwd).is_some_and(|s| {
+                        s.status == SessionStatus::Crashed && s.tmux_pane_id == p.id
+                    });
+
+                if has_claude_process {
+                    // Use daemon state if available (richer status info)
+                    if let Some(daemon_state) = daemon_sessions.get(&p.cwd) {
+                        claude_status = Some(convert_daemon_status(&daemon_state.status));
+                        daemon_session_id = Some(daemon_state.session_id.clone());
+                        transcript_path = daemon_state.transcript_path.clone();
+                        agent_todos = daemon_state.agent_todos.to_vec();
+                        risk = daemon_state.risk.clone();
+                        pending_tool = daemon_state.pending_tool.as_deref().cloned();
+                        usage = daemon_state.usage.as_deref().cloned();
+                        last_activity = daemon_state
+                            .last_activity
+                            .as_ref()
+                            .and_then(|s| parse_timestamp(s));
+                    } else {
+                        // Claude running but no daemon state yet - show as working
+                        claude_status = Some(ClaudeStatus::Unknown);
+                    }
+                    claude_pane =
+                        Some((session.name.clone(), window.index.clone(), p.index.clone()));
+                    break 'outer;
+                }
+            }
+        }
+
+        let git = session_cwd
+            .as_ref()
+            .and_then(|cwd| git_statuses.get(cwd))
+            .cloned();
+        session_infos.push(SessionInfo {
+            name: session.name.clone(),
+            server: session.server.clone(),
+            claude_status,
+            claude_pane,
+            permission_key: None, // Will be assigned after sorting
+            total_cpu,
+            total_mem_kb,
+            last_activity,
+            processes,
+            cwd: session_cwd,
+            listening_ports,
+            resource_alert: daemon_alerts.remove(&(session.server.clone(), session.name.clone())),
+            host: None,
+            daemon_session_id,
+            transcript_path,
+            agent_todos,
+            git,
+            risk,
+            pending_tool,
+            usage,
+        });
+    }
+
+    // Sessions from remote daemons have no local tmux panes; list them as "host:name"
+    for remote in remote_sessions {
+        let host = remote.host.clone().unwrap_or_default();
+        let label = if remote.tmux_session.is_empty() {
+            remote
+                .cwd
+                .rsplit('/')
+                .next()
+                .unwrap_or(&remote.cwd)
+                .to_string()
+        } else {
+            remote.tmux_session.clone()
+        };
+        let name = format!("{}:{}", host, label);
+        if !matches_filter(&name, filter) {
+            continue;
+        }
+        session_infos.push(SessionInfo {
+            name,
+            server: TmuxServer::Current,
+            claude_status: Some(convert_daemon_status(&remote.status)),
+            claude_pane: None,
+            permission_key: None,
+            total_cpu: remote.cpu_percent,
+            total_mem_kb: remote.memory_kb,
+            last_activity: remote.last_activity.as_deref().and_then(parse_timestamp),
+            processes: Vec::new(),
+            cwd: Some(remote.cwd),
+            listening_ports: Vec::new(),
+            resource_alert: None,
+            host: Some(host),
+            daemon_session_id: Some(remote.session_id),
+            transcript_path: None,
+            agent_todos: remote.agent_todos.into_vec(),
+            git: None,
+            risk: remote.risk,
+            pending_tool: remote.pending_tool.map(|tool| *tool),
+            usage: remote.usage.map(|usage| *usage),
+        });
+    }
+    session_infos
+}
+
+/// Sort the list: Claude (non-skipped) → non-Claude (non-skipped) → skipped, local
+/// before remote hosts, grouped by tmux server (discovery order) within each section
+pub fn sort_sessions(session_infos: &mut [SessionInfo], skipped: &HashSet<String>) {
+    // Within each group, stable sort preserves original order
+    let mut server_order: Vec<TmuxServer> = Vec::new();
+    for info in session_infos.iter() {
+        if !server_order.contains(&info.server) {
+            server_order.push(info.server.clone());
+        }
+    }
+    session_infos.sort_by_key(|s| {
+        let server_rank = server_order.iter().position(|srv| *srv == s.server);
+        let is_skipped = skipped.contains(&s.key());
+        (
+            is_skipped,
+            s.claude_status.is_none(),
+            s.host.clone(),
+            server_rank,
+        )
+    });
+}
+
+/// Give sessions with an open prompt a permission key, keeping keys already handed
+/// out (`key_map`) and dropping pending approvals Claude has processed
+pub fn assign_permission_keys(
+    session_infos: &mut [SessionInfo],
+    key_map: &mut HashMap<String, char>,
+    pending_approvals: &mut HashSet<String>,
+) {
+    // Stable permission key assignment
+    // 1. Determine which sessions need permission (excluding pending approvals)
+    let sessions_needing_permission: HashSet<String> = session_infos
+        .iter()
+        .filter(|s| {
+            !pending_approvals.contains(&s.key())
+                && matches!(
+                    s.claude_status,
+                    Some(ClaudeStatus::NeedsPermission(_, _)) | Some(ClaudeStatus::EditApproval(_))
+                )
+        })
+        .map(|s| s.key())
+        .collect();
+
+    // 2. Clean up pending approvals for sessions that no longer need permission
+    //    (Claude has processed the approval)
+    pending_approvals.retain(|key| {
+        session_infos.iter().any(|s| {
+            s.key() == *key
+                && matches!(
+                    s.claude_status,
+                    Some(ClaudeStatus::NeedsPermission(_, _)) | Some(ClaudeStatus::EditApproval(_))
+                )
+        })
+    });
+
+    // 3. Remove sessions that no longer need permission from the key map
+    key_map.retain(|key, _| sessions_needing_permission.contains(key));
+
+    // 4. Get currently used keys and find available keys
+    let used_keys: HashSet<char> = key_map.values().copied().collect();
+    let mut available_keys: Vec<char> = PERMISSION_KEYS
+        .iter()
+        .filter(|k| !used_keys.contains(k))
+        .copied()
+        .collect();
+
+    // 5. Assign keys to sessions that need permission
+    for session in session_infos.iter_mut() {
+        let key = session.key();
+        if sessions_needing_permission.contains(&key) {
+            if let Some(&existing_key) = key_map.get(&key) {
+                // Already has a key, use it
+                session.permission_key = Some(existing_key);
+            } else if let Some(new_key) = available_keys.pop() {
+                // Assign first available key
+                key_map.insert(key, new_key);
+                session.permission_key = Some(new_key);
+            }
+            // else: no more keys available, permission_key stays None
+        }
+    }
+}
+
+/// Approve a session's permission prompt: keys go to the local pane, remote sessions
+/// are approved through the daemon. "Always" only exists for Bash-style prompts.
+pub fn approve_session(
+    session_info: &SessionInfo,
+    always: bool,
+    daemon_client: Option<&mut DaemonClient>,
+) -> bool {
+    let always = always
+        && matches!(
+            session_info.claude_status,
+            Some(ClaudeStatus::NeedsPermission(_, _))
+        );
+    if let Some((ref sess, ref win, ref pane)) = session_info.claude_pane {
+        let key = if always { "2" } else { "1" };
+        send_key_to_pane(&session_info.server, sess, win, pane, key);
+        send_key_to_pane(&session_info.server, sess, win, pane, "Enter");
+        true
+    } else if let Some(ref session_id) = session_info.daemon_session_id {
+        daemon_client
+            .map(|client| client.approve_permission(session_id, always))
+            .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Deny a session's permission prompt (Escape dismisses it)
+pub fn deny_session(session_info: &SessionInfo, daemon_client: Option<&mut DaemonClient>) -> bool {
+    if let Some((ref sess, ref win, ref pane)) = session_info.claude_pane {
+        send_key_to_pane(&session_info.server, sess, win, pane, "Escape");
+        true
+    } else if let Some(ref session_id) = session_info.daemon_session_id {
+        daemon_client
+            .map(|client| client.deny_permission(session_id))
+            .unwrap_or(false)
+    } else {
+        false
+    }
+}
+
+/// Type a message into a session's Claude pane and submit it (local sessions only)
+pub fn send_to_session(session_info: &SessionInfo, text: &str) -> Result<()> {
+    let Some((ref sess, ref win, ref pane)) = session_info.claude_pane else {
+        bail!("No local Claude pane in '{}'", session_info.name);
+    };
+    send_text_to_pane(&session_info.server, sess, win, pane, text);
+    send_key_to_pane(&session_info.server, sess, win, pane, "Enter");
+    Ok(())
+}
+
+/// Switch a tmux client to the session on its server
+pub fn focus_session(session_info: &SessionInfo) -> Result<()> {
+    if let Some(ref host) = session_info.host {
+        bail!("'{}' runs on remote host '{}'", session_info.name, host);
+    }
+    if !switch_to_session(&session_info.server, &session_info.name) {
+        bail!(
+            "No client on tmux server '{}' to switch to '{}'",
+            session_info.server.label(),
+            session_info.name
+        );
+    }
+    Ok(())
+}
+
+/// Find session index by permission key
+pub fn find_session_by_permission_key(sessions: &[SessionInfo], key: char) -> Option<usize> {
+    sessions
+        .iter()
+        .position(|s| s.permission_key == Some(key.to_ascii_lowercase()))
+}
+
+/// Find session index by tmux name ("host:name" for remote sessions), server-qualified
+/// key ("name.server", to pick between same-named sessions) or daemon session id
+pub fn find_session_by_name(sessions: &[SessionInfo], query: &str) -> Option<usize> {
+    sessions
+        .iter()
+        .position(|s| s.name == query)
+        .or_else(|| sessions.iter().position(|s| s.key() == query))
+        .or_else(|| {
+            sessions
+                .iter()
+                .position(|s| s.daemon_session_id.as_deref() == Some(query))
+        })
+}
+
+/// An open request listed for bulk approval, snapshotted when the dialog opens
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkRequest {
+    pub key: String,  // session key
+    pub name: String, // session name for display
+    pub session_id: Option<String>,
+    pub tool: Option<PendingTool>, // tool call from the daemon (with its request time)
+    pub prompt: String,            // command or file the prompt shows
+}
+
+impl BulkRequest {
+    /// Snapshot of a session's open permission or edit prompt
+    pub fn of(session: &SessionInfo) -> Option<Self> {
+        let prompt = match session.claude_status {
+            Some(ClaudeStatus::NeedsPermission(ref command, _)) => command.clone(),
+            Some(ClaudeStatus::EditApproval(ref file)) => format!("Edit: {}", file),
+            _ => return None,
+        };
+        Some(Self {
+            key: session.key(),
+            name: session.name.clone(),
+            session_id: session.daemon_session_id.clone(),
+            tool: session.pending_tool.clone(),
+            prompt,
+        })
+    }
+
+    /// Index of the session if it still shows exactly this request
+    pub fn current_index(&self, sessions: &[SessionInfo]) -> Option<usize> {
+        sessions
+            .iter()
+            .position(|s| s.key() == self.key)
+            .filter(|&idx| BulkRequest::of(&sessions[idx]).as_ref() == Some(self))
+    }
+}
+
+/// Whether a session shows a permission or edit prompt not answered yet
+fn awaits_approval(session: &SessionInfo, pending_approvals: &HashSet<String>) -> bool {
+    !pending_approvals.contains(&session.key())
+        && matches!(
+            session.claude_status,
+            Some(ClaudeStatus::NeedsPermission(_, _)) | Some(ClaudeStatus::EditApproval(_))
+        )
+}
+
+/// Why a session's open request must not be approved without a human (high or unknown risk)
+fn approval_blocker(session: &SessionInfo) -> Option<String> {
+    match session.risk {
+        Some(ref risk) if risk.blocks_bulk_approve() => {
+            Some(format!("{} risk: {}", risk.level.label(), risk.summary()))
+        }
+        Some(_) => None,
+        None => Some("risk unknown".to_string()),
     }
 }
 
-/// Find session by permission key
-pub fn find_session_by_permission_key(sessions: &[SessionInfo], key: char) -> Option<&SessionInfo> {
+/// Open requests of read-only tools (Read, Grep, ...)
+fn read_only_requests(
+    sessions: &[SessionInfo],
+    pending_approvals: &HashSet<String>,
+) -> Vec<BulkRequest> {
     sessions
         .iter()
-        .find(|s| s.permission_key == Some(key.to_ascii_lowercase()))
+        .filter(|s| {
+            awaits_approval(s, pending_approvals)
+                && s.pending_tool
+                    .as_ref()
+                    .is_some_and(|tool| READ_ONLY_TOOLS.contains(&tool.tool_name.as_str()))
+        })
+        .filter_map(BulkRequest::of)
+        .collect()
 }
 
 /// Convert daemon SessionStatus to TUI ClaudeStatus
@@ -859,6 +2219,12 @@ fn convert_daemon_status(status: &SessionStatus) -> ClaudeStatus {
         SessionStatus::EditApproval { filename } => ClaudeStatus::EditApproval(filename.clone()),
         SessionStatus::PlanReview => ClaudeStatus::PlanReview,
         SessionStatus::QuestionAsked => ClaudeStatus::QuestionAsked,
+        SessionStatus::Stalled => ClaudeStatus::Stalled,
+        SessionStatus::Crashed => ClaudeStatus::Crashed,
+        SessionStatus::RateLimited { resets_at } => {
+            ClaudeStatus::RateLimited(resets_at.as_deref().and_then(parse_timestamp))
+        }
+        SessionStatus::ApiError { message } => ClaudeStatus::ApiError(message.clone()),
         SessionStatus::Working => ClaudeStatus::Unknown,
         SessionStatus::Unknown => ClaudeStatus::Unknown,
     }
@@ -870,3 +2236,234 @@ fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
         .ok()
         .map(|dt| dt.with_timezone(&Utc))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::risk::{classify_command, Risk};
+
+    fn session(name: &str, server: TmuxServer, daemon_session_id: Option<&str>) -> SessionInfo {
+        SessionInfo {
+            name: name.to_string(),
+            server,
+            daemon_session_id: daemon_session_id.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Local session with an open permission request for `tool`
+    fn prompting(name: &str, tool: &str, requested_at: &str) -> SessionInfo {
+        SessionInfo {
+            name: name.to_string(),
+            claude_status: Some(ClaudeStatus::NeedsPermission(format!("{} src", tool), None)),
+            daemon_session_id: Some(format!("id-{}", name)),
+            pending_tool: Some(PendingTool {
+                tool_name: tool.to_string(),
+                tool_input: serde_json::json!({"path": "src"}),
+                requested_at: Some(requested_at.to_string()),
+            }),
+            risk: Some(Risk::default()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_read_only_requests() {
+        let mut idle = prompting("idle", "Read", "t1");
+        idle.claude_status = Some(ClaudeStatus::Waiting);
+        let sessions = vec![
+            prompting("read", "Read", "t1"),
+            prompting("bash", "Bash", "t1"),
+            prompting("grep", "Grep", "t1"),
+            prompting("answered", "Glob", "t1"),
+            idle,
+        ];
+        let pending: HashSet<String> = ["answered".to_string()].into();
+        let names: Vec<String> = read_only_requests(&sessions, &pending)
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["read", "grep"]);
+    }
+
+    #[test]
+    fn test_approval_blocker() {
+        let mut session = prompting("api", "Bash", "t1");
+        assert_eq!(approval_blocker(&session), None);
+        session.risk = None;
+        assert_eq!(approval_blocker(&session).as_deref(), Some("risk unknown"));
+        session.risk = Some(classify_command("rm -rf build", Path::new("/work"), &[]));
+        assert_eq!(
+            approval_blocker(&session).as_deref(),
+            Some("high risk: destructive")
+        );
+        session.risk = Some(classify_command("rm notes.txt", Path::new("/work"), &[]));
+        assert_eq!(approval_blocker(&session), None);
+    }
+
+    #[test]
+    fn test_bulk_request_snapshot_must_still_match() {
+        let mut sessions = vec![
+            prompting("api", "Read", "t1"),
+            prompting("web", "Read", "t1"),
+        ];
+        let snapshot = BulkRequest::of(&sessions[1]).unwrap();
+        assert_eq!(snapshot.current_index(&sessions), Some(1));
+
+        // Reordered list: found at its new position
+        sessions.swap(0, 1);
+        assert_eq!(snapshot.current_index(&sessions), Some(0));
+
+        // Same tool call asked again later is a different request
+        sessions[0] = prompting("web", "Read", "t2");
+        assert_eq!(snapshot.current_index(&sessions), None);
+
+        // Another tool call, or the prompt answered, no longer matches either
+        sessions[0] = prompting("web", "Grep", "t1");
+        assert_eq!(snapshot.current_index(&sessions), None);
+        sessions[0] = prompting("web", "Read", "t1");
+        sessions[0].claude_status = Some(ClaudeStatus::Waiting);
+        assert_eq!(snapshot.current_index(&sessions), None);
+
+        // Gone entirely
+        sessions.remove(0);
+        assert_eq!(snapshot.current_index(&sessions), None);
+        assert!(BulkRequest::of(&sessions[0]).is_some());
+    }
+
+    #[test]
+    fn test_find_session_by_name_unknown() {
+        let sessions = vec![session("api", TmuxServer::Current, Some("abc"))];
+        assert_eq!(find_session_by_name(&sessions, "web"), None);
+        assert_eq!(find_session_by_name(&sessions, ""), None);
+        assert_eq!(find_session_by_name(&[], "api"), None);
+    }
+
+    #[test]
+    fn test_find_session_by_name_duplicate_names() {
+        let sessions = vec![
+            session("api", TmuxServer::Current, None),
+            session("api", TmuxServer::Named("work".to_string()), None),
+        ];
+        // The bare name resolves to the first listed session...
+        assert_eq!(find_session_by_name(&sessions, "api"), Some(0));
+        // ...and the server-qualified key picks the other one
+        assert_eq!(find_session_by_name(&sessions, "api.work"), Some(1));
+    }
+
+    #[test]
+    fn test_find_session_by_name_prefers_name_over_session_id() {
+        let sessions = vec![
+            session("other", TmuxServer::Current, Some("api")),
+            session("api", TmuxServer::Current, Some("xyz")),
+        ];
+        assert_eq!(find_session_by_name(&sessions, "api"), Some(1));
+        assert_eq!(find_session_by_name(&sessions, "xyz"), Some(1));
+    }
+
+    #[test]
+    fn test_find_session_by_name_remote() {
+        let mut remote = session("devbox:api", TmuxServer::Current, Some("r1"));
+        remote.host = Some("devbox".to_string());
+        let sessions = vec![session("api", TmuxServer::Current, Some("l1")), remote];
+        assert_eq!(find_session_by_name(&sessions, "devbox:api"), Some(1));
+        assert_eq!(find_session_by_name(&sessions, "r1"), Some(1));
+        // The remote's tmux name alone matches the local session, not the remote one
+        assert_eq!(find_session_by_name(&sessions, "api"), Some(0));
+        assert_eq!(find_session_by_name(&sessions, "devbox"), None);
+    }
+
+    #[test]
+    fn test_sort_and_permission_keys_for_shared_session_list() {
+        let prompt = |name: &str| {
+            let mut info = session(name, TmuxServer::Current, None);
+            info.claude_status = Some(ClaudeStatus::EditApproval("lib.rs".to_string()));
+            info
+        };
+        let mut sessions = vec![
+            session("shell", TmuxServer::Current, None),
+            prompt("skipped"),
+            prompt("api"),
+            prompt("web"),
+        ];
+        let skipped: HashSet<String> = [sessions[1].key()].into();
+        sort_sessions(&mut sessions, &skipped);
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["api", "web", "shell", "skipped"]);
+
+        // Keys already handed out stay put; approvals Claude processed are dropped
+        let mut key_map = HashMap::from([(sessions[1].key(), 'y')]);
+        let mut pending = HashSet::from([sessions[0].key(), "gone".to_string()]);
+        assign_permission_keys(&mut sessions, &mut key_map, &mut pending);
+        assert_eq!(sessions[0].permission_key, None);
+        assert_eq!(sessions[1].permission_key, Some('y'));
+        assert_eq!(sessions[2].permission_key, None);
+        assert!(sessions[3].permission_key.is_some());
+        assert_eq!(pending, HashSet::from([sessions[0].key()]));
+    }
+
+    /// One JSONL line: a user prompt, or an assistant reply mentioning `text`
+    fn transcript_line(user: bool, text: &str) -> String {
+        let role = if user { "user" } else { "assistant" };
+        serde_json::json!({"type": role, "message": {"role": role, "content": text}}).to_string()
+    }
+
+    fn write_transcript(path: &Path, lines: &[String]) {
+        std::fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn test_transcript_view_search_and_reload() {
+        let dir = std::env::temp_dir().join(format!("tmux-claude-view-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("abc.jsonl");
+        let mut lines = vec![
+            transcript_line(true, "fix the Parser"),
+            transcript_line(false, "parser fixed"),
+            transcript_line(true, "now the lexer"),
+            transcript_line(false, "lexer done, parser untouched"),
+        ];
+        write_transcript(&path, &lines);
+
+        let mut view = TranscriptView::load(path.clone(), "api".into(), "/work".into()).unwrap();
+        assert_eq!(view.selected, 2); // latest prompt
+
+        // Case-insensitive, starting at the selection and wrapping to the first match
+        assert!(view.search("PARSER"));
+        assert_eq!(view.matches, vec![0, 1, 3]);
+        assert_eq!(view.selected, 3);
+        view.next_match(true);
+        assert_eq!(view.selected, 0);
+        view.next_match(false);
+        assert_eq!(view.selected, 3);
+        view.next_match(false);
+        assert_eq!(view.selected, 1);
+        assert!(!view.search("tokenizer"));
+        assert_eq!(view.selected, 1);
+
+        // Reloading re-runs the search and keeps a selection that isn't at the end
+        view.search("parser");
+        view.selected = 1;
+        view.expanded.insert(3);
+        lines.push(transcript_line(true, "parser again"));
+        write_transcript(&path, &lines);
+        view.reload().unwrap();
+        assert_eq!(view.items.len(), 5);
+        assert_eq!(view.matches, vec![0, 1, 3, 4]);
+        assert_eq!(view.selected, 1);
+
+        // At the end, the selection follows new items; expansions past the end are dropped
+        view.jump_to_end();
+        lines.truncate(3);
+        write_transcript(&path, &lines);
+        view.reload().unwrap();
+        assert_eq!(view.selected, 2);
+        assert!(view.expanded.is_empty());
+        lines.push(transcript_line(false, "all done"));
+        write_transcript(&path, &lines);
+        view.reload().unwrap();
+        assert_eq!(view.selected, 3);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
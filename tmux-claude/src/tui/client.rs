@@ -1,10 +1,21 @@
 //! TUI client for daemon communication.
 
-use crate::ipc::messages::{get_socket_path, DaemonCommand, DaemonResponse, MetricsHistory, SessionState};
+use crate::common::git::GitStatus;
+use crate::common::launch::LaunchedSession;
+use crate::common::transcript::TranscriptSummary;
+use crate::common::transcript_index::TranscriptHit;
+use crate::common::types::TmuxSession;
+use crate::ipc::messages::{
+    get_socket_path, DaemonCommand, DaemonResponse, MetricsHistory, ResourceAlert, SessionState,
+};
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
 use std::time::Duration;
 
+/// Read timeout while waiting for transcript search results
+const SEARCH_READ_TIMEOUT: Duration = Duration::from_secs(2);
+
 /// Client for communicating with the daemon
 pub struct DaemonClient {
     stream: Option<UnixStream>,
@@ -78,10 +89,23 @@ impl DaemonClient {
         }
     }
 
-    /// Get current state with metrics from daemon
-    pub fn get_state_with_metrics(&mut self) -> Option<(Vec<SessionState>, Option<MetricsHistory>)> {
+    /// Get current state with metrics and alerts from daemon
+    pub fn get_snapshot(&mut self) -> Option<DaemonSnapshot> {
         match self.send_command(DaemonCommand::GetState)? {
-            DaemonResponse::State { sessions, metrics, .. } => Some((sessions, metrics)),
+            DaemonResponse::State {
+                sessions,
+                metrics,
+                alerts,
+                tmux_sessions,
+                git,
+                ..
+            } => Some(DaemonSnapshot {
+                sessions,
+                metrics,
+                alerts,
+                tmux_sessions,
+                git,
+            }),
             _ => None,
         }
     }
@@ -95,6 +119,56 @@ impl DaemonClient {
         matches!(self.send_command(command), Some(DaemonResponse::Ok))
     }
 
+    /// Deny a permission request
+    pub fn deny_permission(&mut self, session_id: &str) -> bool {
+        let command = DaemonCommand::DenyPermission {
+            session_id: session_id.to_string(),
+        };
+        matches!(self.send_command(command), Some(DaemonResponse::Ok))
+    }
+
+    /// Register a just-started Claude session
+    pub fn register_session(&mut self, launched: &LaunchedSession, prompted: bool) -> bool {
+        let command = DaemonCommand::RegisterSession {
+            cwd: launched.cwd.to_string_lossy().to_string(),
+            tmux_session: launched.session.clone(),
+            tmux: launched.location.clone(),
+            prompted,
+        };
+        matches!(self.send_command(command), Some(DaemonResponse::Ok))
+    }
+
+    /// Search the daemon's transcript index
+    pub fn search_transcripts(&mut self, query: &str, limit: usize) -> Option<Vec<TranscriptHit>> {
+        let command = DaemonCommand::SearchTranscripts {
+            query: query.to_string(),
+            limit,
+        };
+        // Searching a large index can take longer than the usual read timeout allows
+        if let Some(stream) = self.stream.as_ref() {
+            let _ = stream.set_read_timeout(Some(SEARCH_READ_TIMEOUT));
+        }
+        let response = self.send_command(command);
+        if let Some(stream) = self.stream.as_ref() {
+            let _ = stream.set_read_timeout(Some(Duration::from_millis(100)));
+        }
+        match response? {
+            DaemonResponse::TranscriptHits { hits } => Some(hits),
+            _ => None,
+        }
+    }
+
+    /// Past conversations of the project in `cwd`, from the daemon's transcript index
+    pub fn list_transcripts(&mut self, cwd: &str) -> Option<Vec<TranscriptSummary>> {
+        let command = DaemonCommand::ListTranscripts {
+            cwd: cwd.to_string(),
+        };
+        match self.send_command(command)? {
+            DaemonResponse::Transcripts { transcripts } => Some(transcripts),
+            _ => None,
+        }
+    }
+
     /// Check daemon status
     pub fn status(&mut self) -> Option<DaemonStatus> {
         match self.send_command(DaemonCommand::Status)? {
@@ -115,7 +189,10 @@ impl DaemonClient {
 
     /// Ping daemon for health check
     pub fn ping(&mut self) -> bool {
-        matches!(self.send_command(DaemonCommand::Ping), Some(DaemonResponse::Pong))
+        matches!(
+            self.send_command(DaemonCommand::Ping),
+            Some(DaemonResponse::Pong)
+        )
     }
 }
 
@@ -125,6 +202,18 @@ impl Default for DaemonClient {
     }
 }
 
+/// Full daemon state as returned by GetState
+#[derive(Debug, Default)]
+pub struct DaemonSnapshot {
+    pub sessions: Vec<SessionState>,
+    pub metrics: Option<MetricsHistory>,
+    pub alerts: Vec<ResourceAlert>,
+    /// tmux topology from the daemon's control-mode client
+    pub tmux_sessions: Option<Vec<TmuxSession>>,
+    /// Git status by cwd
+    pub git: HashMap<String, GitStatus>,
+}
+
 /// Daemon status information
 #[derive(Debug)]
 pub struct DaemonStatus {
@@ -1,19 +1,28 @@
 //! TUI rendering functions.
 
+use crate::common::agent_todos::{todo_progress, TodoStatus};
+use crate::common::diff::DiffKind;
+use crate::common::process::{flatten_process_tree, signal_name, RENICE_NICENESS};
+use crate::common::risk::{Risk, RiskLevel};
+use crate::common::transcript::{TranscriptItem, TranscriptKind};
+use crate::common::transcript_index::DocKind;
 use crate::common::types::{
     format_duration_ago, format_memory, format_rate, lines_for_session, truncate_command,
-    ClaudeStatus,
+    ClaudeStatus, SessionInfo, PROC_CPU_HIGH, PROC_CPU_WARN, PROC_MEM_HIGH_KB, PROC_MEM_WARN_KB,
+    SESSION_CPU_HIGH, SESSION_CPU_WARN, SESSION_MEM_HIGH_KB, SESSION_MEM_WARN_KB,
 };
-use crate::ipc::messages::MetricsHistory;
-use crate::tui::app::{App, InputMode, SearchResult};
+use crate::common::usage::{format_tokens, SessionUsage};
+use crate::ipc::messages::{AlertKind, MetricsHistory, ResourceAlert};
+use crate::tui::app::{App, InputMode, SearchResult, SearchScope};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols::Marker,
     text::{Line, Span},
-    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph},
+    widgets::{Axis, Block, Borders, Chart, Clear, Dataset, GraphType, Paragraph, Wrap},
     Frame,
 };
+use std::collections::HashSet;
 
 /// Sparkline characters from lowest to highest
 const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
@@ -21,15 +30,24 @@ const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '
 /// Width of the stats sidebar (doubled for sparklines)
 const STATS_SIDEBAR_WIDTH: u16 = 48;
 
+/// Fewest diff lines shown for a pending edit, however short the terminal
+const MIN_DIFF_ROWS: usize = 8;
+
+/// Lines of a tool call or result shown before it is folded in the transcript
+const TRANSCRIPT_FOLDED_LINES: usize = 3;
+
 /// Build the ratatui UI
 pub fn ui(frame: &mut Frame, app: &mut App) {
     app.clear_old_error();
+    app.update_edit_preview();
     let area = frame.area();
 
     // Sidebar: show if enabled and terminal is wide enough
     let show_sidebar = app.show_stats && area.width >= 80;
     let (content_area, sidebar_area) = if show_sidebar {
-        let h_chunks = Layout::horizontal([Constraint::Min(40), Constraint::Length(STATS_SIDEBAR_WIDTH)]).split(area);
+        let h_chunks =
+            Layout::horizontal([Constraint::Min(40), Constraint::Length(STATS_SIDEBAR_WIDTH)])
+                .split(area);
         (h_chunks[0], Some(h_chunks[1]))
     } else {
         (area, None)
@@ -49,7 +67,14 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
     // --- Header ---
     let now = chrono::Local::now();
     let title = if app.input_mode == InputMode::Search {
-        "tmux-claude [SEARCH]".to_string()
+        match app.search_scope {
+            SearchScope::Sessions => "tmux-claude [SEARCH]".to_string(),
+            SearchScope::Transcripts => "tmux-claude [SEARCH transcripts]".to_string(),
+        }
+    } else if let Some(ref view) = app.transcript {
+        format!("tmux-claude [{} · transcript]", view.title)
+    } else if let Some(ref history) = app.history {
+        format!("tmux-claude [{} · history]", history.title)
     } else if app.showing_detail.is_some() {
         if let Some(name) = app.detail_session_name() {
             format!("tmux-claude [{}]", name)
@@ -65,9 +90,19 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
     };
     // Mode indicator (standalone vs daemon)
     let mode_indicator = if app.daemon_connected {
-        Span::styled("[daemon]", Style::default().fg(Color::Green).add_modifier(Modifier::DIM))
+        Span::styled(
+            "[daemon]",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::DIM),
+        )
     } else {
-        Span::styled("[standalone]", Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM))
+        Span::styled(
+            "[standalone]",
+            Style::default()
+                .fg(Color::DarkGray)
+                .add_modifier(Modifier::DIM),
+        )
     };
 
     let mut header_spans = vec![
@@ -98,12 +133,29 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
     // --- Main content: session list, parked list, search, or detail view ---
     if app.input_mode == InputMode::Search {
         render_search_view(frame, app, chunks[1]);
+    } else if app.transcript.is_some() {
+        render_transcript_view(frame, app, chunks[1]);
+    } else if app.history.is_some() {
+        render_history_view(frame, app, chunks[1]);
     } else if app.showing_detail.is_some() {
         render_detail_view(frame, app, chunks[1]);
         if app.input_mode == InputMode::ParkNote {
             render_input_modal(frame, app, chunks[1], "Park", "park", Color::Yellow);
         } else if app.input_mode == InputMode::AddTodo {
             render_input_modal(frame, app, chunks[1], "Add Todo", "add", Color::Cyan);
+        } else if matches!(
+            app.input_mode,
+            InputMode::ProcessAction | InputMode::ConfirmSignal
+        ) {
+            render_process_action_modal(frame, app, chunks[1]);
+        } else if app.input_mode == InputMode::ConfirmKill {
+            let name = app.detail_session_name().unwrap_or_default();
+            render_confirm_modal(
+                frame,
+                chunks[1],
+                " Kill session ",
+                &format!("Kill tmux session '{}'?", name),
+            );
         }
     } else if app.showing_parked_detail.is_some() {
         render_parked_detail_view(frame, app, chunks[1]);
@@ -111,15 +163,38 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
         render_parked_view(frame, app, chunks[1]);
     } else {
         render_session_list(frame, app, chunks[1]);
+        if app.input_mode == InputMode::ConfirmBulkApprove {
+            render_bulk_approve_modal(frame, app, chunks[1]);
+        } else if app.input_mode == InputMode::NewSession {
+            render_input_modal(
+                frame,
+                app,
+                chunks[1],
+                "New Claude: <dir|project>[@branch] [prompt]",
+                "start",
+                Color::Green,
+            );
+        } else if app.input_mode == InputMode::ConfirmRemoveWorktree {
+            if let Some(worktree) = app.pending_worktree() {
+                render_confirm_modal(
+                    frame,
+                    chunks[1],
+                    " Remove worktree ",
+                    &format!(
+                        "Remove clean, merged worktree {} and branch '{}'?",
+                        worktree.path.display(),
+                        worktree.branch
+                    ),
+                );
+            }
+        }
     }
 
     // --- Error message ---
     if let Some((ref msg, _)) = app.error_message {
         let error_line = Line::from(Span::styled(
             msg.clone(),
-            Style::default()
-                .fg(Color::Red)
-                .add_modifier(Modifier::BOLD),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
         ));
         frame.render_widget(Paragraph::new(error_line), chunks[2]);
     }
@@ -134,18 +209,84 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
             Span::raw("  "),
             Span::styled("[Enter]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("select "),
+            Span::styled("[Tab]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(match app.search_scope {
+                SearchScope::Sessions => "transcripts ",
+                SearchScope::Transcripts => "sessions ",
+            }),
             Span::styled("[Esc]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("cancel"),
         ])
+    } else if app.input_mode == InputMode::TranscriptSearch {
+        Line::from(vec![
+            Span::styled("/", Style::default().fg(Color::Yellow)),
+            Span::styled(&app.input_buffer, Style::default().fg(Color::Yellow)),
+            Span::styled("█", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+            Span::raw("  "),
+            Span::styled("[Enter]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("search "),
+            Span::styled("[Esc]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("cancel"),
+        ])
+    } else if let Some(ref view) = app.transcript {
+        // Transcript view footer
+        let mut spans = vec![
+            Span::styled("[↑↓]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("move "),
+            Span::styled("[Space]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("expand "),
+            Span::styled("[/]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("search "),
+        ];
+        if !view.matches.is_empty() {
+            spans.extend([
+                Span::styled("[n/N]", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw("match "),
+            ]);
+        }
+        spans.extend([
+            Span::styled("[G]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("latest turn "),
+            Span::styled("[C]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("ontinue "),
+            Span::styled("[R]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("eload "),
+            Span::styled("[Esc]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("back"),
+        ]);
+        Line::from(spans)
+    } else if app.history.is_some() {
+        // History view footer
+        Line::from(vec![
+            Span::styled("[↑↓]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("select "),
+            Span::styled("[Enter]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("resume "),
+            Span::styled("[T]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("ranscript "),
+            Span::styled("[Esc]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("back"),
+        ])
     } else if app.showing_detail.is_some() {
         // Detail view footer
-        Line::from(vec![
+        let mut spans = Vec::new();
+        if app.detail_edit_preview().is_some() {
+            spans.extend([
+                Span::styled("[Y]", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw("approve edit "),
+                Span::styled("[PgUp/PgDn]", Style::default().add_modifier(Modifier::BOLD)),
+                Span::raw("scroll diff "),
+            ]);
+        }
+        spans.extend([
             Span::styled("[A]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("dd todo "),
             Span::styled("[D]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("elete "),
             Span::styled("[Enter]", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw("switch "),
+            Span::raw("switch/act "),
+            Span::styled("[Space]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("fold "),
             Span::styled("[P]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("ark "),
             Span::styled("[!]", Style::default().add_modifier(Modifier::BOLD)),
@@ -154,11 +295,18 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
             Span::raw("ute "),
             Span::styled("[S]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("kip "),
+            Span::styled("[T]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("ranscript "),
+            Span::styled("[R]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("esume… "),
+            Span::styled("[X]", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw("kill "),
             Span::styled("[Esc]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("back "),
             Span::styled("[Q]", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw("uit"),
-        ])
+        ]);
+        Line::from(spans)
     } else if app.showing_parked_detail.is_some() {
         // Parked detail view footer
         Line::from(vec![
@@ -206,6 +354,31 @@ pub fn ui(frame: &mut Frame, app: &mut App) {
                 Style::default().add_modifier(Modifier::DIM),
             ));
         }
+        spans.push(Span::styled(
+            "[Space]",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw("mark "));
+        if !app.marked_sessions.is_empty() {
+            spans.push(Span::styled(
+                "[A]",
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(format!("pprove({}) ", app.marked_sessions.len())));
+        }
+        let read_only = app.read_only_requests().len();
+        if read_only > 0 {
+            spans.push(Span::styled(
+                "[O]",
+                Style::default().add_modifier(Modifier::BOLD),
+            ));
+            spans.push(Span::raw(format!("k reads({}) ", read_only)));
+        }
+        spans.push(Span::styled(
+            "[N]",
+            Style::default().add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::raw("ew "));
         spans.push(Span::styled(
             "[R]",
             Style::default().add_modifier(Modifier::BOLD),
@@ -435,22 +608,14 @@ fn render_chart_metrics(frame: &mut Frame, area: Rect, metrics: &MetricsHistory)
                     Style::default().fg(cpu_color).add_modifier(Modifier::BOLD),
                 )),
         )
-        .x_axis(
-            Axis::default()
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("30m", Style::default().fg(Color::DarkGray)),
-                    Span::styled("now", Style::default().fg(Color::DarkGray)),
-                ]),
-        )
-        .y_axis(
-            Axis::default()
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("0%", Style::default().fg(Color::DarkGray)),
-                    Span::styled("100%", Style::default().fg(Color::DarkGray)),
-                ]),
-        );
+        .x_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+            Span::styled("30m", Style::default().fg(Color::DarkGray)),
+            Span::styled("now", Style::default().fg(Color::DarkGray)),
+        ]))
+        .y_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+            Span::styled("0%", Style::default().fg(Color::DarkGray)),
+            Span::styled("100%", Style::default().fg(Color::DarkGray)),
+        ]));
     frame.render_widget(cpu_chart, chunks[0]);
 
     // MEM Chart
@@ -474,22 +639,14 @@ fn render_chart_metrics(frame: &mut Frame, area: Rect, metrics: &MetricsHistory)
                     Style::default().fg(mem_color).add_modifier(Modifier::BOLD),
                 )),
         )
-        .x_axis(
-            Axis::default()
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("30m", Style::default().fg(Color::DarkGray)),
-                    Span::styled("now", Style::default().fg(Color::DarkGray)),
-                ]),
-        )
-        .y_axis(
-            Axis::default()
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("0%", Style::default().fg(Color::DarkGray)),
-                    Span::styled("100%", Style::default().fg(Color::DarkGray)),
-                ]),
-        );
+        .x_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+            Span::styled("30m", Style::default().fg(Color::DarkGray)),
+            Span::styled("now", Style::default().fg(Color::DarkGray)),
+        ]))
+        .y_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+            Span::styled("0%", Style::default().fg(Color::DarkGray)),
+            Span::styled("100%", Style::default().fg(Color::DarkGray)),
+        ]));
     frame.render_widget(mem_chart, chunks[1]);
 
     // NET Chart (RX and TX combined)
@@ -537,26 +694,27 @@ fn render_chart_metrics(frame: &mut Frame, area: Rect, metrics: &MetricsHistory)
                 .borders(Borders::TOP | Borders::LEFT)
                 .border_style(Style::default().fg(Color::DarkGray))
                 .title(Span::styled(
-                    format!(" NET ↓{} ↑{} ", format_rate(rx_current), format_rate(tx_current)),
-                    Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+                    format!(
+                        " NET ↓{} ↑{} ",
+                        format_rate(rx_current),
+                        format_rate(tx_current)
+                    ),
+                    Style::default()
+                        .fg(Color::White)
+                        .add_modifier(Modifier::BOLD),
                 )),
         )
-        .x_axis(
-            Axis::default()
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("30m", Style::default().fg(Color::DarkGray)),
-                    Span::styled("now", Style::default().fg(Color::DarkGray)),
-                ]),
-        )
-        .y_axis(
-            Axis::default()
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("0", Style::default().fg(Color::DarkGray)),
-                    Span::styled(format_rate(net_max as u64), Style::default().fg(Color::DarkGray)),
-                ]),
-        );
+        .x_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+            Span::styled("30m", Style::default().fg(Color::DarkGray)),
+            Span::styled("now", Style::default().fg(Color::DarkGray)),
+        ]))
+        .y_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+            Span::styled("0", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format_rate(net_max as u64),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ]));
     frame.render_widget(net_chart, chunks[2]);
 
     // TMP Chart (if available)
@@ -588,22 +746,14 @@ fn render_chart_metrics(frame: &mut Frame, area: Rect, metrics: &MetricsHistory)
                         Style::default().fg(temp_color).add_modifier(Modifier::BOLD),
                     )),
             )
-            .x_axis(
-                Axis::default()
-                    .bounds([0.0, 100.0])
-                    .labels(vec![
-                        Span::styled("30m", Style::default().fg(Color::DarkGray)),
-                        Span::styled("now", Style::default().fg(Color::DarkGray)),
-                    ]),
-            )
-            .y_axis(
-                Axis::default()
-                    .bounds([0.0, 100.0])
-                    .labels(vec![
-                        Span::styled("20°C", Style::default().fg(Color::DarkGray)),
-                        Span::styled("100°C", Style::default().fg(Color::DarkGray)),
-                    ]),
-            );
+            .x_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+                Span::styled("30m", Style::default().fg(Color::DarkGray)),
+                Span::styled("now", Style::default().fg(Color::DarkGray)),
+            ]))
+            .y_axis(Axis::default().bounds([0.0, 100.0]).labels(vec![
+                Span::styled("20°C", Style::default().fg(Color::DarkGray)),
+                Span::styled("100°C", Style::default().fg(Color::DarkGray)),
+            ]));
         frame.render_widget(temp_chart, chunks[3]);
     }
 }
@@ -612,13 +762,17 @@ fn render_chart_metrics(frame: &mut Frame, area: Rect, metrics: &MetricsHistory)
 fn render_no_daemon_message(frame: &mut Frame, chunks: &[Rect]) {
     let msg = Line::from(Span::styled(
         "Daemon not connected",
-        Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::DIM),
     ));
     frame.render_widget(Paragraph::new(msg), chunks[1]);
 
     let hint = Line::from(Span::styled(
         "Run: tmux-claude daemon start",
-        Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::DIM),
     ));
     frame.render_widget(Paragraph::new(hint), chunks[2]);
 }
@@ -634,11 +788,10 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
     let non_claude_start = app
         .session_infos
         .iter()
-        .position(|s| !app.is_skipped(&s.name) && s.claude_status.is_none());
-    let skipped_start = app
-        .session_infos
-        .iter()
-        .position(|s| app.is_skipped(&s.name));
+        .position(|s| !app.is_skipped(s) && s.claude_status.is_none());
+    let skipped_start = app.session_infos.iter().position(|s| app.is_skipped(s));
+
+    let show_server = app.has_multiple_servers();
 
     let mut lines: Vec<Line> = Vec::new();
     lines.push(Line::raw("")); // Spacing after header
@@ -649,7 +802,7 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
 
     while idx < app.session_infos.len() {
         let session_info = &app.session_infos[idx];
-        let is_skipped = app.is_skipped(&session_info.name);
+        let is_skipped = app.is_skipped(session_info);
         let is_claude = session_info.claude_status.is_some();
 
         // Show divider before first non-claude (non-skipped) session
@@ -694,9 +847,9 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
 
         // CPU styling
         let cpu_text = format!("{:.1}%", session_info.total_cpu);
-        let cpu_color = if session_info.total_cpu < 20.0 {
+        let cpu_color = if session_info.total_cpu < SESSION_CPU_WARN {
             Color::Green
-        } else if session_info.total_cpu < 100.0 {
+        } else if session_info.total_cpu < SESSION_CPU_HIGH {
             Color::Yellow
         } else {
             Color::Red
@@ -704,9 +857,9 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
 
         // Memory styling
         let mem_text = format_memory(session_info.total_mem_kb);
-        let mem_color = if session_info.total_mem_kb < 512000 {
+        let mem_color = if session_info.total_mem_kb < SESSION_MEM_WARN_KB {
             Color::Green
-        } else if session_info.total_mem_kb < 2048000 {
+        } else if session_info.total_mem_kb < SESSION_MEM_HIGH_KB {
             Color::Yellow
         } else {
             Color::Red
@@ -734,9 +887,17 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
                 Style::default()
             };
 
+            let mark_span = if app.marked_sessions.contains(&session_info.key()) {
+                Span::styled(
+                    "*",
+                    header_style.fg(Color::Magenta).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                Span::styled(".", header_style)
+            };
             let mut header_spans = vec![
                 prefix_span,
-                Span::styled(".", header_style),
+                mark_span,
                 Span::styled(" ", header_style),
                 Span::styled(
                     session_info.name.clone(),
@@ -750,7 +911,7 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
             ];
 
             // Add todo count indicator if there are todos
-            let todo_count = app.todo_count(&session_info.name);
+            let todo_count = app.todo_count(session_info);
             if todo_count > 0 {
                 header_spans.push(Span::styled(
                     format!(" [{}]", todo_count),
@@ -759,13 +920,39 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
             }
 
             // Add auto-approve badge
-            if app.is_auto_approved(&session_info.name) {
+            if app.is_auto_approved(session_info) {
                 header_spans.push(Span::styled(" [auto]", Style::default().fg(Color::Green)));
             }
 
             // Add muted badge
-            if app.is_muted(&session_info.name) {
-                header_spans.push(Span::styled(" [muted]", Style::default().fg(Color::DarkGray)));
+            if app.is_muted(session_info) {
+                header_spans.push(Span::styled(
+                    " [muted]",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+
+            // Add git badge (branch, dirty count, ahead/behind); worktree branch as fallback
+            if let Some(badge) = git_badge(app, session_info) {
+                header_spans.push(badge);
+            }
+
+            // Add context window fill gauge
+            if let Some(badge) = session_info.usage.as_ref().and_then(context_badge) {
+                header_spans.push(badge);
+            }
+
+            // Add server badge when sessions span multiple tmux servers
+            if show_server && session_info.host.is_none() {
+                header_spans.push(Span::styled(
+                    format!(" @{}", session_info.server.label()),
+                    Style::default().fg(Color::Blue),
+                ));
+            }
+
+            // Add resource alert flag with the offending PID
+            if let Some(ref alert) = session_info.resource_alert {
+                header_spans.push(alert_badge(alert));
             }
 
             lines.push(Line::from(header_spans));
@@ -792,17 +979,23 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
                             format!("   → needs permission: {}", cmd)
                         };
                         lines.push(Line::from(vec![
-                            Span::styled(text, Style::default().fg(Color::Yellow)),
+                            Span::styled(
+                                text,
+                                risk_style(session_info.risk.as_ref().map(|r| r.level)),
+                            ),
                             Span::styled(
                                 ago_text.clone(),
                                 Style::default().add_modifier(Modifier::DIM),
                             ),
                         ]));
                         let desc_text = desc.as_deref().unwrap_or("");
-                        lines.push(Line::from(Span::styled(
-                            format!("     {}", desc_text),
+                        let mut desc_spans = vec![Span::raw("     ")];
+                        desc_spans.extend(risk_badge(session_info.risk.as_ref()));
+                        desc_spans.push(Span::styled(
+                            desc_text.to_string(),
                             Style::default().add_modifier(Modifier::DIM),
-                        )));
+                        ));
+                        lines.push(Line::from(desc_spans));
                     }
                     ClaudeStatus::EditApproval(filename) => {
                         let text = if let Some(key) = session_info.permission_key {
@@ -816,13 +1009,18 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
                             format!("   → edit: {}", filename)
                         };
                         lines.push(Line::from(vec![
-                            Span::styled(text, Style::default().fg(Color::Yellow)),
+                            Span::styled(
+                                text,
+                                risk_style(session_info.risk.as_ref().map(|r| r.level)),
+                            ),
                             Span::styled(
                                 ago_text.clone(),
                                 Style::default().add_modifier(Modifier::DIM),
                             ),
                         ]));
-                        lines.push(Line::raw(""));
+                        let mut risk_spans = vec![Span::raw("     ")];
+                        risk_spans.extend(risk_badge(session_info.risk.as_ref()));
+                        lines.push(Line::from(risk_spans));
                     }
                     ClaudeStatus::PlanReview => {
                         lines.push(Line::from(vec![
@@ -850,6 +1048,32 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
                         ]));
                         lines.push(Line::raw(""));
                     }
+                    ClaudeStatus::RateLimited(_) => {
+                        lines.push(Line::from(vec![
+                            Span::styled(
+                                format!("   → {}", status),
+                                Style::default().fg(Color::Yellow),
+                            ),
+                            Span::styled(
+                                ago_text.clone(),
+                                Style::default().add_modifier(Modifier::DIM),
+                            ),
+                        ]));
+                        lines.push(Line::raw(""));
+                    }
+                    ClaudeStatus::Stalled | ClaudeStatus::Crashed | ClaudeStatus::ApiError(_) => {
+                        lines.push(Line::from(vec![
+                            Span::styled(
+                                format!("   → {}", status),
+                                Style::default().fg(Color::Red),
+                            ),
+                            Span::styled(
+                                ago_text.clone(),
+                                Style::default().add_modifier(Modifier::DIM),
+                            ),
+                        ]));
+                        lines.push(Line::raw(""));
+                    }
                     ClaudeStatus::Waiting => {
                         lines.push(Line::from(vec![
                             Span::styled(
@@ -899,7 +1123,7 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
             ];
 
             // Add todo count indicator if there are todos
-            let todo_count = app.todo_count(&session_info.name);
+            let todo_count = app.todo_count(session_info);
             if todo_count > 0 {
                 header_spans.push(Span::styled(
                     format!(" [{}]", todo_count),
@@ -908,13 +1132,34 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
             }
 
             // Add auto-approve badge
-            if app.is_auto_approved(&session_info.name) {
+            if app.is_auto_approved(session_info) {
                 header_spans.push(Span::styled(" [auto]", Style::default().fg(Color::Green)));
             }
 
             // Add muted badge
-            if app.is_muted(&session_info.name) {
-                header_spans.push(Span::styled(" [muted]", Style::default().fg(Color::DarkGray)));
+            if app.is_muted(session_info) {
+                header_spans.push(Span::styled(
+                    " [muted]",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+
+            // Add git badge (branch, dirty count, ahead/behind); worktree branch as fallback
+            if let Some(badge) = git_badge(app, session_info) {
+                header_spans.push(badge);
+            }
+
+            // Add server badge when sessions span multiple tmux servers
+            if show_server && session_info.host.is_none() {
+                header_spans.push(Span::styled(
+                    format!(" @{}", session_info.server.label()),
+                    Style::default().fg(Color::Blue),
+                ));
+            }
+
+            // Add resource alert flag with the offending PID
+            if let Some(ref alert) = session_info.resource_alert {
+                header_spans.push(alert_badge(alert));
             }
 
             lines.push(Line::from(header_spans));
@@ -927,6 +1172,19 @@ pub fn render_session_list(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(Paragraph::new(lines), area);
 }
 
+/// Compact red flag for a session row, e.g. " [! cpu pid 1234]"
+fn alert_badge(alert: &ResourceAlert) -> Span<'static> {
+    let kind = match alert.kind {
+        AlertKind::Cpu { .. } => "cpu",
+        AlertKind::Rss { .. } => "mem",
+        AlertKind::ProcessCount { .. } => "procs",
+    };
+    Span::styled(
+        format!(" [! {} pid {}]", kind, alert.pid),
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+    )
+}
+
 /// Render the parked sessions view
 pub fn render_parked_view(frame: &mut Frame, app: &mut App, area: Rect) {
     let parked_list = app.parked_list();
@@ -966,9 +1224,7 @@ pub fn render_parked_view(frame: &mut Frame, app: &mut App, area: Rect) {
 
             // Show note on next line(s) if present
             if !note.is_empty() {
-                let note_style = Style::default()
-                    .fg(Color::Cyan)
-                    .add_modifier(Modifier::DIM);
+                let note_style = Style::default().fg(Color::Cyan).add_modifier(Modifier::DIM);
                 for (j, note_line) in note.split('\n').enumerate() {
                     let prefix = if j == 0 { "   → " } else { "     " };
                     lines.push(Line::from(Span::styled(
@@ -994,8 +1250,15 @@ pub fn render_search_view(frame: &mut Frame, app: &mut App, area: Rect) {
     lines.push(Line::raw("")); // Spacing after header
 
     if app.search_results.is_empty() {
+        let message = if app.search_scope == SearchScope::Transcripts
+            && app.search_query.trim().chars().count() < 2
+        {
+            "  Type at least 2 characters to search transcripts"
+        } else {
+            "  No matches"
+        };
         lines.push(Line::from(Span::styled(
-            "  No matches",
+            message,
             Style::default().add_modifier(Modifier::DIM),
         )));
     } else {
@@ -1026,6 +1289,10 @@ pub fn render_search_view(frame: &mut Frame, app: &mut App, area: Rect) {
                         Some(ClaudeStatus::Waiting) => " [waiting]",
                         Some(ClaudeStatus::PlanReview) => " [plan]",
                         Some(ClaudeStatus::QuestionAsked) => " [question]",
+                        Some(ClaudeStatus::Stalled) => " [stalled]",
+                        Some(ClaudeStatus::Crashed) => " [crashed]",
+                        Some(ClaudeStatus::RateLimited(_)) => " [limited]",
+                        Some(ClaudeStatus::ApiError(_)) => " [api error]",
                         Some(ClaudeStatus::Unknown) => " [working]",
                         None => "",
                     };
@@ -1060,9 +1327,8 @@ pub fn render_search_view(frame: &mut Frame, app: &mut App, area: Rect) {
                     // Show note on next line(s) if present
                     if has_note {
                         if let Some(note) = app.parked_sessions.get(name) {
-                            let note_style = Style::default()
-                                .fg(Color::Cyan)
-                                .add_modifier(Modifier::DIM);
+                            let note_style =
+                                Style::default().fg(Color::Cyan).add_modifier(Modifier::DIM);
                             for (j, note_line) in note.split('\n').enumerate() {
                                 let prefix = if j == 0 { "   → " } else { "     " };
                                 lines.push(Line::from(Span::styled(
@@ -1083,6 +1349,56 @@ pub fn render_search_view(frame: &mut Frame, app: &mut App, area: Rect) {
                     ]));
                     lines_remaining -= 1;
                 }
+                SearchResult::Transcript(hit_idx) => {
+                    let hit = &app.transcript_hits[*hit_idx];
+                    let header = app.starts_project_group(*hit_idx);
+                    if lines_remaining < 2 + usize::from(header) {
+                        break;
+                    }
+                    if header {
+                        lines.push(Line::from(Span::styled(
+                            format!(" {}", hit.project),
+                            Style::default()
+                                .fg(Color::Cyan)
+                                .add_modifier(Modifier::BOLD),
+                        )));
+                        lines_remaining -= 1;
+                    }
+                    let when = hit
+                        .last_timestamp
+                        .as_deref()
+                        .and_then(|t| t.parse::<chrono::DateTime<chrono::Utc>>().ok())
+                        .map(|t| format!(" · {}", format_duration_ago(&t)))
+                        .unwrap_or_default();
+                    lines.push(Line::from(vec![
+                        prefix,
+                        Span::styled(". ", style),
+                        Span::styled(hit.session_id.chars().take(8).collect::<String>(), style),
+                        Span::styled(
+                            format!(
+                                " {} match{}{}",
+                                hit.match_count,
+                                if hit.match_count == 1 { "" } else { "es" },
+                                when
+                            ),
+                            Style::default().add_modifier(Modifier::DIM),
+                        ),
+                    ]));
+                    if let Some(snippet) = hit.snippets.first() {
+                        let icon = match snippet.kind {
+                            DocKind::Prompt => "❯",
+                            DocKind::Reply => "●",
+                            DocKind::Command => "⚙",
+                        };
+                        lines.push(Line::from(Span::styled(
+                            format!("   {} {}", icon, snippet.text),
+                            Style::default().add_modifier(Modifier::DIM),
+                        )));
+                    } else {
+                        lines.push(Line::raw(""));
+                    }
+                    lines_remaining -= 2;
+                }
             }
             idx += 1;
         }
@@ -1094,6 +1410,8 @@ pub fn render_search_view(frame: &mut Frame, app: &mut App, area: Rect) {
 /// Render the session detail view
 pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
     let mut lines: Vec<Line> = Vec::new();
+    // Line index of the selected item, used to keep it scrolled into view
+    let mut selected_line: Option<usize> = None;
     lines.push(Line::raw("")); // Spacing after header
 
     let Some(idx) = app.showing_detail else {
@@ -1105,18 +1423,18 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
 
     // --- Session stats ---
     let cpu_text = format!("{:.1}%", session_info.total_cpu);
-    let cpu_color = if session_info.total_cpu < 20.0 {
+    let cpu_color = if session_info.total_cpu < SESSION_CPU_WARN {
         Color::Green
-    } else if session_info.total_cpu < 100.0 {
+    } else if session_info.total_cpu < SESSION_CPU_HIGH {
         Color::Yellow
     } else {
         Color::Red
     };
 
     let mem_text = format_memory(session_info.total_mem_kb);
-    let mem_color = if session_info.total_mem_kb < 512000 {
+    let mem_color = if session_info.total_mem_kb < SESSION_MEM_WARN_KB {
         Color::Green
-    } else if session_info.total_mem_kb < 2048000 {
+    } else if session_info.total_mem_kb < SESSION_MEM_HIGH_KB {
         Color::Yellow
     } else {
         Color::Red
@@ -1142,12 +1460,36 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
             }
             ClaudeStatus::PlanReview => ("plan ready for review".to_string(), Color::Magenta),
             ClaudeStatus::QuestionAsked => ("question asked".to_string(), Color::Magenta),
+            ClaudeStatus::Stalled => (
+                "stalled (no activity; check the pane)".to_string(),
+                Color::Red,
+            ),
+            ClaudeStatus::Crashed => ("crashed (Claude exited mid-turn)".to_string(), Color::Red),
+            ClaudeStatus::RateLimited(_) => (status.to_string(), Color::Yellow),
+            ClaudeStatus::ApiError(_) => (status.to_string(), Color::Red),
             ClaudeStatus::Unknown => ("working".to_string(), Color::White),
         };
         lines.push(Line::from(vec![
             Span::styled("Claude: ", Style::default().add_modifier(Modifier::DIM)),
             Span::styled(status_text, Style::default().fg(status_color)),
         ]));
+        if let Some(ref risk) = session_info.risk {
+            if risk.level > RiskLevel::Low {
+                lines.push(Line::from(vec![
+                    Span::styled("Risk: ", Style::default().add_modifier(Modifier::DIM)),
+                    Span::styled(risk.level.label(), risk_style(Some(risk.level))),
+                ]));
+                for tag in &risk.tags {
+                    lines.push(Line::from(vec![
+                        Span::styled(format!("  {}: ", tag.label), risk_style(Some(tag.level))),
+                        Span::styled(
+                            tag.matched.clone(),
+                            Style::default().add_modifier(Modifier::DIM),
+                        ),
+                    ]));
+                }
+            }
+        }
     } else {
         lines.push(Line::from(Span::styled(
             "Claude: not running",
@@ -1155,24 +1497,248 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         )));
     }
 
+    let mut clamped_diff_scroll = None;
+    // --- Pending edit diff (scrolls on its own so long diffs don't push the rest away) ---
+    if let Some(preview) = app.detail_edit_preview() {
+        let mut header = vec![
+            Span::styled("Edit: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(
+                preview.path.display().to_string(),
+                Style::default().fg(Color::Yellow),
+            ),
+        ];
+        if let Some(ref note) = preview.note {
+            header.push(Span::styled(
+                format!("  ({})", note),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        lines.push(Line::from(header));
+
+        let max_rows = (area.height as usize / 2).max(MIN_DIFF_ROWS);
+        let total = preview.lines.len();
+        let diff_scroll = app.diff_scroll.min(total.saturating_sub(max_rows));
+        clamped_diff_scroll = Some(diff_scroll);
+        if total == 0 {
+            lines.push(Line::from(Span::styled(
+                "  (no changes)",
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+        }
+        for diff_line in preview.lines.iter().skip(diff_scroll).take(max_rows) {
+            let (prefix, style) = match diff_line.kind {
+                DiffKind::Header => ("", Style::default().fg(Color::Cyan)),
+                DiffKind::Context => (" ", Style::default().add_modifier(Modifier::DIM)),
+                DiffKind::Added => ("+", Style::default().fg(Color::Green)),
+                DiffKind::Removed => ("-", Style::default().fg(Color::Red)),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  {}{}", prefix, diff_line.text),
+                style,
+            )));
+        }
+        if total > max_rows {
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  lines {}-{} of {}",
+                    diff_scroll + 1,
+                    (diff_scroll + max_rows).min(total),
+                    total
+                ),
+                Style::default().add_modifier(Modifier::DIM),
+            )));
+        }
+    }
+
+    // --- Remote host / tmux server (only when there's more than one) ---
+    if let Some(ref host) = session_info.host {
+        lines.push(Line::from(vec![
+            Span::styled("Host: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(host.clone(), Style::default().fg(Color::Blue)),
+        ]));
+    } else if app.has_multiple_servers() {
+        lines.push(Line::from(vec![
+            Span::styled("Server: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(
+                session_info.server.label(),
+                Style::default().fg(Color::Blue),
+            ),
+        ]));
+    }
+
+    // --- Git status of the session cwd ---
+    if let Some(ref git) = session_info.git {
+        let mut spans = vec![
+            Span::styled("Git: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(
+                git.branch
+                    .clone()
+                    .unwrap_or_else(|| "(detached)".to_string()),
+                Style::default().fg(Color::Magenta),
+            ),
+        ];
+        if git.dirty > 0 {
+            spans.push(Span::styled(
+                format!("  {} uncommitted", git.dirty),
+                Style::default().fg(Color::Yellow),
+            ));
+        } else {
+            spans.push(Span::styled("  clean", Style::default().fg(Color::Green)));
+        }
+        if let Some(ref upstream) = git.upstream {
+            spans.push(Span::styled(
+                format!("  ↑{} ↓{} {}", git.ahead, git.behind, upstream),
+                Style::default().add_modifier(Modifier::DIM),
+            ));
+        }
+        lines.push(Line::from(spans));
+        if let Some(ref subject) = git.last_commit {
+            lines.push(Line::from(vec![
+                Span::styled(
+                    "Last commit: ",
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+                Span::raw(subject.clone()),
+            ]));
+        }
+    }
+
+    // --- Token usage and cost of the Claude session ---
+    if let Some(ref usage) = session_info.usage {
+        let cost_style = if usage.over_budget() {
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![
+            Span::styled("Usage: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::raw(format!("{} tokens  ", format_tokens(usage.tokens.total()))),
+            Span::styled(format!("${:.2}", usage.cost_usd), cost_style),
+        ];
+        if let Some(budget) = usage.budget_usd {
+            spans.push(Span::styled(
+                format!(" of ${:.2} budget", budget),
+                cost_style.add_modifier(Modifier::DIM),
+            ));
+        }
+        spans.push(Span::styled(
+            format!(
+                "  ({} in, {} out, {} cached)",
+                format_tokens(usage.tokens.input + usage.tokens.cache_write),
+                format_tokens(usage.tokens.output),
+                format_tokens(usage.tokens.cache_read)
+            ),
+            Style::default().add_modifier(Modifier::DIM),
+        ));
+        lines.push(Line::from(spans));
+
+        if let Some(percent) = usage.context_percent() {
+            lines.push(Line::from(vec![
+                Span::styled("Context: ", Style::default().add_modifier(Modifier::DIM)),
+                Span::styled(
+                    format!("{} {:.0}%", context_gauge(percent, 20, '█', '░'), percent),
+                    context_style(percent),
+                ),
+                Span::styled(
+                    format!(
+                        "  {} of {}",
+                        format_tokens(usage.context_tokens),
+                        format_tokens(usage.context_window)
+                    ),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ]));
+        }
+    }
+
+    // --- Worktree the session runs in ---
+    if let Some(worktree) = app.worktrees.get(&session_info.name) {
+        lines.push(Line::from(vec![
+            Span::styled("Worktree: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(
+                format!("⎇ {} ", worktree.branch),
+                Style::default().fg(Color::Magenta),
+            ),
+            Span::styled(
+                worktree.path.display().to_string(),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ]));
+    }
+
+    // --- Resource alert ---
+    if let Some(ref alert) = session_info.resource_alert {
+        lines.push(Line::from(vec![
+            Span::styled("Alert: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::styled(
+                alert.describe(),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+
     // --- Flags ---
     let mut flag_spans: Vec<Span> = Vec::new();
-    if app.is_auto_approved(&session_info.name) {
-        flag_spans.push(Span::styled("[auto-approve] ", Style::default().fg(Color::Green)));
+    if app.is_auto_approved(session_info) {
+        flag_spans.push(Span::styled(
+            "[auto-approve] ",
+            Style::default().fg(Color::Green),
+        ));
     }
-    if app.is_muted(&session_info.name) {
-        flag_spans.push(Span::styled("[muted] ", Style::default().fg(Color::DarkGray)));
+    if app.is_muted(session_info) {
+        flag_spans.push(Span::styled(
+            "[muted] ",
+            Style::default().fg(Color::DarkGray),
+        ));
     }
-    if app.is_skipped(&session_info.name) {
-        flag_spans.push(Span::styled("[skip-cycling] ", Style::default().fg(Color::DarkGray)));
+    if app.is_skipped(session_info) {
+        flag_spans.push(Span::styled(
+            "[skip-cycling] ",
+            Style::default().fg(Color::DarkGray),
+        ));
     }
     if !flag_spans.is_empty() {
-        flag_spans.insert(0, Span::styled("Flags: ", Style::default().add_modifier(Modifier::DIM)));
+        flag_spans.insert(
+            0,
+            Span::styled("Flags: ", Style::default().add_modifier(Modifier::DIM)),
+        );
         lines.push(Line::from(flag_spans));
     }
 
     lines.push(Line::raw("")); // Spacing
 
+    // --- Claude's own plan from TodoWrite (not selectable) ---
+    if !session_info.agent_todos.is_empty() {
+        let (done, total) = todo_progress(&session_info.agent_todos);
+        lines.push(Line::from(vec![
+            Span::styled(
+                "Claude's plan: ",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}/{} done", done, total),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ]));
+        for todo in &session_info.agent_todos {
+            let (marker, style) = match todo.status {
+                TodoStatus::Completed => ("✓", Style::default().add_modifier(Modifier::DIM)),
+                TodoStatus::InProgress => (
+                    "▸",
+                    Style::default()
+                        .fg(Color::Yellow)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                TodoStatus::Pending => ("○", Style::default()),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  {} {}", marker, todo.label()),
+                style,
+            )));
+        }
+        lines.push(Line::raw(""));
+    }
+
     // --- Todos section (only if non-empty) ---
     let todos = app.detail_todos();
     if !todos.is_empty() {
@@ -1184,6 +1750,9 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         for (i, todo) in todos.iter().enumerate() {
             let letter = (b'a' + i as u8) as char;
             let is_selected = i == app.detail_selected;
+            if is_selected {
+                selected_line = Some(lines.len());
+            }
 
             let prefix = if is_selected {
                 Span::styled(">", Style::default().add_modifier(Modifier::BOLD))
@@ -1234,6 +1803,9 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         for (i, port_info) in listening_ports.iter().enumerate() {
             let sel_idx = port_selection_offset + i;
             let is_selected = sel_idx == app.detail_selected;
+            if is_selected {
+                selected_line = Some(lines.len());
+            }
 
             let port_label = format!(":{:<5}  ({})", port_info.port, port_info.process_name);
 
@@ -1269,7 +1841,11 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
                     Span::styled(format!(" {}", port_label), style),
                     Span::styled(
                         format!(" → {}", title),
-                        if is_selected { style } else { Style::default().fg(Color::Cyan) },
+                        if is_selected {
+                            style
+                        } else {
+                            Style::default().fg(Color::Cyan)
+                        },
                     ),
                 ]));
             } else {
@@ -1290,10 +1866,17 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         Style::default().add_modifier(Modifier::BOLD),
     )));
 
+    // Processes are selectable after todos and ports, shown as a collapsible tree
     let processes = &session_info.processes;
+    let process_rows = flatten_process_tree(processes, &app.detail_collapsed);
+    let process_selection_offset = port_selection_offset + listening_ports.len();
     // Prepare CWD prefix for making paths relative (with trailing slash)
     let cwd_prefix = session_info.cwd.as_ref().map(|c| {
-        if c.ends_with('/') { c.clone() } else { format!("{}/", c) }
+        if c.ends_with('/') {
+            c.clone()
+        } else {
+            format!("{}/", c)
+        }
     });
     if processes.is_empty() {
         lines.push(Line::from(Span::styled(
@@ -1302,23 +1885,26 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         )));
     } else {
         let max_cmd_width = (area.width as usize).saturating_sub(40); // Reserve space for PID, CPU, MEM, name
-        for proc in processes {
+        for (i, row) in process_rows.iter().enumerate() {
+            let proc = &processes[row.index];
+            let is_selected = process_selection_offset + i == app.detail_selected;
+            if is_selected {
+                selected_line = Some(lines.len());
+            }
             let pid_text = format!("PID {:>5}", proc.pid);
             let cpu_text = format!("{:>5.1}%", proc.cpu_percent);
-            let proc_cpu_color = if proc.cpu_percent < 10.0 {
+            let proc_cpu_color = if proc.cpu_percent < PROC_CPU_WARN {
                 Color::Green
-            } else if proc.cpu_percent < 50.0 {
+            } else if proc.cpu_percent < PROC_CPU_HIGH {
                 Color::Yellow
             } else {
                 Color::Red
             };
 
             let mem_text = format!("{:>5}", format_memory(proc.memory_kb));
-            let proc_mem_color = if proc.memory_kb < 102400 {
-                // <100M
+            let proc_mem_color = if proc.memory_kb < PROC_MEM_WARN_KB {
                 Color::Green
-            } else if proc.memory_kb < 512000 {
-                // <500M
+            } else if proc.memory_kb < PROC_MEM_HIGH_KB {
                 Color::Yellow
             } else {
                 Color::Red
@@ -1336,11 +1922,36 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
                 Some(prefix) => proc.command.replace(prefix, "./"),
                 None => proc.command.clone(),
             };
-            let cmd_display = truncate_command(&cmd_relative, max_cmd_width.max(10));
+            let indent = "  ".repeat(row.depth);
+            let cmd_display = truncate_command(
+                &cmd_relative,
+                max_cmd_width.saturating_sub(indent.len() + 2).max(10),
+            );
+
+            let node_marker = if !row.has_children {
+                "  "
+            } else if app.detail_collapsed.contains(&proc.pid) {
+                "▸ "
+            } else {
+                "▾ "
+            };
+            let prefix = if is_selected {
+                Span::styled(">", Style::default().add_modifier(Modifier::BOLD))
+            } else {
+                Span::raw(" ")
+            };
+            let pid_style = if is_selected {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default().add_modifier(Modifier::DIM)
+            };
 
             lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(pid_text, Style::default().add_modifier(Modifier::DIM)),
+                Span::raw(" "),
+                prefix,
+                Span::raw(indent),
+                Span::raw(node_marker),
+                Span::styled(pid_text, pid_style),
                 Span::raw("  "),
                 Span::styled(cpu_text, Style::default().fg(proc_cpu_color)),
                 Span::raw("  "),
@@ -1353,6 +1964,10 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         }
     }
 
+    if let Some(diff_scroll) = clamped_diff_scroll {
+        app.diff_scroll = diff_scroll;
+    }
+
     // Apply scroll offset
     let available_height = area.height as usize;
     let total_lines = lines.len();
@@ -1367,6 +1982,15 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
         app.detail_scroll_offset = 0;
     }
 
+    // Keep the selected item visible
+    if let Some(line) = selected_line {
+        if line < app.detail_scroll_offset {
+            app.detail_scroll_offset = line;
+        } else if available_height > 0 && line >= app.detail_scroll_offset + available_height {
+            app.detail_scroll_offset = line + 1 - available_height;
+        }
+    }
+
     let visible_lines: Vec<Line> = lines
         .into_iter()
         .skip(app.detail_scroll_offset)
@@ -1376,6 +2000,309 @@ pub fn render_detail_view(frame: &mut Frame, app: &mut App, area: Rect) {
     frame.render_widget(Paragraph::new(visible_lines), area);
 }
 
+/// Hard-wrap text to `width` columns, keeping blank lines
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut rows = Vec::new();
+    for line in text.replace('\t', "    ").lines() {
+        let chars: Vec<char> = line.chars().collect();
+        if chars.is_empty() {
+            rows.push(String::new());
+        }
+        for chunk in chars.chunks(width) {
+            rows.push(chunk.iter().collect());
+        }
+    }
+    rows
+}
+
+/// Local "HH:MM" of an ISO 8601 transcript timestamp
+fn transcript_time(timestamp: Option<&str>) -> Option<String> {
+    let time = chrono::DateTime::parse_from_rfc3339(timestamp?).ok()?;
+    Some(
+        time.with_timezone(&chrono::Local)
+            .format("%H:%M")
+            .to_string(),
+    )
+}
+
+/// Rows of one transcript item: icon, body (folded unless expanded) and style
+fn transcript_item_rows(item: &TranscriptItem, expanded: bool) -> (Vec<String>, Style) {
+    let line_count = item.text.lines().count();
+    let (icon, style, foldable) = match &item.kind {
+        TranscriptKind::UserPrompt => (
+            "❯ ".to_string(),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+            false,
+        ),
+        TranscriptKind::AssistantText => ("● ".to_string(), Style::default(), false),
+        TranscriptKind::Thinking => {
+            let style = Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::DIM);
+            if !expanded {
+                return (vec![format!("✻ thinking ({} lines)", line_count)], style);
+            }
+            ("✻ ".to_string(), style, false)
+        }
+        TranscriptKind::ToolCall(name) => (
+            format!("⚙ {}: ", name),
+            Style::default().fg(Color::Yellow),
+            true,
+        ),
+        TranscriptKind::ToolResult { is_error: true } => {
+            ("⎿ ".to_string(), Style::default().fg(Color::Red), true)
+        }
+        TranscriptKind::ToolResult { is_error: false } => (
+            "⎿ ".to_string(),
+            Style::default().add_modifier(Modifier::DIM),
+            true,
+        ),
+    };
+    let mut rows: Vec<String> = item.text.lines().map(str::to_string).collect();
+    if foldable && !expanded && rows.len() > TRANSCRIPT_FOLDED_LINES {
+        rows.truncate(TRANSCRIPT_FOLDED_LINES);
+        rows.push(format!("… +{} lines", line_count - TRANSCRIPT_FOLDED_LINES));
+    }
+    match rows.first_mut() {
+        Some(first) => first.insert_str(0, &icon),
+        None => rows.push(icon.trim_end().to_string()),
+    }
+    // Full tool input below the summary, collapsed to a hint until expanded
+    if let Some(input) = &item.tool_input {
+        if expanded {
+            rows.extend(input.lines().map(|line| format!("  {}", line)));
+        } else {
+            rows.push(format!("  ▸ input ({} lines)", input.lines().count()));
+        }
+    }
+    (rows, style)
+}
+
+/// Render the conversation of the detail view's session
+pub fn render_transcript_view(frame: &mut Frame, app: &mut App, area: Rect) {
+    let Some(view) = app.transcript.as_mut() else {
+        return;
+    };
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(area);
+    let turns = view.items.last().map(|item| item.turn).unwrap_or(0);
+    let mut title = vec![
+        Span::styled("Transcript: ", Style::default().add_modifier(Modifier::DIM)),
+        Span::raw(
+            view.path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        ),
+        Span::styled(
+            format!("  {} turns · {} items", turns, view.items.len()),
+            Style::default().add_modifier(Modifier::DIM),
+        ),
+    ];
+    if !view.query.is_empty() {
+        let position = view
+            .matches
+            .iter()
+            .position(|&i| i == view.selected)
+            .map(|p| format!("{}/", p + 1))
+            .unwrap_or_default();
+        title.push(Span::styled(
+            format!(
+                "  '{}' {}{} matches",
+                view.query,
+                position,
+                view.matches.len()
+            ),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    frame.render_widget(Paragraph::new(Line::from(title)), chunks[0]);
+
+    let area = chunks[1];
+    if view.items.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No messages yet",
+                Style::default().add_modifier(Modifier::DIM),
+            )),
+            area,
+        );
+        return;
+    }
+
+    // Two columns for the selection/match marker
+    let width = (area.width as usize).saturating_sub(2);
+    let mut lines: Vec<Line> = Vec::new();
+    let mut selected_rows = (0, 0);
+    for (i, item) in view.items.iter().enumerate() {
+        if item.kind == TranscriptKind::UserPrompt && i > 0 {
+            lines.push(Line::raw(""));
+        }
+        let start = lines.len();
+        let (rows, mut style) = transcript_item_rows(item, view.expanded.contains(&i));
+        let marker = if i == view.selected {
+            style = style.add_modifier(Modifier::BOLD);
+            Span::styled(
+                "▶ ",
+                Style::default()
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD),
+            )
+        } else if view.matches.binary_search(&i).is_ok() {
+            Span::styled("• ", Style::default().fg(Color::Yellow))
+        } else {
+            Span::raw("  ")
+        };
+        let time = (item.kind == TranscriptKind::UserPrompt)
+            .then(|| transcript_time(item.timestamp.as_deref()))
+            .flatten();
+        for (row_idx, row) in rows
+            .iter()
+            .flat_map(|row| wrap_text(row, width))
+            .enumerate()
+        {
+            let mut spans = vec![
+                if row_idx == 0 {
+                    marker.clone()
+                } else {
+                    Span::raw("  ")
+                },
+                Span::styled(row, style),
+            ];
+            if row_idx == 0 {
+                if let Some(ref time) = time {
+                    spans.push(Span::styled(
+                        format!("  {}", time),
+                        Style::default().add_modifier(Modifier::DIM),
+                    ));
+                }
+            }
+            lines.push(Line::from(spans));
+        }
+        if i == view.selected {
+            selected_rows = (start, lines.len());
+        }
+    }
+
+    // Keep the selected item in view (its first line if it doesn't fit)
+    let height = area.height as usize;
+    let (start, end) = selected_rows;
+    if end > view.scroll + height {
+        view.scroll = end.saturating_sub(height).min(start);
+    }
+    if start < view.scroll {
+        view.scroll = start;
+    }
+    view.scroll = view.scroll.min(lines.len().saturating_sub(height));
+
+    let visible: Vec<Line> = lines.into_iter().skip(view.scroll).take(height).collect();
+    frame.render_widget(Paragraph::new(visible), area);
+}
+
+/// Render the past conversations of the detail view's project
+pub fn render_history_view(frame: &mut Frame, app: &mut App, area: Rect) {
+    let running: HashSet<String> = app
+        .session_infos
+        .iter()
+        .filter_map(|s| s.daemon_session_id.clone())
+        .collect();
+    let Some(history) = app.history.as_mut() else {
+        return;
+    };
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(area);
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("History: ", Style::default().add_modifier(Modifier::DIM)),
+            Span::raw(history.cwd.clone()),
+            Span::styled(
+                format!("  {} conversations", history.entries.len()),
+                Style::default().add_modifier(Modifier::DIM),
+            ),
+        ])),
+        chunks[0],
+    );
+
+    // Keep the selection visible
+    let height = chunks[1].height as usize;
+    if history.selected < history.scroll {
+        history.scroll = history.selected;
+    } else if height > 0 && history.selected >= history.scroll + height {
+        history.scroll = history.selected + 1 - height;
+    }
+
+    let lines: Vec<Line> = history
+        .entries
+        .iter()
+        .enumerate()
+        .skip(history.scroll)
+        .take(height)
+        .map(|(i, entry)| {
+            let selected = i == history.selected;
+            let style = if selected {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            let when = entry
+                .last_timestamp
+                .as_deref()
+                .and_then(|t| t.parse::<chrono::DateTime<chrono::Utc>>().ok())
+                .map(|t| format_duration_ago(&t))
+                .unwrap_or_else(|| "-".to_string());
+            let mut spans = vec![
+                Span::styled(
+                    if selected { ">" } else { " " },
+                    Style::default().add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(entry.session_id.chars().take(8).collect::<String>(), style),
+                Span::styled(
+                    format!(
+                        " {:>4} turns {:>5} {:>5}  ",
+                        entry.turns,
+                        when,
+                        format_memory(entry.size / 1024)
+                    ),
+                    Style::default().add_modifier(Modifier::DIM),
+                ),
+            ];
+            if running.contains(&entry.session_id) {
+                spans.push(Span::styled(
+                    "● running ",
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            let prompt = entry
+                .first_prompt
+                .as_deref()
+                .map(|p| p.split_whitespace().collect::<Vec<_>>().join(" "))
+                .unwrap_or_else(|| "(no prompt)".to_string());
+            spans.push(Span::raw(prompt));
+            Line::from(spans)
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[1]);
+}
+
+/// Style of a pending request line: yellow, orange-ish for medium risk, bold red for high
+fn risk_style(level: Option<RiskLevel>) -> Style {
+    match level {
+        Some(RiskLevel::High) => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        Some(RiskLevel::Medium) => Style::default().fg(Color::LightRed),
+        _ => Style::default().fg(Color::Yellow),
+    }
+}
+
+/// "⚠ high: destructive " tag for risky requests (nothing for low risk)
+fn risk_badge(risk: Option<&Risk>) -> Option<Span<'static>> {
+    let risk = risk.filter(|r| r.level > RiskLevel::Low)?;
+    Some(Span::styled(
+        format!("⚠ {}: {} ", risk.level.label(), risk.summary()),
+        risk_style(Some(risk.level)),
+    ))
+}
+
 /// Render a multiline input modal overlay (used for park notes and todos)
 fn render_input_modal(
     frame: &mut Frame,
@@ -1385,9 +2312,11 @@ fn render_input_modal(
     submit_label: &str,
     border_color: Color,
 ) {
-    let session_name = app
-        .detail_session_name()
-        .unwrap_or_else(|| "session".to_string());
+    // Session-specific modals name the session in the title
+    let block_title = match app.detail_session_name() {
+        Some(name) => format!(" {}: {} ", title, name),
+        None => format!(" {} ", title),
+    };
 
     // Split input into lines for multiline display
     let input_lines: Vec<&str> = app.input_buffer.split('\n').collect();
@@ -1410,7 +2339,7 @@ fn render_input_modal(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(border_color))
-        .title(format!(" {}: {} ", title, session_name));
+        .title(block_title);
 
     let inner = block.inner(modal_area);
     frame.render_widget(block, modal_area);
@@ -1433,10 +2362,7 @@ fn render_input_modal(
         if is_last {
             lines.push(Line::from(vec![
                 Span::raw(visible),
-                Span::styled(
-                    "█",
-                    Style::default().add_modifier(Modifier::SLOW_BLINK),
-                ),
+                Span::styled("█", Style::default().add_modifier(Modifier::SLOW_BLINK)),
             ]));
         } else {
             lines.push(Line::from(Span::raw(visible)));
@@ -1462,6 +2388,221 @@ fn render_input_modal(
     frame.render_widget(Paragraph::new(lines), inner);
 }
 
+/// Compact git badge for a session row: daemon git status, else the recorded worktree branch
+fn git_badge(app: &App, session_info: &SessionInfo) -> Option<Span<'static>> {
+    if let Some(ref git) = session_info.git {
+        let color = if git.dirty > 0 {
+            Color::Yellow
+        } else {
+            Color::Magenta
+        };
+        return Some(Span::styled(
+            format!(" ⎇ {}", git.badge()),
+            Style::default().fg(color),
+        ));
+    }
+    app.worktrees.get(&session_info.name).map(|worktree| {
+        Span::styled(
+            format!(" ⎇ {}", worktree.branch),
+            Style::default().fg(Color::Magenta),
+        )
+    })
+}
+
+/// Context fill at which the gauge turns red
+const CONTEXT_FULL_PERCENT: f64 = 85.0;
+
+/// Gauge color: dim while there's room, yellow past 60%, red when nearly full
+fn context_style(percent: f64) -> Style {
+    if percent >= CONTEXT_FULL_PERCENT {
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else if percent >= 60.0 {
+        Style::default().fg(Color::Yellow)
+    } else {
+        Style::default().add_modifier(Modifier::DIM)
+    }
+}
+
+/// `width` cells, filled in proportion to `percent`
+fn context_gauge(percent: f64, width: usize, filled: char, empty: char) -> String {
+    let cells = ((percent / 100.0) * width as f64).round() as usize;
+    (0..width)
+        .map(|i| if i < cells { filled } else { empty })
+        .collect()
+}
+
+/// " ▰▰▰▱▱ 62%" context window fill for the session list
+fn context_badge(usage: &SessionUsage) -> Option<Span<'static>> {
+    let percent = usage.context_percent()?;
+    Some(Span::styled(
+        format!(" {} {:.0}%", context_gauge(percent, 5, '▰', '▱'), percent),
+        context_style(percent),
+    ))
+}
+
+/// Render a yes/no confirmation modal
+fn render_confirm_modal(frame: &mut Frame, area: Rect, title: &str, message: &str) {
+    let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+    let lines = vec![
+        Line::from(Span::styled(
+            message.to_string(),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::raw(""),
+        Line::from(vec![
+            key("[Y/Enter]"),
+            Span::raw(" confirm  "),
+            key("[any]"),
+            Span::raw(" cancel"),
+        ]),
+    ];
+
+    let modal_width = (area.width.saturating_sub(4)).clamp(40, 60);
+    let inner_width = modal_width.saturating_sub(2).max(1) as usize;
+    let message_rows = message.chars().count().div_ceil(inner_width).max(1) as u16;
+    let modal_height = (message_rows + 4).min(area.height.saturating_sub(2));
+    let x = area.x + (area.width.saturating_sub(modal_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(modal_height)) / 2;
+    let modal_area = Rect::new(x, y, modal_width, modal_height);
+
+    frame.render_widget(Clear, modal_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Red))
+        .title(title.to_string());
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+/// Render the bulk approval confirmation: one line per session and its request
+fn render_bulk_approve_modal(frame: &mut Frame, app: &App, area: Rect) {
+    let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+    let rows = app.bulk_approval_rows();
+    let approvable = rows.iter().filter(|(_, _, held)| held.is_none()).count();
+
+    let mut lines: Vec<Line> = rows
+        .iter()
+        .map(|(name, request, held)| {
+            let mut spans = vec![Span::styled(
+                format!("{} ", name),
+                Style::default().add_modifier(Modifier::BOLD),
+            )];
+            match held {
+                Some(reason) => spans.push(Span::styled(
+                    format!("held back ({}): {}", reason, request),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::DIM),
+                )),
+                None => spans.push(Span::styled(
+                    request.clone(),
+                    Style::default().fg(Color::Yellow),
+                )),
+            }
+            Line::from(spans)
+        })
+        .collect();
+    lines.push(Line::raw(""));
+    lines.push(Line::from(vec![
+        key("[Y/Enter]"),
+        Span::raw(format!(" approve {}  ", approvable)),
+        key("[any]"),
+        Span::raw(" cancel"),
+    ]));
+
+    let modal_width = area.width.saturating_sub(4).clamp(40, 90);
+    let modal_height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = area.x + (area.width.saturating_sub(modal_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(modal_height)) / 2;
+    let modal_area = Rect::new(x, y, modal_width, modal_height);
+
+    frame.render_widget(Clear, modal_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .title(format!(" Approve {} requests ", approvable));
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Render the process action modal (or the signal confirmation prompt)
+fn render_process_action_modal(frame: &mut Frame, app: &App, area: Rect) {
+    let Some(ref proc) = app.process_target else {
+        return;
+    };
+
+    let key = |k: &'static str| Span::styled(k, Style::default().add_modifier(Modifier::BOLD));
+    let (border_color, lines) = match app.pending_signal {
+        Some(signal) if app.input_mode == InputMode::ConfirmSignal => (
+            Color::Red,
+            vec![
+                Line::from(Span::styled(
+                    format!(
+                        "Send {} to PID {} ({})?",
+                        signal_name(signal),
+                        proc.pid,
+                        proc.name
+                    ),
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                )),
+                Line::raw(""),
+                Line::from(vec![
+                    key("[Y/Enter]"),
+                    Span::raw(" confirm  "),
+                    key("[any]"),
+                    Span::raw(" cancel"),
+                ]),
+            ],
+        ),
+        _ => (
+            Color::Yellow,
+            vec![
+                Line::from(Span::styled(
+                    truncate_command(&proc.command, area.width.saturating_sub(8) as usize),
+                    Style::default().add_modifier(Modifier::DIM),
+                )),
+                Line::raw(""),
+                Line::from(vec![
+                    key("[I]"),
+                    Span::raw(" SIGINT   "),
+                    key("[T]"),
+                    Span::raw(" SIGTERM   "),
+                    key("[K]"),
+                    Span::raw(" SIGKILL"),
+                ]),
+                Line::from(vec![
+                    key("[N]"),
+                    Span::raw(format!(" renice +{}   ", RENICE_NICENESS)),
+                    key("[c]"),
+                    Span::raw(" copy PID   "),
+                    key("[C]"),
+                    Span::raw(" copy command"),
+                ]),
+                Line::raw(""),
+                Line::from(Span::styled(
+                    "[Esc] cancel",
+                    Style::default().add_modifier(Modifier::DIM),
+                )),
+            ],
+        ),
+    };
+
+    let modal_width = (area.width.saturating_sub(4)).clamp(40, 60);
+    let modal_height = (lines.len() as u16 + 2).min(area.height.saturating_sub(2));
+    let x = area.x + (area.width.saturating_sub(modal_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(modal_height)) / 2;
+    let modal_area = Rect::new(x, y, modal_width, modal_height);
+
+    frame.render_widget(Clear, modal_area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(border_color))
+        .title(format!(" PID {}: {} ", proc.pid, proc.name));
+    let inner = block.inner(modal_area);
+    frame.render_widget(block, modal_area);
+    frame.render_widget(Paragraph::new(lines), inner);
+}
+
 /// Render the parked session detail view
 pub fn render_parked_detail_view(frame: &mut Frame, app: &App, area: Rect) {
     let mut lines: Vec<Line> = Vec::new();